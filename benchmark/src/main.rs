@@ -26,14 +26,39 @@ impl FromStr for Engine {
     }
 }
 
+#[derive(Clone, Debug)]
+enum Program {
+    Fibonacci,
+    Arithmetic,
+}
+
+impl FromStr for Program {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            fib if fib.to_lowercase() == "fibonacci" => Ok(Program::Fibonacci),
+            arith if arith.to_lowercase() == "arithmetic" => Ok(Program::Arithmetic),
+            str => Err(format!("Couldn't convert \"{str}\" to Program enum")),
+        }
+    }
+}
+
 #[derive(Parser)]
 #[clap(author, version, about)]
 struct Arguments {
     #[clap(short = 'H', long, help = "use \"vm\" or \"eval\"")]
     engine: Engine,
+    #[clap(
+        short,
+        long,
+        default_value = "fibonacci",
+        help = "use \"fibonacci\" or \"arithmetic\""
+    )]
+    program: Program,
 }
 
-const INPUT: &str = "
+const FIBONACCI_INPUT: &str = "
 let fibonacci = fn(x) {
     if (x == 0) {
         0
@@ -49,10 +74,30 @@ let fibonacci = fn(x) {
 fibonacci(30);
 ";
 
+const ARITHMETIC_INPUT: &str = "
+let i = 0;
+let sum = 0;
+
+while (i < 1000000) {
+    sum = sum + i * 3 % 7 - i / 5;
+    if (sum > 1000 == true) {
+        sum = sum - 1000;
+    }
+    i = i + 1;
+}
+
+sum;
+";
+
 fn main() {
     let arguments = Arguments::parse();
 
-    let lexer = Lexer::new(INPUT.to_string());
+    let input = match arguments.program {
+        Program::Fibonacci => FIBONACCI_INPUT,
+        Program::Arithmetic => ARITHMETIC_INPUT,
+    };
+
+    let lexer = Lexer::new(input.to_string());
     let mut parser = monke_lang::parser::parser::Parser::new(lexer);
 
     let program = parser.parse_program().unwrap();
@@ -45,12 +45,12 @@ fn main() -> Result<()> {
                 },
                 Err(err) => {
                     println!("{MONKEY_FACE}");
-                    println!("{err}");
+                    println!("{}", err.render(&buffer));
                 }
             },
             Err(err) => {
                 println!("{MONKEY_FACE}");
-                println!("{err}");
+                println!("{}", err.render(&buffer));
             }
         };
 
@@ -1,6 +1,8 @@
+use std::{cmp::Ordering, io::Write, iter};
+
 use crate::{
     result::MonkeyResult,
-    types::{Array, BuiltinFunction, Integer, Null, Object},
+    types::{Array, Boolean, BuiltinFunction, Float, HashTable, Integer, Null, Object, Str},
 };
 
 const LEN_BUILTIN: &str = "len";
@@ -9,16 +11,70 @@ const LAST_BUILTIN: &str = "last";
 const REST_BUILTIN: &str = "rest";
 const PUSH_BUILTIN: &str = "push";
 const PUTS_BUILTIN: &str = "puts";
+const KEYS_BUILTIN: &str = "keys";
+const VALUES_BUILTIN: &str = "values";
+const DELETE_BUILTIN: &str = "delete";
+const TYPE_BUILTIN: &str = "type";
+const STR_BUILTIN: &str = "str";
+const INT_BUILTIN: &str = "int";
+const MAP_BUILTIN: &str = "map";
+const FILTER_BUILTIN: &str = "filter";
+const REDUCE_BUILTIN: &str = "reduce";
+const SORT_BUILTIN: &str = "sort";
+const CHARS_BUILTIN: &str = "chars";
+const SPLIT_BUILTIN: &str = "split";
+const JOIN_BUILTIN: &str = "join";
+const TRIM_BUILTIN: &str = "trim";
+const RANGE_BUILTIN: &str = "range";
+const MIN_BUILTIN: &str = "min";
+const MAX_BUILTIN: &str = "max";
+const ABS_BUILTIN: &str = "abs";
+const ASSERT_BUILTIN: &str = "assert";
+const ASSERT_EQ_BUILTIN: &str = "assert_eq";
+const CONTAINS_BUILTIN: &str = "contains";
+const INDEX_OF_BUILTIN: &str = "index_of";
+const SLICE_BUILTIN: &str = "slice";
 
-pub const BUILTINS: [&str; 6] = [
+pub const BUILTINS: [&str; 29] = [
     LEN_BUILTIN,
     PUTS_BUILTIN,
     FIRST_BUILTIN,
     LAST_BUILTIN,
     REST_BUILTIN,
     PUSH_BUILTIN,
+    KEYS_BUILTIN,
+    VALUES_BUILTIN,
+    DELETE_BUILTIN,
+    TYPE_BUILTIN,
+    STR_BUILTIN,
+    INT_BUILTIN,
+    MAP_BUILTIN,
+    FILTER_BUILTIN,
+    REDUCE_BUILTIN,
+    SORT_BUILTIN,
+    CHARS_BUILTIN,
+    SPLIT_BUILTIN,
+    JOIN_BUILTIN,
+    TRIM_BUILTIN,
+    RANGE_BUILTIN,
+    MIN_BUILTIN,
+    MAX_BUILTIN,
+    ABS_BUILTIN,
+    ASSERT_BUILTIN,
+    ASSERT_EQ_BUILTIN,
+    CONTAINS_BUILTIN,
+    INDEX_OF_BUILTIN,
+    SLICE_BUILTIN,
 ];
 
+pub const MAX_BUILTIN_CALL_DEPTH: usize = 64;
+const MAX_RANGE_LENGTH: i128 = 10_000_000;
+
+pub trait BuiltinContext {
+    fn output(&mut self) -> &mut dyn Write;
+    fn call_function(&mut self, func: Object, args: Vec<Object>) -> MonkeyResult<Object>;
+}
+
 pub fn get_builtin_function(fn_name: &str) -> Option<Object> {
     match fn_name {
         LEN_BUILTIN => Some(Object::Builtin(BuiltinFunction(len_builtin))),
@@ -27,11 +83,34 @@ pub fn get_builtin_function(fn_name: &str) -> Option<Object> {
         REST_BUILTIN => Some(Object::Builtin(BuiltinFunction(rest_builtin))),
         PUSH_BUILTIN => Some(Object::Builtin(BuiltinFunction(push_builtin))),
         PUTS_BUILTIN => Some(Object::Builtin(BuiltinFunction(puts_builtin))),
+        KEYS_BUILTIN => Some(Object::Builtin(BuiltinFunction(keys_builtin))),
+        VALUES_BUILTIN => Some(Object::Builtin(BuiltinFunction(values_builtin))),
+        DELETE_BUILTIN => Some(Object::Builtin(BuiltinFunction(delete_builtin))),
+        TYPE_BUILTIN => Some(Object::Builtin(BuiltinFunction(type_builtin))),
+        STR_BUILTIN => Some(Object::Builtin(BuiltinFunction(str_builtin))),
+        INT_BUILTIN => Some(Object::Builtin(BuiltinFunction(int_builtin))),
+        MAP_BUILTIN => Some(Object::Builtin(BuiltinFunction(map_builtin))),
+        FILTER_BUILTIN => Some(Object::Builtin(BuiltinFunction(filter_builtin))),
+        REDUCE_BUILTIN => Some(Object::Builtin(BuiltinFunction(reduce_builtin))),
+        SORT_BUILTIN => Some(Object::Builtin(BuiltinFunction(sort_builtin))),
+        CHARS_BUILTIN => Some(Object::Builtin(BuiltinFunction(chars_builtin))),
+        SPLIT_BUILTIN => Some(Object::Builtin(BuiltinFunction(split_builtin))),
+        JOIN_BUILTIN => Some(Object::Builtin(BuiltinFunction(join_builtin))),
+        TRIM_BUILTIN => Some(Object::Builtin(BuiltinFunction(trim_builtin))),
+        RANGE_BUILTIN => Some(Object::Builtin(BuiltinFunction(range_builtin))),
+        MIN_BUILTIN => Some(Object::Builtin(BuiltinFunction(min_builtin))),
+        MAX_BUILTIN => Some(Object::Builtin(BuiltinFunction(max_builtin))),
+        ABS_BUILTIN => Some(Object::Builtin(BuiltinFunction(abs_builtin))),
+        ASSERT_BUILTIN => Some(Object::Builtin(BuiltinFunction(assert_builtin))),
+        ASSERT_EQ_BUILTIN => Some(Object::Builtin(BuiltinFunction(assert_eq_builtin))),
+        CONTAINS_BUILTIN => Some(Object::Builtin(BuiltinFunction(contains_builtin))),
+        INDEX_OF_BUILTIN => Some(Object::Builtin(BuiltinFunction(index_of_builtin))),
+        SLICE_BUILTIN => Some(Object::Builtin(BuiltinFunction(slice_builtin))),
         _ => None,
     }
 }
 
-fn len_builtin(args: Vec<Object>) -> MonkeyResult<Object> {
+fn len_builtin(args: Vec<Object>, _ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
     if args.len() != 1 {
         return Err(format!(
             "wrong number of arguments for len function, 1 argument expected, but got {}",
@@ -41,18 +120,18 @@ fn len_builtin(args: Vec<Object>) -> MonkeyResult<Object> {
 
     match args.first().unwrap() {
         Object::String(string) => Ok(Object::Integer(Integer {
-            value: string.value.len() as i64,
+            value: string.value.chars().count() as i64,
         })),
         Object::Array(array) => Ok(Object::Integer(Integer {
             value: array.elements.len() as i64,
         })),
         actual => Err(format!(
-            "argument to len function is not supported, String expected, but got \"{actual}\""
+            "argument to len function is not supported, String or Array expected, but got \"{actual}\""
         )),
     }
 }
 
-fn first_builtin(args: Vec<Object>) -> MonkeyResult<Object> {
+fn first_builtin(args: Vec<Object>, _ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
     if args.len() != 1 {
         return Err(format!(
             "wrong number of arguments for first function, 1 argument expected, but got {}",
@@ -65,13 +144,19 @@ fn first_builtin(args: Vec<Object>) -> MonkeyResult<Object> {
             0 => Ok(Object::Null(Null {})),
             _ => Ok(array.elements.get(0).cloned().unwrap()),
         },
+        Object::String(string) => match string.value.chars().next() {
+            Some(ch) => Ok(Object::String(Str {
+                value: ch.to_string(),
+            })),
+            None => Ok(Object::Null(Null {})),
+        },
         actual => Err(format!(
-            "argument to first function is not supported, Array expected, but got \"{actual}\""
+            "argument to first function is not supported, String or Array expected, but got \"{actual}\""
         )),
     }
 }
 
-fn last_builtin(args: Vec<Object>) -> MonkeyResult<Object> {
+fn last_builtin(args: Vec<Object>, _ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
     if args.len() != 1 {
         return Err(format!(
             "wrong number of arguments for last function, 1 argument expected, but got {}",
@@ -88,13 +173,19 @@ fn last_builtin(args: Vec<Object>) -> MonkeyResult<Object> {
                 .cloned()
                 .unwrap()),
         },
+        Object::String(string) => match string.value.chars().next_back() {
+            Some(ch) => Ok(Object::String(Str {
+                value: ch.to_string(),
+            })),
+            None => Ok(Object::Null(Null {})),
+        },
         actual => Err(format!(
-            "argument to last function is not supported, Array expected, but got \"{actual}\""
+            "argument to last function is not supported, String or Array expected, but got \"{actual}\""
         )),
     }
 }
 
-fn rest_builtin(args: Vec<Object>) -> MonkeyResult<Object> {
+fn rest_builtin(args: Vec<Object>, _ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
     if args.len() != 1 {
         return Err(format!(
             "wrong number of arguments for rest function, 1 argument expected, but got {}",
@@ -109,16 +200,22 @@ fn rest_builtin(args: Vec<Object>) -> MonkeyResult<Object> {
                 elements: array.elements[1..].to_vec(),
             })),
         },
+        Object::String(string) => match string.value.chars().next() {
+            Some(ch) => Ok(Object::String(Str {
+                value: string.value[ch.len_utf8()..].to_string(),
+            })),
+            None => Ok(Object::Null(Null {})),
+        },
         actual => Err(format!(
-            "argument to rest function is not supported, Array expected, but got \"{actual}\""
+            "argument to rest function is not supported, String or Array expected, but got \"{actual}\""
         )),
     }
 }
 
-fn push_builtin(args: Vec<Object>) -> MonkeyResult<Object> {
+fn push_builtin(args: Vec<Object>, _ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
     if args.len() != 2 {
         return Err(format!(
-            "wrong number of arguments for push function, 2 argument expected, but got {}",
+            "wrong number of arguments for push function, 2 arguments expected, but got {}",
             args.len()
         ));
     }
@@ -138,10 +235,600 @@ fn push_builtin(args: Vec<Object>) -> MonkeyResult<Object> {
     }
 }
 
-fn puts_builtin(args: Vec<Object>) -> MonkeyResult<Object> {
+fn puts_builtin(args: Vec<Object>, ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
     for arg in args {
-        println!("{arg}");
+        writeln!(ctx.output(), "{arg}").map_err(|err| format!("unable to write output: {err}"))?;
     }
 
     Ok(Object::Null(Null {}))
 }
+
+fn keys_builtin(args: Vec<Object>, _ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
+    if args.len() != 1 {
+        return Err(format!(
+            "wrong number of arguments for keys function, 1 argument expected, but got {}",
+            args.len()
+        ));
+    }
+
+    match args.first().unwrap() {
+        Object::HashTable(hash) => Ok(Object::Array(Array {
+            elements: hash.pairs.keys().cloned().collect(),
+        })),
+        actual => Err(format!(
+            "argument to keys function is not supported, HashTable expected, but got \"{actual}\""
+        )),
+    }
+}
+
+fn values_builtin(args: Vec<Object>, _ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
+    if args.len() != 1 {
+        return Err(format!(
+            "wrong number of arguments for values function, 1 argument expected, but got {}",
+            args.len()
+        ));
+    }
+
+    match args.first().unwrap() {
+        Object::HashTable(hash) => Ok(Object::Array(Array {
+            elements: hash.pairs.values().cloned().collect(),
+        })),
+        actual => Err(format!(
+            "argument to values function is not supported, HashTable expected, but got \"{actual}\""
+        )),
+    }
+}
+
+fn delete_builtin(args: Vec<Object>, _ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
+    if args.len() != 2 {
+        return Err(format!(
+            "wrong number of arguments for delete function, 2 arguments expected, but got {}",
+            args.len()
+        ));
+    }
+
+    match args.first().unwrap() {
+        Object::HashTable(hash) => {
+            let key = args.get(1).unwrap();
+            key.validate_hash_key()?;
+
+            Ok(Object::HashTable(HashTable {
+                pairs: hash
+                    .pairs
+                    .iter()
+                    .filter(|(k, _)| *k != key)
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect(),
+            }))
+        }
+        actual => Err(format!(
+            "argument to delete function is not supported, HashTable expected, but got \"{actual}\""
+        )),
+    }
+}
+
+fn type_builtin(args: Vec<Object>, _ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
+    if args.len() != 1 {
+        return Err(format!(
+            "wrong number of arguments for type function, 1 argument expected, but got {}",
+            args.len()
+        ));
+    }
+
+    Ok(Object::String(Str {
+        value: String::from(args.first().unwrap().type_name()),
+    }))
+}
+
+fn str_builtin(args: Vec<Object>, _ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
+    if args.len() != 1 {
+        return Err(format!(
+            "wrong number of arguments for str function, 1 argument expected, but got {}",
+            args.len()
+        ));
+    }
+
+    Ok(Object::String(Str {
+        value: args.first().unwrap().to_string(),
+    }))
+}
+
+fn int_builtin(args: Vec<Object>, _ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
+    if args.len() != 1 {
+        return Err(format!(
+            "wrong number of arguments for int function, 1 argument expected, but got {}",
+            args.len()
+        ));
+    }
+
+    match args.first().unwrap() {
+        Object::String(string) => match string.value.parse::<i64>() {
+            Ok(value) => Ok(Object::Integer(Integer { value })),
+            Err(_) => Err(format!(
+                "argument to int function is not a valid integer, but got \"{}\"",
+                string.value
+            )),
+        },
+        actual => Err(format!(
+            "argument to int function is not supported, String expected, but got \"{actual}\""
+        )),
+    }
+}
+
+fn map_builtin(args: Vec<Object>, ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
+    if args.len() != 2 {
+        return Err(format!(
+            "wrong number of arguments for map function, 2 arguments expected, but got {}",
+            args.len()
+        ));
+    }
+
+    let func = callable_argument("map", args.get(1).unwrap())?;
+
+    match args.first().unwrap() {
+        Object::Array(array) => Ok(Object::Array(Array {
+            elements: array
+                .elements
+                .iter()
+                .map(|el| ctx.call_function(func.clone(), vec![el.clone()]))
+                .collect::<MonkeyResult<_>>()?,
+        })),
+        actual => Err(format!(
+            "argument to map function is not supported, Array expected, but got \"{actual}\""
+        )),
+    }
+}
+
+fn filter_builtin(args: Vec<Object>, ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
+    if args.len() != 2 {
+        return Err(format!(
+            "wrong number of arguments for filter function, 2 arguments expected, but got {}",
+            args.len()
+        ));
+    }
+
+    let func = callable_argument("filter", args.get(1).unwrap())?;
+
+    match args.first().unwrap() {
+        Object::Array(array) => {
+            let mut elements = vec![];
+
+            for el in &array.elements {
+                if ctx
+                    .call_function(func.clone(), vec![el.clone()])?
+                    .is_truthy()
+                {
+                    elements.push(el.clone());
+                }
+            }
+
+            Ok(Object::Array(Array { elements }))
+        }
+        actual => Err(format!(
+            "argument to filter function is not supported, Array expected, but got \"{actual}\""
+        )),
+    }
+}
+
+fn reduce_builtin(args: Vec<Object>, ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
+    if args.len() != 3 {
+        return Err(format!(
+            "wrong number of arguments for reduce function, 3 arguments expected, but got {}",
+            args.len()
+        ));
+    }
+
+    let func = callable_argument("reduce", args.get(2).unwrap())?;
+
+    match args.first().unwrap() {
+        Object::Array(array) => array
+            .elements
+            .iter()
+            .try_fold(args.get(1).cloned().unwrap(), |acc, el| {
+                ctx.call_function(func.clone(), vec![acc, el.clone()])
+            }),
+        actual => Err(format!(
+            "argument to reduce function is not supported, Array expected, but got \"{actual}\""
+        )),
+    }
+}
+
+fn sort_builtin(args: Vec<Object>, _ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
+    if args.len() != 1 {
+        return Err(format!(
+            "wrong number of arguments for sort function, 1 argument expected, but got {}",
+            args.len()
+        ));
+    }
+
+    match args.first().unwrap() {
+        Object::Array(array) => {
+            if let Some(first) = array.elements.first() {
+                for el in array.elements.iter().skip(1) {
+                    if first.compare(el).is_none() {
+                        return Err(format!(
+                            "argument to sort function is not supported, couldn't compare \"{first}\" and \"{el}\""
+                        ));
+                    }
+                }
+            }
+
+            let mut elements = array.elements.clone();
+            elements.sort_by(|a, b| a.compare(b).unwrap_or(Ordering::Equal));
+
+            Ok(Object::Array(Array { elements }))
+        }
+        actual => Err(format!(
+            "argument to sort function is not supported, Array expected, but got \"{actual}\""
+        )),
+    }
+}
+
+fn chars_builtin(args: Vec<Object>, _ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
+    if args.len() != 1 {
+        return Err(format!(
+            "wrong number of arguments for chars function, 1 argument expected, but got {}",
+            args.len()
+        ));
+    }
+
+    match args.first().unwrap() {
+        Object::String(string) => Ok(Object::Array(Array {
+            elements: string.chars(),
+        })),
+        actual => Err(format!(
+            "argument to chars function is not supported, String expected, but got \"{actual}\""
+        )),
+    }
+}
+
+fn split_builtin(args: Vec<Object>, _ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
+    if args.len() != 2 {
+        return Err(format!(
+            "wrong number of arguments for split function, 2 arguments expected, but got {}",
+            args.len()
+        ));
+    }
+
+    match (args.first().unwrap(), args.get(1).unwrap()) {
+        (Object::String(string), Object::String(sep)) if sep.value.is_empty() => {
+            Ok(Object::Array(Array {
+                elements: string.chars(),
+            }))
+        }
+        (Object::String(string), Object::String(sep)) => Ok(Object::Array(Array {
+            elements: string
+                .value
+                .split(sep.value.as_str())
+                .map(|part| {
+                    Object::String(Str {
+                        value: String::from(part),
+                    })
+                })
+                .collect(),
+        })),
+        (actual_string, actual_sep) => Err(format!(
+            "arguments to split function are not supported, String and String expected, but got \"{actual_string}\" and \"{actual_sep}\""
+        )),
+    }
+}
+
+fn join_builtin(args: Vec<Object>, _ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
+    if args.len() != 2 {
+        return Err(format!(
+            "wrong number of arguments for join function, 2 arguments expected, but got {}",
+            args.len()
+        ));
+    }
+
+    match (args.first().unwrap(), args.get(1).unwrap()) {
+        (Object::Array(array), Object::String(sep)) => {
+            let parts = array
+                .elements
+                .iter()
+                .map(|el| match el {
+                    Object::String(string) => Ok(string.value.as_str()),
+                    actual => Err(format!(
+                        "argument to join function is not supported, String elements expected, but got \"{actual}\""
+                    )),
+                })
+                .collect::<MonkeyResult<Vec<_>>>()?;
+
+            Ok(Object::String(Str {
+                value: parts.join(&sep.value),
+            }))
+        }
+        (actual_array, actual_sep) => Err(format!(
+            "arguments to join function are not supported, Array and String expected, but got \"{actual_array}\" and \"{actual_sep}\""
+        )),
+    }
+}
+
+fn trim_builtin(args: Vec<Object>, _ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
+    if args.len() != 1 {
+        return Err(format!(
+            "wrong number of arguments for trim function, 1 argument expected, but got {}",
+            args.len()
+        ));
+    }
+
+    match args.first().unwrap() {
+        Object::String(string) => Ok(Object::String(Str {
+            value: String::from(string.value.trim()),
+        })),
+        actual => Err(format!(
+            "argument to trim function is not supported, String expected, but got \"{actual}\""
+        )),
+    }
+}
+
+fn callable_argument(fn_name: &str, arg: &Object) -> MonkeyResult<Object> {
+    match arg {
+        Object::Function(_) | Object::Closure(_) | Object::Builtin(_) => Ok(arg.clone()),
+        actual => Err(format!(
+            "argument to {fn_name} function is not supported, Function expected, but got \"{actual}\""
+        )),
+    }
+}
+
+fn range_builtin(args: Vec<Object>, _ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
+    let bounds = args
+        .iter()
+        .map(|arg| match arg {
+            Object::Integer(int) => Ok(int.value),
+            actual => Err(format!(
+                "argument to range function is not supported, Integer expected, but got \"{actual}\""
+            )),
+        })
+        .collect::<MonkeyResult<Vec<_>>>()?;
+
+    let (start, end, step) = match bounds.as_slice() {
+        [end] => (0, *end, 1),
+        [start, end] => (*start, *end, 1),
+        [start, end, step] => (*start, *end, *step),
+        _ => {
+            return Err(format!(
+            "wrong number of arguments for range function, 1 to 3 arguments expected, but got {}",
+            args.len()
+        ))
+        }
+    };
+
+    if step == 0 {
+        return Err(String::from(
+            "argument to range function is not supported, step can't be zero",
+        ));
+    }
+
+    let distance = i128::from(end) - i128::from(start);
+    let step_i128 = i128::from(step);
+    let length = match distance.signum() == step_i128.signum() {
+        true => distance / step_i128 + i128::from(distance % step_i128 != 0),
+        false => 0,
+    };
+
+    if length > MAX_RANGE_LENGTH {
+        return Err(format!(
+            "argument to range function is not supported, range is longer than {MAX_RANGE_LENGTH} elements"
+        ));
+    }
+
+    Ok(Object::Array(Array {
+        elements: iter::successors(Some(start), |value| value.checked_add(step))
+            .take_while(|&value| match step > 0 {
+                true => value < end,
+                false => value > end,
+            })
+            .map(|value| Object::Integer(Integer { value }))
+            .collect(),
+    }))
+}
+
+fn min_builtin(args: Vec<Object>, _ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
+    extremum(args, MIN_BUILTIN, Ordering::Less)
+}
+
+fn max_builtin(args: Vec<Object>, _ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
+    extremum(args, MAX_BUILTIN, Ordering::Greater)
+}
+
+fn extremum(args: Vec<Object>, fn_name: &str, wanted: Ordering) -> MonkeyResult<Object> {
+    if args.len() < 2 {
+        return Err(format!(
+            "wrong number of arguments for {fn_name} function, at least 2 arguments expected, but got {}",
+            args.len()
+        ));
+    }
+
+    let mut args = args.into_iter();
+    let mut result = args.next().unwrap();
+
+    for arg in args {
+        match arg.compare(&result) {
+            Some(ordering) if ordering == wanted => result = arg,
+            Some(_) => (),
+            None => {
+                return Err(format!(
+                    "arguments to {fn_name} function are not supported, couldn't compare \"{result}\" and \"{arg}\""
+                ))
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+fn abs_builtin(args: Vec<Object>, _ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
+    if args.len() != 1 {
+        return Err(format!(
+            "wrong number of arguments for abs function, 1 argument expected, but got {}",
+            args.len()
+        ));
+    }
+
+    match args.first().unwrap() {
+        Object::Integer(int) => match int.value.checked_abs() {
+            Some(value) => Ok(Object::Integer(Integer { value })),
+            None => Err(String::from("integer overflow in abs")),
+        },
+        Object::Float(float) => Ok(Object::Float(Float {
+            value: float.value.abs(),
+        })),
+        actual => Err(format!(
+            "argument to abs function is not supported, Integer or Float expected, but got \"{actual}\""
+        )),
+    }
+}
+
+fn assert_builtin(args: Vec<Object>, _ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
+    if args.len() != 1 {
+        return Err(format!(
+            "wrong number of arguments for assert function, 1 argument expected, but got {}",
+            args.len()
+        ));
+    }
+
+    match args.first().unwrap().is_truthy() {
+        true => Ok(Object::Null(Null {})),
+        false => Err(String::from("assertion failed")),
+    }
+}
+
+fn assert_eq_builtin(args: Vec<Object>, _ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
+    if args.len() != 2 {
+        return Err(format!(
+            "wrong number of arguments for assert_eq function, 2 arguments expected, but got {}",
+            args.len()
+        ));
+    }
+
+    let (left, right) = (&args[0], &args[1]);
+
+    match left == right {
+        true => Ok(Object::Null(Null {})),
+        false => Err(format!(
+            "assertion failed, {} \"{left}\" is not equal to {} \"{right}\"",
+            left.type_name(),
+            right.type_name()
+        )),
+    }
+}
+
+fn contains_builtin(args: Vec<Object>, _ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
+    if args.len() != 2 {
+        return Err(format!(
+            "wrong number of arguments for contains function, 2 arguments expected, but got {}",
+            args.len()
+        ));
+    }
+
+    let item = args.get(1).unwrap();
+
+    let value = match (args.first().unwrap(), item) {
+        (Object::Array(array), item) => array.elements.contains(item),
+        (Object::String(string), Object::String(substring)) => {
+            string.value.contains(substring.value.as_str())
+        }
+        (Object::String(_), actual) => {
+            return Err(format!(
+                "argument to contains function is not supported, String expected, but got \"{actual}\""
+            ))
+        }
+        (Object::HashTable(hash), key) => {
+            key.validate_hash_key()?;
+            hash.pairs.contains_key(key)
+        }
+        (actual, _) => {
+            return Err(format!(
+                "argument to contains function is not supported, Array, String or HashTable expected, but got \"{actual}\""
+            ))
+        }
+    };
+
+    Ok(Object::Boolean(Boolean { value }))
+}
+
+fn index_of_builtin(args: Vec<Object>, _ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
+    if args.len() != 2 {
+        return Err(format!(
+            "wrong number of arguments for index_of function, 2 arguments expected, but got {}",
+            args.len()
+        ));
+    }
+
+    let index = match (args.first().unwrap(), args.get(1).unwrap()) {
+        (Object::Array(array), item) => array.elements.iter().position(|element| element == item),
+        (Object::String(string), Object::String(substring)) => string
+            .value
+            .find(substring.value.as_str())
+            .map(|byte_idx| string.value[..byte_idx].chars().count()),
+        (Object::String(_), actual) => {
+            return Err(format!(
+                "argument to index_of function is not supported, String expected, but got \"{actual}\""
+            ))
+        }
+        (actual, _) => {
+            return Err(format!(
+                "argument to index_of function is not supported, String or Array expected, but got \"{actual}\""
+            ))
+        }
+    };
+
+    Ok(Object::Integer(Integer {
+        value: index.map_or(-1, |idx| idx as i64),
+    }))
+}
+
+fn slice_builtin(args: Vec<Object>, _ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
+    if args.len() != 3 {
+        return Err(format!(
+            "wrong number of arguments for slice function, 3 arguments expected, but got {}",
+            args.len()
+        ));
+    }
+
+    let bounds = args[1..]
+        .iter()
+        .map(|arg| match arg {
+            Object::Integer(int) => Ok(int.value),
+            actual => Err(format!(
+                "argument to slice function is not supported, Integer expected, but got \"{actual}\""
+            )),
+        })
+        .collect::<MonkeyResult<Vec<_>>>()?;
+
+    // negative indices count from the end, out of range ones are clamped
+    let clamp = |idx: i64, len: usize| {
+        let len = len as i64;
+        let idx = if idx < 0 { len + idx } else { idx };
+
+        idx.clamp(0, len) as usize
+    };
+
+    match args.first().unwrap() {
+        Object::Array(array) => {
+            let len = array.elements.len();
+            let (start, end) = (clamp(bounds[0], len), clamp(bounds[1], len));
+
+            Ok(Object::Array(Array {
+                elements: array.elements[start..end.max(start)].to_vec(),
+            }))
+        }
+        Object::String(string) => {
+            let len = string.value.chars().count();
+            let (start, end) = (clamp(bounds[0], len), clamp(bounds[1], len));
+
+            Ok(Object::String(Str {
+                value: string
+                    .value
+                    .chars()
+                    .skip(start)
+                    .take(end.saturating_sub(start))
+                    .collect(),
+            }))
+        }
+        actual => Err(format!(
+            "argument to slice function is not supported, String or Array expected, but got \"{actual}\""
+        )),
+    }
+}
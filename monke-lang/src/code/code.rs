@@ -4,6 +4,8 @@ use std::{
     vec::IntoIter,
 };
 
+use crate::result::MonkeyResult;
+
 const BYTE_LENGTH: usize = 0x8;
 
 pub type OpCode = u8;
@@ -35,19 +37,20 @@ impl Into<Instructions> for &[u8] {
     }
 }
 
-impl Display for Instructions {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl Instructions {
+    pub fn disassemble(&self) -> MonkeyResult<String> {
         let mut i = 0;
         let mut buffer = String::new();
 
         while i < self.0.len() {
-            let op: OpCodeType = (*self.0.get(i).ok_or(Error)?)
-                .try_into()
-                .map_err(|_| Error)?;
-            let def = get_definition(&op);
+            let op: OpCodeType = self.0[i].try_into()?;
+            let def = definition(op.clone());
+            let width = def.operand_widths.iter().sum::<u32>() as usize;
 
-            let (operands, read) =
-                read_operands(def.clone(), self.0.get(i + 1..).ok_or(Error)?.into());
+            let operands_bytes = self.0.get(i + 1..i + 1 + width).ok_or(format!(
+                "couldn't disassemble {op} at {i:0>4}, expected {width} operand bytes"
+            ))?;
+            let (operands, read) = read_operands(def, operands_bytes.into())?;
 
             let formatted_ops = operands
                 .iter()
@@ -61,11 +64,17 @@ impl Display for Instructions {
             i += 1 + read;
         }
 
-        write!(f, "{buffer}")
+        Ok(buffer)
+    }
+}
+
+impl Display for Instructions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.disassemble().map_err(|_| Error)?)
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum OpCodeType {
     Constant = 1,
     Add,
@@ -97,6 +106,9 @@ pub enum OpCodeType {
     Closure,
     GetFree,
     CurrentClosure,
+    Mod,
+    Plus,
+    LessThan,
 }
 
 impl TryInto<OpCodeType> for u8 {
@@ -134,6 +146,9 @@ impl TryInto<OpCodeType> for u8 {
             28 => Ok(OpCodeType::Closure),
             29 => Ok(OpCodeType::GetFree),
             30 => Ok(OpCodeType::CurrentClosure),
+            31 => Ok(OpCodeType::Mod),
+            32 => Ok(OpCodeType::Plus),
+            33 => Ok(OpCodeType::LessThan),
             n => {
                 let error = format!("Error converting \"{n}\" to OpCodeType");
 
@@ -177,95 +192,70 @@ impl From<OpCodeType> for u8 {
             OpCodeType::Closure => 28,
             OpCodeType::GetFree => 29,
             OpCodeType::CurrentClosure => 30,
+            OpCodeType::Mod => 31,
+            OpCodeType::Plus => 32,
+            OpCodeType::LessThan => 33,
         }
     }
 }
 
 impl Display for OpCodeType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            OpCodeType::Constant => write!(f, "OpConstant"),
-            OpCodeType::Add => write!(f, "OpAdd"),
-            OpCodeType::Pop => write!(f, "OpPop"),
-            OpCodeType::Sub => write!(f, "OpSub"),
-            OpCodeType::Mul => write!(f, "OpMul"),
-            OpCodeType::Div => write!(f, "OpDiv"),
-            OpCodeType::True => write!(f, "OpTrue"),
-            OpCodeType::False => write!(f, "OpFalse"),
-            OpCodeType::Equal => write!(f, "OpEqual"),
-            OpCodeType::NotEqual => write!(f, "OpNotEqual"),
-            OpCodeType::GreaterThan => write!(f, "OpGreaterThan"),
-            OpCodeType::Minus => write!(f, "OpMinus"),
-            OpCodeType::Bang => write!(f, "OpBang"),
-            OpCodeType::JumpNotTruthy => write!(f, "OpJumpNotTruthy"),
-            OpCodeType::Jump => write!(f, "OpJump"),
-            OpCodeType::Null => write!(f, "OpNull"),
-            OpCodeType::GetGlobal => write!(f, "OpGetGlobal"),
-            OpCodeType::SetGlobal => write!(f, "OpSetGlobal"),
-            OpCodeType::Array => write!(f, "OpArray"),
-            OpCodeType::Hash => write!(f, "OpHash"),
-            OpCodeType::Index => write!(f, "OpIndex"),
-            OpCodeType::Call => write!(f, "OpCall"),
-            OpCodeType::ReturnValue => write!(f, "OpReturnValue"),
-            OpCodeType::Return => write!(f, "OpReturn"),
-            OpCodeType::GetLocal => write!(f, "OpGetLocal"),
-            OpCodeType::SetLocal => write!(f, "OpSetLocal"),
-            OpCodeType::GetBuiltin => write!(f, "OpGetBuiltin"),
-            OpCodeType::Closure => write!(f, "OpClosure"),
-            OpCodeType::GetFree => write!(f, "OpGetFree"),
-            OpCodeType::CurrentClosure => write!(f, "OpCurrentClosure"),
-        }
+        write!(f, "{}", definition(self.clone()).name)
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Definition {
-    pub name: OpCodeType,
+    pub name: &'static str,
     pub operand_widths: Vec<u32>,
 }
 
-pub fn get_definition(name: &OpCodeType) -> Definition {
-    let operand_widths = match name {
-        OpCodeType::Constant => vec![2],
-        OpCodeType::Add => vec![],
-        OpCodeType::Pop => vec![],
-        OpCodeType::Sub => vec![],
-        OpCodeType::Mul => vec![],
-        OpCodeType::Div => vec![],
-        OpCodeType::True => vec![],
-        OpCodeType::False => vec![],
-        OpCodeType::Equal => vec![],
-        OpCodeType::NotEqual => vec![],
-        OpCodeType::GreaterThan => vec![],
-        OpCodeType::Minus => vec![],
-        OpCodeType::Bang => vec![],
-        OpCodeType::JumpNotTruthy => vec![2],
-        OpCodeType::Jump => vec![2],
-        OpCodeType::Null => vec![],
-        OpCodeType::GetGlobal => vec![2],
-        OpCodeType::SetGlobal => vec![2],
-        OpCodeType::Array => vec![2],
-        OpCodeType::Hash => vec![2],
-        OpCodeType::Index => vec![],
-        OpCodeType::Call => vec![1],
-        OpCodeType::ReturnValue => vec![],
-        OpCodeType::Return => vec![],
-        OpCodeType::GetLocal => vec![1],
-        OpCodeType::SetLocal => vec![1],
-        OpCodeType::GetBuiltin => vec![1],
-        OpCodeType::Closure => vec![2, 1],
-        OpCodeType::GetFree => vec![1],
-        OpCodeType::CurrentClosure => vec![],
+pub fn definition(op: OpCodeType) -> Definition {
+    let (name, operand_widths) = match op {
+        OpCodeType::Constant => ("OpConstant", vec![2]),
+        OpCodeType::Add => ("OpAdd", vec![]),
+        OpCodeType::Pop => ("OpPop", vec![]),
+        OpCodeType::Sub => ("OpSub", vec![]),
+        OpCodeType::Mul => ("OpMul", vec![]),
+        OpCodeType::Div => ("OpDiv", vec![]),
+        OpCodeType::True => ("OpTrue", vec![]),
+        OpCodeType::False => ("OpFalse", vec![]),
+        OpCodeType::Equal => ("OpEqual", vec![]),
+        OpCodeType::NotEqual => ("OpNotEqual", vec![]),
+        OpCodeType::GreaterThan => ("OpGreaterThan", vec![]),
+        OpCodeType::Minus => ("OpMinus", vec![]),
+        OpCodeType::Bang => ("OpBang", vec![]),
+        OpCodeType::JumpNotTruthy => ("OpJumpNotTruthy", vec![2]),
+        OpCodeType::Jump => ("OpJump", vec![2]),
+        OpCodeType::Null => ("OpNull", vec![]),
+        OpCodeType::GetGlobal => ("OpGetGlobal", vec![2]),
+        OpCodeType::SetGlobal => ("OpSetGlobal", vec![2]),
+        OpCodeType::Array => ("OpArray", vec![2]),
+        OpCodeType::Hash => ("OpHash", vec![2]),
+        OpCodeType::Index => ("OpIndex", vec![]),
+        OpCodeType::Call => ("OpCall", vec![1]),
+        OpCodeType::ReturnValue => ("OpReturnValue", vec![]),
+        OpCodeType::Return => ("OpReturn", vec![]),
+        OpCodeType::GetLocal => ("OpGetLocal", vec![1]),
+        OpCodeType::SetLocal => ("OpSetLocal", vec![1]),
+        OpCodeType::GetBuiltin => ("OpGetBuiltin", vec![1]),
+        OpCodeType::Closure => ("OpClosure", vec![2, 1]),
+        OpCodeType::GetFree => ("OpGetFree", vec![1]),
+        OpCodeType::CurrentClosure => ("OpCurrentClosure", vec![]),
+        OpCodeType::Mod => ("OpMod", vec![]),
+        OpCodeType::Plus => ("OpPlus", vec![]),
+        OpCodeType::LessThan => ("OpLessThan", vec![]),
     };
 
     Definition {
-        name: name.clone(),
+        name,
         operand_widths,
     }
 }
 
 pub fn make(op: OpCodeType, operands: Vec<i32>) -> Instructions {
-    let definition = get_definition(&op);
+    let definition = definition(op.clone());
 
     let mut converted_operands = operands
         .iter()
@@ -285,36 +275,45 @@ pub fn make(op: OpCodeType, operands: Vec<i32>) -> Instructions {
     Instructions(instructions)
 }
 
-pub fn read_operands(def: Definition, instruction: Instructions) -> (Vec<i32>, usize) {
+pub fn read_operands(
+    def: Definition,
+    instruction: Instructions,
+) -> MonkeyResult<(Vec<i32>, usize)> {
     let mut offset = 0;
 
     let mut result = Vec::with_capacity(def.operand_widths.len());
 
     for width in def.operand_widths.into_iter() {
-        let slice = &instruction.0[offset..offset + width as usize];
+        let slice = instruction.0.get(offset..).unwrap_or_default();
 
         match width {
-            2 => {
-                result.push((slice[0] as i32) << BYTE_LENGTH | slice[1] as i32);
-            }
-            1 => result.push(slice[0] as i32),
+            2 => result.push(read_u16(slice)? as i32),
+            1 => result.push(read_u8(slice)? as i32),
             _ => (),
         }
 
         offset += width as usize;
     }
 
-    (result, offset)
+    Ok((result, offset))
 }
 
-pub fn read_u16(bytes: &[u8]) -> u16 {
-    match bytes.len() {
-        0 => 0,
-        1 => ((bytes[0] as u16) << BYTE_LENGTH) as u16,
-        _ => ((bytes[0] as u16) << BYTE_LENGTH | bytes[1] as u16) as u16,
+pub fn read_u16(bytes: &[u8]) -> MonkeyResult<u16> {
+    match bytes {
+        [high, low, ..] => Ok((*high as u16) << BYTE_LENGTH | *low as u16),
+        _ => Err(String::from(
+            "couldn't read operand, unexpected end of instructions",
+        )),
     }
 }
 
+pub fn read_u8(bytes: &[u8]) -> MonkeyResult<u8> {
+    bytes
+        .first()
+        .copied()
+        .ok_or_else(|| String::from("couldn't read operand, unexpected end of instructions"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -361,15 +360,47 @@ mod tests {
 
         for (op, operands, bytes_read) in expected {
             let instruction = make(op.clone(), operands.clone());
-            let def = get_definition(&op);
+            let def = definition(op.clone());
 
-            let (operands_read, n) = read_operands(def, instruction[1..].into());
+            let (operands_read, n) = read_operands(def, instruction[1..].into()).unwrap();
 
             assert_eq!(n, bytes_read);
             assert_eq!(operands_read, operands);
         }
     }
 
+    #[test]
+    fn definition_test() {
+        assert_eq!(
+            definition(OpCodeType::Constant),
+            Definition {
+                name: "OpConstant",
+                operand_widths: vec![2],
+            }
+        );
+        assert_eq!(definition(OpCodeType::Closure).operand_widths, vec![2, 1]);
+        assert_eq!(
+            definition(OpCodeType::Add).operand_widths,
+            Vec::<u32>::new()
+        );
+    }
+
+    #[test]
+    fn read_mixed_widths_test() {
+        let get_local = make(OpCodeType::GetLocal, vec![254]);
+        assert_eq!(read_u8(&get_local[1..]), Ok(254));
+
+        let closure = make(OpCodeType::Closure, vec![65534, 3]);
+        assert_eq!(read_u16(&closure[1..]), Ok(65534));
+        assert_eq!(read_u8(&closure[3..]), Ok(3));
+
+        let error = Err(String::from(
+            "couldn't read operand, unexpected end of instructions",
+        ));
+        assert_eq!(read_u8(&[]), error);
+        assert_eq!(read_u16(&closure[2..3]), error.map(u16::from));
+    }
+
     #[test]
     fn instructions_string_test() {
         let instructions = vec![
@@ -391,4 +422,21 @@ mod tests {
 
         assert_eq!(instructions.to_string(), expected);
     }
+
+    #[test]
+    fn disassemble_error_test() {
+        let instructions =
+            Instructions(vec![OpCodeType::Add.into(), OpCodeType::Constant.into(), 1]);
+
+        assert_eq!(
+            instructions.disassemble(),
+            Err(String::from(
+                "couldn't disassemble OpConstant at 0001, expected 2 operand bytes"
+            ))
+        );
+        assert_eq!(
+            Instructions(vec![255]).disassemble(),
+            Err(String::from("Error converting \"255\" to OpCodeType"))
+        );
+    }
 }
@@ -0,0 +1,343 @@
+use std::{collections::HashMap, rc::Rc};
+
+use crate::{
+    code::code::Instructions,
+    result::MonkeyResult,
+    types::{Boolean, CompiledFunction, Float, Integer, Null, Object, Str},
+};
+
+use super::compiler::ByteCode;
+
+const MAGIC: &[u8; 4] = b"MONK";
+const VERSION: u8 = 3;
+
+const INTEGER_TAG: u8 = 1;
+const FLOAT_TAG: u8 = 2;
+const BOOLEAN_TAG: u8 = 3;
+const STRING_TAG: u8 = 4;
+const NULL_TAG: u8 = 5;
+const COMPILED_FUNCTION_TAG: u8 = 6;
+
+impl ByteCode {
+    pub fn to_bytes(&self) -> MonkeyResult<Vec<u8>> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION);
+
+        write_bytes(&mut bytes, &self.instructions)?;
+        write_len(&mut bytes, self.constants.len())?;
+
+        for constant in &self.constants {
+            write_constant(&mut bytes, constant)?;
+        }
+
+        let mut global_names: Vec<_> = self.global_names.iter().collect();
+        global_names.sort_by_key(|&(_, index)| index);
+
+        write_len(&mut bytes, global_names.len())?;
+
+        for (name, &index) in global_names {
+            write_bytes(&mut bytes, name.as_bytes())?;
+            write_len(&mut bytes, index)?;
+        }
+
+        write_len(&mut bytes, self.builtin_names.len())?;
+
+        for name in &self.builtin_names {
+            write_bytes(&mut bytes, name.as_bytes())?;
+        }
+
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> MonkeyResult<ByteCode> {
+        let mut reader = Reader { bytes, offset: 0 };
+
+        if reader.read(MAGIC.len())? != MAGIC {
+            return Err(String::from(
+                "couldn't read byte code, invalid magic header",
+            ));
+        }
+
+        match reader.read_u8()? {
+            VERSION => (),
+            version => {
+                return Err(format!(
+                    "couldn't read byte code, unsupported version {version}, {VERSION} expected"
+                ))
+            }
+        }
+
+        let instructions = Instructions(reader.read_bytes()?);
+        let constants_num = reader.read_len()?;
+        let mut constants = Vec::new();
+
+        for _ in 0..constants_num {
+            constants.push(Rc::new(reader.read_constant()?));
+        }
+
+        let names_num = reader.read_len()?;
+        let mut global_names = HashMap::new();
+
+        // every name takes at least its length and its index
+        if names_num > reader.remaining() / 8 {
+            return Err(String::from(
+                "couldn't read byte code, unexpected end of input",
+            ));
+        }
+
+        for _ in 0..names_num {
+            global_names.insert(reader.read_string()?, reader.read_len()?);
+        }
+
+        let builtins_num = reader.read_len()?;
+        let mut builtin_names = Vec::new();
+
+        for _ in 0..builtins_num {
+            builtin_names.push(reader.read_string()?);
+        }
+
+        if reader.offset != bytes.len() {
+            return Err(String::from(
+                "couldn't read byte code, unexpected trailing bytes",
+            ));
+        }
+
+        Ok(ByteCode {
+            instructions,
+            constants,
+            global_names,
+            builtin_names,
+        })
+    }
+}
+
+fn write_len(bytes: &mut Vec<u8>, len: usize) -> MonkeyResult<()> {
+    let len =
+        u32::try_from(len).map_err(|_| format!("couldn't write byte code, {len} is too large"))?;
+    bytes.extend_from_slice(&len.to_be_bytes());
+
+    Ok(())
+}
+
+fn write_bytes(bytes: &mut Vec<u8>, data: &[u8]) -> MonkeyResult<()> {
+    write_len(bytes, data.len())?;
+    bytes.extend_from_slice(data);
+
+    Ok(())
+}
+
+fn write_constant(bytes: &mut Vec<u8>, constant: &Object) -> MonkeyResult<()> {
+    match constant {
+        Object::Integer(int) => {
+            bytes.push(INTEGER_TAG);
+            bytes.extend_from_slice(&int.value.to_be_bytes());
+        }
+        Object::Float(float) => {
+            bytes.push(FLOAT_TAG);
+            bytes.extend_from_slice(&float.value.to_bits().to_be_bytes());
+        }
+        Object::Boolean(boolean) => {
+            bytes.push(BOOLEAN_TAG);
+            bytes.push(boolean.value as u8);
+        }
+        Object::String(string) => {
+            bytes.push(STRING_TAG);
+            write_bytes(bytes, string.value.as_bytes())?;
+        }
+        Object::Null(_) => bytes.push(NULL_TAG),
+        Object::CompiledFunction(func) => {
+            bytes.push(COMPILED_FUNCTION_TAG);
+            write_bytes(bytes, &func.instructions)?;
+            write_len(bytes, func.locals_num)?;
+            write_len(bytes, func.parameters_num)?;
+        }
+        actual => Err(format!(
+            "couldn't write byte code, unsupported constant \"{actual}\""
+        ))?,
+    }
+
+    Ok(())
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn read(&mut self, len: usize) -> MonkeyResult<&'a [u8]> {
+        let slice = self
+            .bytes
+            .get(self.offset..self.offset + len)
+            .ok_or(String::from(
+                "couldn't read byte code, unexpected end of input",
+            ))?;
+        self.offset += len;
+
+        Ok(slice)
+    }
+
+    fn remaining(&self) -> usize {
+        self.bytes.len().saturating_sub(self.offset)
+    }
+
+    fn read_u8(&mut self) -> MonkeyResult<u8> {
+        Ok(self.read(1)?[0])
+    }
+
+    fn read_u64(&mut self) -> MonkeyResult<u64> {
+        Ok(u64::from_be_bytes(self.read(8)?.try_into().unwrap()))
+    }
+
+    fn read_len(&mut self) -> MonkeyResult<usize> {
+        Ok(u32::from_be_bytes(self.read(4)?.try_into().unwrap()) as usize)
+    }
+
+    fn read_bytes(&mut self) -> MonkeyResult<Vec<u8>> {
+        let len = self.read_len()?;
+        Ok(self.read(len)?.to_vec())
+    }
+
+    fn read_string(&mut self) -> MonkeyResult<String> {
+        String::from_utf8(self.read_bytes()?)
+            .map_err(|_| String::from("couldn't read byte code, invalid utf-8 string"))
+    }
+
+    fn read_constant(&mut self) -> MonkeyResult<Object> {
+        match self.read_u8()? {
+            INTEGER_TAG => Ok(Object::Integer(Integer {
+                value: self.read_u64()? as i64,
+            })),
+            FLOAT_TAG => Ok(Object::Float(Float {
+                value: f64::from_bits(self.read_u64()?),
+            })),
+            BOOLEAN_TAG => Ok(Object::Boolean(Boolean {
+                value: self.read_u8()? != 0,
+            })),
+            STRING_TAG => Ok(Object::String(Str {
+                value: self.read_string()?,
+            })),
+            NULL_TAG => Ok(Object::Null(Null {})),
+            COMPILED_FUNCTION_TAG => Ok(Object::CompiledFunction(CompiledFunction {
+                instructions: Instructions(self.read_bytes()?),
+                locals_num: self.read_len()?,
+                parameters_num: self.read_len()?,
+            })),
+            tag => Err(format!(
+                "couldn't read byte code, unknown constant tag {tag}"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MAGIC, VERSION};
+    use crate::{
+        compiler::compiler::{ByteCode, Compiler},
+        lexer::lexer::Lexer,
+        parser::parser::Parser,
+        types::{Boolean, Integer, Object},
+        vm::vm::Vm,
+    };
+
+    fn compile_input(input: &str) -> ByteCode {
+        let program = Parser::new(Lexer::new(String::from(input)))
+            .parse_program()
+            .unwrap();
+
+        let mut compiler = Compiler::new();
+        compiler.compile(program).unwrap();
+        compiler.byte_code().unwrap()
+    }
+
+    #[test]
+    fn round_trip_test() {
+        let expected = vec![
+            ("1 + 2", Object::Integer(Integer { value: 3 })),
+            (
+                r#"let f = fn(a, b) { let c = a * b; c + 0.5 }; len(["x", "yz"]) + f(2, 3) > 6.0 == true"#,
+                Object::Boolean(Boolean { value: true }),
+            ),
+        ];
+
+        for (input, expected_result) in expected {
+            let byte_code = compile_input(input);
+            let bytes = byte_code.to_bytes().unwrap();
+            let decoded = ByteCode::from_bytes(&bytes).unwrap();
+
+            assert_eq!(decoded.instructions, byte_code.instructions);
+            assert_eq!(decoded.constants, byte_code.constants);
+            assert_eq!(decoded.global_names, byte_code.global_names);
+            assert_eq!(decoded.builtin_names, byte_code.builtin_names);
+
+            let mut vm = Vm::new(decoded);
+            vm.run().unwrap();
+
+            assert_eq!(vm.last_popped_stack_elem().unwrap(), expected_result);
+        }
+    }
+
+    #[test]
+    fn invalid_bytes_test() {
+        let bytes = compile_input("1 + 2").to_bytes().unwrap();
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'X';
+
+        let mut bad_version = bytes.clone();
+        bad_version[4] = 42;
+
+        let expected = vec![
+            (bad_magic, "couldn't read byte code, invalid magic header"),
+            (
+                bad_version,
+                "couldn't read byte code, unsupported version 42, 3 expected",
+            ),
+            (
+                bytes[..bytes.len() - 1].to_vec(),
+                "couldn't read byte code, unexpected end of input",
+            ),
+            (vec![], "couldn't read byte code, unexpected end of input"),
+        ];
+
+        for (input, error) in expected {
+            assert_eq!(
+                ByteCode::from_bytes(&input).map(|_| ()),
+                Err(String::from(error))
+            );
+        }
+    }
+
+    #[test]
+    fn huge_constants_num_test() {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&0u32.to_be_bytes());
+        bytes.extend_from_slice(&u32::MAX.to_be_bytes());
+
+        assert_eq!(
+            ByteCode::from_bytes(&bytes).map(|_| ()),
+            Err(String::from(
+                "couldn't read byte code, unexpected end of input"
+            ))
+        );
+    }
+
+    #[test]
+    fn huge_global_names_num_test() {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&0u32.to_be_bytes());
+        bytes.extend_from_slice(&0u32.to_be_bytes());
+        bytes.extend_from_slice(&u32::MAX.to_be_bytes());
+        bytes.extend_from_slice(&[0; 8]);
+
+        assert_eq!(
+            ByteCode::from_bytes(&bytes).map(|_| ()),
+            Err(String::from(
+                "couldn't read byte code, unexpected end of input"
+            ))
+        );
+    }
+}
@@ -1,14 +1,14 @@
-use std::rc::Rc;
+use std::{collections::HashMap, rc::Rc};
 
 use crate::{
-    code::code::{make, Instructions, OpCodeType},
+    code::code::{definition, make, Instructions, OpCodeType},
     lexer::token::Token,
-    parser::ast::{Expression, Program, Statement},
-    result::MonkeyResult,
-    types::{CompiledFunction, Integer, Object, Str},
+    parser::ast::{Expression, InfixExpression, Program, Statement},
+    result::{MonkeyError, MonkeyResult},
+    types::{CompiledFunction, Float, Integer, Object, Str},
 };
 
-use super::symbol_table::{SymbolScope, SymbolTable, SymbolTableRef};
+use super::symbol_table::{Symbol, SymbolScope, SymbolTable, SymbolTableRef};
 
 #[derive(Debug, Clone)]
 struct EmittedInstruction {
@@ -25,16 +25,20 @@ pub struct CompilationScope {
 
 #[derive(Debug)]
 pub struct Compiler {
-    pub constants: Vec<Object>,
+    pub constants: Vec<Rc<Object>>,
     pub symbol_table: SymbolTableRef,
     pub scopes: Vec<CompilationScope>,
     scope_index: usize,
+    optimize: bool,
+    forward_globals: Vec<String>,
 }
 
 #[derive(Debug)]
 pub struct ByteCode {
     pub instructions: Instructions,
-    pub constants: Vec<Object>,
+    pub constants: Vec<Rc<Object>>,
+    pub global_names: HashMap<String, usize>,
+    pub builtin_names: Vec<String>,
 }
 
 impl Compiler {
@@ -57,10 +61,12 @@ impl Compiler {
             symbol_table,
             scopes: vec![main_scope],
             scope_index: 0,
+            optimize: false,
+            forward_globals: vec![],
         }
     }
 
-    pub fn new_with_state(symbol_table: SymbolTableRef, constants: Vec<Object>) -> Self {
+    pub fn new_with_state(symbol_table: SymbolTableRef, constants: Vec<Rc<Object>>) -> Self {
         let main_scope = CompilationScope {
             instructions: Instructions(vec![]),
             last_instruction: None,
@@ -72,25 +78,81 @@ impl Compiler {
             symbol_table,
             scopes: vec![main_scope],
             scope_index: 0,
+            optimize: false,
+            forward_globals: vec![],
         }
     }
 
-    pub fn compile(&mut self, program: Program) -> MonkeyResult<()> {
+    pub fn register_builtin(&mut self, name: &str) {
+        let mut symbol_table = self.symbol_table.borrow_mut();
+
+        if let Some(symbol) = symbol_table.store.get(name) {
+            if symbol.scope == SymbolScope::Builtin {
+                return;
+            }
+        }
+
+        let index = symbol_table.builtins_num;
+        symbol_table.define_builtin(index, String::from(name));
+    }
+
+    pub fn with_optimizations(mut self) -> Self {
+        self.optimize = true;
+        self
+    }
+
+    pub fn compile(&mut self, program: Program) -> Result<(), MonkeyError> {
+        self.compile_node(program)
+            .map_err(|message| MonkeyError::Compile {
+                message,
+                position: None,
+            })
+    }
+
+    fn compile_node(&mut self, program: Program) -> MonkeyResult<()> {
         match program {
             Program::Statements(statements) => {
+                let is_global = self.symbol_table.borrow().outer.is_none();
+
                 for statement in statements {
-                    self.compile(statement.into())?;
+                    // functions may refer to globals defined right after them, but nothing can
+                    // run before those are defined
+                    if is_global && !Self::is_function_let(&statement) {
+                        self.check_forward_globals()?;
+                    }
+
+                    self.compile_node(statement.into())?;
+                }
+
+                if is_global {
+                    self.check_forward_globals()?;
                 }
 
                 Ok(())
             }
             Program::Statement(statement) => match statement.as_ref() {
                 Statement::Let(let_statement) => {
-                    let symbol = self
-                        .symbol_table
-                        .borrow_mut()
-                        .define(let_statement.name.to_string());
-                    self.compile(Rc::clone(&let_statement.value).into())?;
+                    let define = |compiler: &mut Compiler| {
+                        compiler
+                            .symbol_table
+                            .borrow_mut()
+                            .define(let_statement.name.to_string())
+                    };
+
+                    self.forward_globals
+                        .retain(|name| *name != let_statement.name.to_string());
+
+                    let symbol = match let_statement.value.as_ref() {
+                        Expression::FunctionLiteral(_) => {
+                            let symbol = define(self);
+                            self.compile_node(Rc::clone(&let_statement.value).into())?;
+                            symbol
+                        }
+                        _ => {
+                            self.compile_node(Rc::clone(&let_statement.value).into())?;
+                            define(self)
+                        }
+                    };
 
                     match symbol.scope {
                         SymbolScope::Global => {
@@ -105,35 +167,89 @@ impl Compiler {
                     Ok(())
                 }
                 Statement::Return(return_statement) => {
-                    self.compile(Rc::clone(&return_statement.return_value).into())?;
+                    self.compile_node(Rc::clone(&return_statement.return_value).into())?;
                     self.emit(OpCodeType::ReturnValue, vec![])?;
 
                     Ok(())
                 }
                 Statement::Expression(expression_statement) => {
-                    self.compile(Rc::clone(&expression_statement.expression).into())?;
+                    self.compile_node(Rc::clone(&expression_statement.expression).into())?;
                     self.emit(OpCodeType::Pop, vec![])?;
 
                     Ok(())
                 }
                 Statement::Block(block) => {
                     for statement in &block.statements {
-                        self.compile(Rc::clone(statement).into())?;
+                        self.compile_node(Rc::clone(statement).into())?;
                     }
 
+                    Ok(())
+                }
+                Statement::While(while_statement) => {
+                    let loop_start_pos = self
+                        .current_instructions()
+                        .ok_or(String::from("couldn't get current instructions"))?
+                        .len() as i32;
+
+                    self.compile_node(Rc::clone(&while_statement.condition).into())?;
+                    let jump_not_truthy_pos =
+                        self.emit(OpCodeType::JumpNotTruthy, vec![Self::KEKL_VALUE])?;
+
+                    self.compile_node(Rc::clone(&while_statement.body).into())?;
+                    self.emit(OpCodeType::Jump, vec![loop_start_pos])?;
+
+                    let after_body_pos = self
+                        .current_instructions()
+                        .ok_or(String::from("couldn't get current instructions"))?
+                        .len() as i32;
+                    self.change_operand(jump_not_truthy_pos, after_body_pos)?;
+
+                    // while evaluates to null, the same as in the evaluator
+                    self.emit(OpCodeType::Null, vec![])?;
+                    self.emit(OpCodeType::Pop, vec![])?;
+
                     Ok(())
                 }
             },
             Program::Expression(expression) => match expression.as_ref() {
                 Expression::Identifier(ident) => {
-                    let value = self
+                    let resolved = self.symbol_table.borrow_mut().resolve(&ident.to_string());
+                    let value = match resolved {
+                        Some(symbol) => symbol,
+                        None => self.define_forward_global(ident.to_string())?,
+                    };
+
+                    self.load_symbol(value)?;
+                    Ok(())
+                }
+                Expression::Assign(assign) => {
+                    let symbol = self
                         .symbol_table
                         .borrow_mut()
-                        .resolve(&ident.to_string())
-                        .ok_or(format!("couldn't resolve identifier value: \"{ident}\""))?
+                        .resolve(&assign.name.to_string())
+                        .ok_or(format!(
+                            "couldn't assign to undefined identifier: \"{}\"",
+                            assign.name
+                        ))?
                         .clone();
 
-                    self.load_symbol(value)?;
+                    self.compile_node(Rc::clone(&assign.value).into())?;
+
+                    match symbol.scope {
+                        SymbolScope::Global => {
+                            self.emit(OpCodeType::SetGlobal, vec![symbol.index as i32])?
+                        }
+                        SymbolScope::Local => {
+                            self.emit(OpCodeType::SetLocal, vec![symbol.index as i32])?
+                        }
+                        _ => Err(format!(
+                            "couldn't assign to \"{}\", only global and local bindings could be reassigned",
+                            assign.name
+                        ))?,
+                    };
+
+                    self.load_symbol(symbol)?;
+
                     Ok(())
                 }
                 Expression::IntegerLiteral(int_expression) => {
@@ -145,6 +261,15 @@ impl Compiler {
 
                     Ok(())
                 }
+                Expression::FloatLiteral(float_expression) => {
+                    let float = Object::Float(Float {
+                        value: float_expression.value,
+                    });
+                    let operand = self.add_constant(float);
+                    self.emit(OpCodeType::Constant, vec![operand as i32])?;
+
+                    Ok(())
+                }
                 Expression::StringLiteral(string) => {
                     let str = Object::String(Str {
                         value: string.to_string(),
@@ -155,34 +280,41 @@ impl Compiler {
                     Ok(())
                 }
                 Expression::Prefix(prefix) => {
-                    self.compile(Rc::clone(&prefix.right).into())?;
+                    if self.fold_constant(expression.as_ref())? {
+                        return Ok(());
+                    }
+
+                    self.compile_node(Rc::clone(&prefix.right).into())?;
 
                     match &prefix.token {
                         Token::Bang => self.emit(OpCodeType::Bang, vec![])?,
                         Token::Minus => self.emit(OpCodeType::Minus, vec![])?,
-                        actual => Err(format!("couldn't compile prefix expression, bang or minus operators expected, but got {actual}"))?,
+                        Token::Plus => self.emit(OpCodeType::Plus, vec![])?,
+                        actual => Err(format!("couldn't compile prefix expression, bang, minus or plus operators expected, but got {actual}"))?,
                     };
 
                     Ok(())
                 }
                 Expression::Infix(infix_expression) => {
-                    if infix_expression.token == Token::Lt {
-                        self.compile(Rc::clone(&infix_expression.right).into())?;
-                        self.compile(Rc::clone(&infix_expression.left).into())?;
-                        self.emit(OpCodeType::GreaterThan, vec![])?;
-
+                    if self.fold_constant(expression.as_ref())? {
                         return Ok(());
                     }
 
-                    self.compile(Rc::clone(&infix_expression.left).into())?;
-                    self.compile(Rc::clone(&infix_expression.right).into())?;
+                    if infix_expression.token == Token::And || infix_expression.token == Token::Or {
+                        return self.compile_logical_expression(infix_expression);
+                    }
+
+                    self.compile_node(Rc::clone(&infix_expression.left).into())?;
+                    self.compile_node(Rc::clone(&infix_expression.right).into())?;
 
                     match infix_expression.token {
                         Token::Plus => self.emit(OpCodeType::Add, vec![])?,
                         Token::Minus => self.emit(OpCodeType::Sub, vec![])?,
                         Token::Asterisk => self.emit(OpCodeType::Mul, vec![])?,
                         Token::Slash => self.emit(OpCodeType::Div, vec![])?,
+                        Token::Percent => self.emit(OpCodeType::Mod, vec![])?,
                         Token::Gt => self.emit(OpCodeType::GreaterThan, vec![])?,
+                        Token::Lt => self.emit(OpCodeType::LessThan, vec![])?,
                         Token::Eq => self.emit(OpCodeType::Equal, vec![])?,
                         Token::Ne => self.emit(OpCodeType::NotEqual, vec![])?,
                         _ => todo!(),
@@ -201,14 +333,16 @@ impl Compiler {
                     }
                 },
                 Expression::If(if_expression) => {
-                    self.compile(Rc::clone(&if_expression.condition).into())?;
+                    self.compile_node(Rc::clone(&if_expression.condition).into())?;
                     let jump_not_truthy_pos =
                         self.emit(OpCodeType::JumpNotTruthy, vec![Self::KEKL_VALUE])?;
 
-                    self.compile(Rc::clone(&if_expression.consequence).into())?;
+                    self.compile_node(Rc::clone(&if_expression.consequence).into())?;
 
                     if self.last_instruction_is(OpCodeType::Pop) {
                         self.remove_last_pop()?;
+                    } else {
+                        self.emit(OpCodeType::Null, vec![])?;
                     }
 
                     let jump_pos = self.emit(OpCodeType::Jump, vec![Self::KEKL_VALUE])?;
@@ -221,10 +355,12 @@ impl Compiler {
 
                     match &if_expression.alternative {
                         Some(alternative) => {
-                            self.compile(Rc::clone(alternative).into())?;
+                            self.compile_node(Rc::clone(alternative).into())?;
 
                             if self.last_instruction_is(OpCodeType::Pop) {
                                 self.remove_last_pop()?;
+                            } else {
+                                self.emit(OpCodeType::Null, vec![])?;
                             }
                         }
                         None => {
@@ -255,7 +391,7 @@ impl Compiler {
                             .define(param.token.to_string());
                     }
 
-                    self.compile(Rc::clone(&func.body).into())?;
+                    self.compile_node(Rc::clone(&func.body).into())?;
 
                     if self.last_instruction_is(OpCodeType::Pop) {
                         self.replace_last_pop_with_return()?;
@@ -291,10 +427,10 @@ impl Compiler {
                     Ok(())
                 }
                 Expression::Call(call) => {
-                    self.compile(Rc::clone(&call.function).into())?;
+                    self.compile_node(Rc::clone(&call.function).into())?;
 
                     for arg in &call.arguments {
-                        self.compile(Rc::clone(arg).into())?;
+                        self.compile_node(Rc::clone(arg).into())?;
                     }
 
                     self.emit(OpCodeType::Call, vec![call.arguments.len() as i32])?;
@@ -303,7 +439,7 @@ impl Compiler {
                 }
                 Expression::ArrayLiteral(array) => {
                     for el in &array.elements {
-                        self.compile(Rc::clone(el).into())?;
+                        self.compile_node(Rc::clone(el).into())?;
                     }
 
                     self.emit(OpCodeType::Array, vec![array.elements.len() as i32])?;
@@ -311,27 +447,17 @@ impl Compiler {
                     Ok(())
                 }
                 Expression::IndexExpression(index_exp) => {
-                    self.compile(Rc::clone(&index_exp.left).into())?;
-                    self.compile(Rc::clone(&index_exp.index).into())?;
+                    self.compile_node(Rc::clone(&index_exp.left).into())?;
+                    self.compile_node(Rc::clone(&index_exp.index).into())?;
 
                     self.emit(OpCodeType::Index, vec![])?;
 
                     Ok(())
                 }
                 Expression::HashLiteral(hash_literal) => {
-                    let mut keys: Vec<_> = hash_literal.pairs.keys().collect();
-                    keys.sort_unstable_by(|&a, &b| {
-                        a.as_ref().to_string().cmp(&b.as_ref().to_string())
-                    });
-
-                    for key in keys {
-                        self.compile(Rc::clone(&key).into())?;
-
-                        let value = hash_literal
-                            .pairs
-                            .get(key)
-                            .ok_or(String::from("couldn't compile hash literal"))?;
-                        self.compile(Rc::clone(value).into())?;
+                    for (key, value) in &hash_literal.pairs {
+                        self.compile_node(Rc::clone(key).into())?;
+                        self.compile_node(Rc::clone(value).into())?;
                     }
 
                     self.emit(
@@ -346,20 +472,89 @@ impl Compiler {
     }
 
     pub fn byte_code(&self) -> MonkeyResult<ByteCode> {
+        let symbol_table = self.symbol_table.borrow();
+        let mut builtin_names = vec![String::new(); symbol_table.builtins_num];
+
+        for symbol in symbol_table.store.values() {
+            if symbol.scope == SymbolScope::Builtin {
+                builtin_names[symbol.index] = symbol.name.clone();
+            }
+        }
+
         Ok(ByteCode {
             constants: self.constants.clone(),
             instructions: self
                 .current_instructions()
                 .ok_or(String::from("couldn't get current instructions"))?,
+            global_names: symbol_table
+                .store
+                .values()
+                .filter(|symbol| symbol.scope == SymbolScope::Global)
+                .map(|symbol| (symbol.name.clone(), symbol.index))
+                .collect(),
+            builtin_names,
         })
     }
 
+    fn is_function_let(statement: &Statement) -> bool {
+        match statement {
+            Statement::Let(let_statement) => {
+                matches!(let_statement.value.as_ref(), Expression::FunctionLiteral(_))
+            }
+            _ => false,
+        }
+    }
+
+    fn define_forward_global(&mut self, name: String) -> MonkeyResult<Symbol> {
+        if self.symbol_table.borrow().outer.is_none() {
+            return Err(format!("couldn't resolve identifier value: \"{name}\""));
+        }
+
+        let mut global_table = Rc::clone(&self.symbol_table);
+
+        while let Some(outer) = global_table.clone().borrow().outer.clone() {
+            global_table = outer;
+        }
+
+        let symbol = global_table.borrow_mut().define(name.clone());
+        self.forward_globals.push(name);
+
+        Ok(symbol)
+    }
+
+    fn check_forward_globals(&self) -> MonkeyResult<()> {
+        match self.forward_globals.first() {
+            Some(name) => Err(format!("couldn't resolve identifier value: \"{name}\"")),
+            None => Ok(()),
+        }
+    }
+
     fn add_constant(&mut self, obj: Object) -> usize {
-        self.constants.push(obj);
+        if self.optimize {
+            if let Some(idx) = self
+                .constants
+                .iter()
+                .position(|constant| constant.as_ref() == &obj)
+            {
+                return idx;
+            }
+        }
+
+        self.constants.push(Rc::new(obj));
         self.constants.len() - 1
     }
 
     fn emit(&mut self, op: OpCodeType, operands: Vec<i32>) -> MonkeyResult<usize> {
+        let definition = definition(op.clone());
+
+        for (operand, width) in operands.iter().zip(definition.operand_widths.iter()) {
+            if *operand < 0 || *operand as i64 >= 1 << (width * 8) {
+                return Err(format!(
+                    "couldn't emit {op}, operand {operand} doesn't fit into {width} bytes"
+                ));
+            }
+        }
+
         let instructions = make(op.clone(), operands);
         let pos = self.add_instructions(instructions)?;
 
@@ -555,6 +750,56 @@ impl Compiler {
         Ok(())
     }
 
+    fn fold_constant(&mut self, expression: &Expression) -> MonkeyResult<bool> {
+        if !self.optimize {
+            return Ok(false);
+        }
+
+        match fold_integer_expression(expression) {
+            Some(value) => {
+                let operand = self.add_constant(Object::Integer(Integer { value }));
+                self.emit(OpCodeType::Constant, vec![operand as i32])?;
+
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn compile_logical_expression(
+        &mut self,
+        infix_expression: &InfixExpression,
+    ) -> MonkeyResult<()> {
+        self.compile_node(Rc::clone(&infix_expression.left).into())?;
+
+        if infix_expression.token == Token::Or {
+            self.emit(OpCodeType::Bang, vec![])?;
+        }
+
+        let left_jump_pos = self.emit(OpCodeType::JumpNotTruthy, vec![Self::KEKL_VALUE])?;
+
+        self.compile_node(Rc::clone(&infix_expression.right).into())?;
+        let right_jump_pos = self.emit(OpCodeType::JumpNotTruthy, vec![Self::KEKL_VALUE])?;
+
+        let true_pos = self.emit(OpCodeType::True, vec![])?;
+        let jump_pos = self.emit(OpCodeType::Jump, vec![Self::KEKL_VALUE])?;
+        let false_pos = self.emit(OpCodeType::False, vec![])?;
+
+        let after_pos = self
+            .current_instructions()
+            .ok_or(String::from("couldn't get current instructions"))?
+            .len() as i32;
+
+        match infix_expression.token {
+            Token::Or => self.change_operand(left_jump_pos, true_pos as i32)?,
+            _ => self.change_operand(left_jump_pos, false_pos as i32)?,
+        };
+        self.change_operand(right_jump_pos, false_pos as i32)?;
+        self.change_operand(jump_pos, after_pos)?;
+
+        Ok(())
+    }
+
     fn load_symbol(&mut self, value: super::symbol_table::Symbol) -> MonkeyResult<()> {
         match value.scope {
             SymbolScope::Global => self.emit(OpCodeType::GetGlobal, vec![value.index as i32])?,
@@ -568,6 +813,32 @@ impl Compiler {
     }
 }
 
+fn fold_integer_expression(expression: &Expression) -> Option<i64> {
+    match expression {
+        Expression::IntegerLiteral(int) => Some(int.value),
+        Expression::Prefix(prefix) if prefix.token == Token::Minus => {
+            fold_integer_expression(&prefix.right)?.checked_neg()
+        }
+        Expression::Prefix(prefix) if prefix.token == Token::Plus => {
+            fold_integer_expression(&prefix.right)
+        }
+        Expression::Infix(infix) => {
+            let left = fold_integer_expression(&infix.left)?;
+            let right = fold_integer_expression(&infix.right)?;
+
+            match infix.token {
+                Token::Plus => left.checked_add(right),
+                Token::Minus => left.checked_sub(right),
+                Token::Asterisk => left.checked_mul(right),
+                Token::Slash => left.checked_div(right),
+                Token::Percent => left.checked_rem(right),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod test {
     use core::panic;
@@ -592,6 +863,7 @@ mod test {
     #[derive(Debug)]
     enum TestCaseResult {
         Integer(i64),
+        Float(f64),
         String(String),
         InstructionsVec(Vec<Instructions>),
     }
@@ -602,6 +874,9 @@ mod test {
                 (TestCaseResult::Integer(expected), Object::Integer(actual_int)) => {
                     assert_eq!(expected, &actual_int.value)
                 }
+                (TestCaseResult::Float(expected), Object::Float(actual_float)) => {
+                    assert_eq!(expected, &actual_float.value)
+                }
                 (TestCaseResult::String(expected), Object::String(actual_str)) => {
                     assert_eq!(expected, &actual_str.value)
                 }
@@ -678,6 +953,136 @@ mod test {
         );
     }
 
+    #[test]
+    fn disassemble_test() {
+        let program = Parser::new(Lexer::new(String::from("1 + 2")))
+            .parse_program()
+            .unwrap();
+
+        let mut compiler = Compiler::new();
+        compiler.compile(program).unwrap();
+
+        assert_eq!(
+            compiler
+                .byte_code()
+                .unwrap()
+                .instructions
+                .disassemble()
+                .unwrap(),
+            "0000 OpConstant 0\n0003 OpConstant 1\n0006 OpAdd\n0007 OpPop\n"
+        );
+    }
+
+    #[test]
+    fn constant_deduplication_test() {
+        let compile = |compiler: Compiler, input: &str| {
+            let mut compiler = compiler;
+            let program = Parser::new(Lexer::new(String::from(input)))
+                .parse_program()
+                .unwrap();
+
+            compiler.compile(program).unwrap();
+            compiler.byte_code().unwrap()
+        };
+
+        let byte_code = compile(Compiler::new().with_optimizations(), "[1, 1]");
+        let expected = TestCase {
+            input: String::from("[1, 1]"),
+            expected_constants: vec![TestCaseResult::Integer(1)],
+            expected_instructions: vec![
+                make(OpCodeType::Constant, vec![0]),
+                make(OpCodeType::Constant, vec![0]),
+                make(OpCodeType::Array, vec![2]),
+                make(OpCodeType::Pop, vec![]),
+            ],
+        };
+
+        test_instructions(&byte_code, &expected);
+        test_constants(&byte_code, &expected);
+
+        let byte_code = compile(
+            Compiler::new().with_optimizations(),
+            r#"[1, "a", 1, 2.5, "a", 2.5, 2]"#,
+        );
+        assert_eq!(byte_code.constants.len(), 4);
+
+        let byte_code = compile(Compiler::new(), "1 + 1");
+        assert_eq!(byte_code.constants.len(), 2);
+    }
+
+    #[test]
+    fn constant_folding_test() {
+        let expected = vec![
+            (
+                "2 + 3",
+                vec![TestCaseResult::Integer(5)],
+                vec![
+                    make(OpCodeType::Constant, vec![0]),
+                    make(OpCodeType::Pop, vec![]),
+                ],
+            ),
+            (
+                "-(2 + 3 * 4) % 5",
+                vec![TestCaseResult::Integer(-4)],
+                vec![
+                    make(OpCodeType::Constant, vec![0]),
+                    make(OpCodeType::Pop, vec![]),
+                ],
+            ),
+            (
+                "1 / 0",
+                vec![TestCaseResult::Integer(1), TestCaseResult::Integer(0)],
+                vec![
+                    make(OpCodeType::Constant, vec![0]),
+                    make(OpCodeType::Constant, vec![1]),
+                    make(OpCodeType::Div, vec![]),
+                    make(OpCodeType::Pop, vec![]),
+                ],
+            ),
+            (
+                "let x = 2; x * (3 + 4)",
+                vec![TestCaseResult::Integer(2), TestCaseResult::Integer(7)],
+                vec![
+                    make(OpCodeType::Constant, vec![0]),
+                    make(OpCodeType::SetGlobal, vec![0]),
+                    make(OpCodeType::GetGlobal, vec![0]),
+                    make(OpCodeType::Constant, vec![1]),
+                    make(OpCodeType::Mul, vec![]),
+                    make(OpCodeType::Pop, vec![]),
+                ],
+            ),
+            (
+                "1 < 2",
+                vec![TestCaseResult::Integer(1), TestCaseResult::Integer(2)],
+                vec![
+                    make(OpCodeType::Constant, vec![0]),
+                    make(OpCodeType::Constant, vec![1]),
+                    make(OpCodeType::LessThan, vec![]),
+                    make(OpCodeType::Pop, vec![]),
+                ],
+            ),
+        ];
+
+        for (input, expected_constants, expected_instructions) in expected {
+            let program = Parser::new(Lexer::new(String::from(input)))
+                .parse_program()
+                .unwrap();
+
+            let mut compiler = Compiler::new().with_optimizations();
+            compiler.compile(program).unwrap();
+            let byte_code = compiler.byte_code().unwrap();
+
+            let expected = TestCase {
+                input: String::from(input),
+                expected_constants,
+                expected_instructions,
+            };
+
+            test_instructions(&byte_code, &expected);
+            test_constants(&byte_code, &expected);
+        }
+    }
+
     #[test]
     fn integer_arithmetic_test() {
         let expected = vec![
@@ -731,6 +1136,16 @@ mod test {
                     make(OpCodeType::Pop, vec![]),
                 ],
             },
+            TestCase {
+                input: String::from("5 % 2"),
+                expected_constants: vec![TestCaseResult::Integer(5), TestCaseResult::Integer(2)],
+                expected_instructions: vec![
+                    make(OpCodeType::Constant, vec![0]),
+                    make(OpCodeType::Constant, vec![1]),
+                    make(OpCodeType::Mod, vec![]),
+                    make(OpCodeType::Pop, vec![]),
+                ],
+            },
             TestCase {
                 input: String::from("-1"),
                 expected_constants: vec![TestCaseResult::Integer(1)],
@@ -745,6 +1160,22 @@ mod test {
         run_compiler_tests(expected);
     }
 
+    #[test]
+    fn float_arithmetic_test() {
+        let expected = vec![TestCase {
+            input: String::from("1.5 * 2"),
+            expected_constants: vec![TestCaseResult::Float(1.5), TestCaseResult::Integer(2)],
+            expected_instructions: vec![
+                make(OpCodeType::Constant, vec![0]),
+                make(OpCodeType::Constant, vec![1]),
+                make(OpCodeType::Mul, vec![]),
+                make(OpCodeType::Pop, vec![]),
+            ],
+        }];
+
+        run_compiler_tests(expected);
+    }
+
     #[test]
     fn boolean_expression_test() {
         let expected = vec![
@@ -776,11 +1207,11 @@ mod test {
             },
             TestCase {
                 input: String::from("1 < 2"),
-                expected_constants: vec![TestCaseResult::Integer(2), TestCaseResult::Integer(1)],
+                expected_constants: vec![TestCaseResult::Integer(1), TestCaseResult::Integer(2)],
                 expected_instructions: vec![
                     make(OpCodeType::Constant, vec![0]),
                     make(OpCodeType::Constant, vec![1]),
-                    make(OpCodeType::GreaterThan, vec![]),
+                    make(OpCodeType::LessThan, vec![]),
                     make(OpCodeType::Pop, vec![]),
                 ],
             },
@@ -876,6 +1307,55 @@ mod test {
                     make(OpCodeType::Pop, vec![]),
                 ],
             },
+            TestCase {
+                input: String::from("if (true) { }"),
+                expected_constants: vec![],
+                expected_instructions: vec![
+                    make(OpCodeType::True, vec![]),
+                    make(OpCodeType::JumpNotTruthy, vec![8]),
+                    make(OpCodeType::Null, vec![]),
+                    make(OpCodeType::Jump, vec![9]),
+                    make(OpCodeType::Null, vec![]),
+                    make(OpCodeType::Pop, vec![]),
+                ],
+            },
+        ];
+
+        run_compiler_tests(expected);
+    }
+
+    #[test]
+    fn logical_operators_test() {
+        let expected = vec![
+            TestCase {
+                input: String::from("true && false"),
+                expected_constants: vec![],
+                expected_instructions: vec![
+                    make(OpCodeType::True, vec![]),
+                    make(OpCodeType::JumpNotTruthy, vec![12]),
+                    make(OpCodeType::False, vec![]),
+                    make(OpCodeType::JumpNotTruthy, vec![12]),
+                    make(OpCodeType::True, vec![]),
+                    make(OpCodeType::Jump, vec![13]),
+                    make(OpCodeType::False, vec![]),
+                    make(OpCodeType::Pop, vec![]),
+                ],
+            },
+            TestCase {
+                input: String::from("false || true"),
+                expected_constants: vec![],
+                expected_instructions: vec![
+                    make(OpCodeType::False, vec![]),
+                    make(OpCodeType::Bang, vec![]),
+                    make(OpCodeType::JumpNotTruthy, vec![9]),
+                    make(OpCodeType::True, vec![]),
+                    make(OpCodeType::JumpNotTruthy, vec![13]),
+                    make(OpCodeType::True, vec![]),
+                    make(OpCodeType::Jump, vec![14]),
+                    make(OpCodeType::False, vec![]),
+                    make(OpCodeType::Pop, vec![]),
+                ],
+            },
         ];
 
         run_compiler_tests(expected);
@@ -932,11 +1412,83 @@ two;
                     make(OpCodeType::Pop, vec![]),
                 ],
             },
+            TestCase {
+                input: String::from(
+                    "
+let one = 1;
+let one = 2;
+one;
+",
+                ),
+                expected_constants: vec![TestCaseResult::Integer(1), TestCaseResult::Integer(2)],
+                expected_instructions: vec![
+                    make(OpCodeType::Constant, vec![0]),
+                    make(OpCodeType::SetGlobal, vec![0]),
+                    make(OpCodeType::Constant, vec![1]),
+                    make(OpCodeType::SetGlobal, vec![0]),
+                    make(OpCodeType::GetGlobal, vec![0]),
+                    make(OpCodeType::Pop, vec![]),
+                ],
+            },
         ];
 
         run_compiler_tests(expected);
     }
 
+    #[test]
+    fn undefined_identifier_test() {
+        let expected = vec![
+            ("let one = 1; two;", "two"),
+            ("let one = one;", "one"),
+            ("fn() { let num = num; num }", "num"),
+            ("g(); let g = fn() { 1 };", "g"),
+            ("let f = fn() { g() }; f(); let g = fn() { 1 };", "g"),
+            ("let f = fn() { g() }; let x = f(); let g = fn() { 1 };", "g"),
+            ("let f = fn() { g() };", "g"),
+        ];
+
+        for (input, ident) in expected {
+            let lexer = Lexer::new(String::from(input));
+            let mut parser = Parser::new(lexer);
+            let program = parser.parse_program().unwrap();
+
+            let mut compiler = Compiler::new();
+
+            assert_eq!(
+                compiler.compile(program).map_err(|err| err.to_string()),
+                Err(format!("couldn't resolve identifier value: \"{ident}\""))
+            );
+        }
+    }
+
+    #[test]
+    fn assign_expression_error_test() {
+        let expected = vec![
+            ("x = 1;", "couldn't assign to undefined identifier: \"x\""),
+            (
+                "fn(a) { fn() { a = 2; } }",
+                "couldn't assign to \"a\", only global and local bindings could be reassigned",
+            ),
+            (
+                "len = 1;",
+                "couldn't assign to \"len\", only global and local bindings could be reassigned",
+            ),
+        ];
+
+        for (input, error) in expected {
+            let lexer = Lexer::new(String::from(input));
+            let mut parser = Parser::new(lexer);
+            let program = parser.parse_program().unwrap();
+
+            let mut compiler = Compiler::new();
+
+            assert_eq!(
+                compiler.compile(program).map_err(|err| err.to_string()),
+                Err(String::from(error))
+            );
+        }
+    }
+
     #[test]
     fn string_expression_test() {
         let expected = vec![
@@ -1021,6 +1573,21 @@ two;
         run_compiler_tests(expected);
     }
 
+    #[test]
+    fn array_literal_too_large_test() {
+        let input = format!("[{}]", vec!["true"; 65536].join(", "));
+        let program = Parser::new(Lexer::new(input)).parse_program().unwrap();
+
+        let mut compiler = Compiler::new();
+
+        assert_eq!(
+            compiler.compile(program).map_err(|err| err.to_string()),
+            Err(String::from(
+                "couldn't emit OpArray, operand 65536 doesn't fit into 2 bytes"
+            ))
+        );
+    }
+
     #[test]
     fn hash_literal_test() {
         let expected = vec![
@@ -1076,6 +1643,23 @@ two;
                     make(OpCodeType::Pop, vec![]),
                 ],
             },
+            TestCase {
+                input: String::from("{3: 4, 1: 2}"),
+                expected_constants: vec![
+                    TestCaseResult::Integer(3),
+                    TestCaseResult::Integer(4),
+                    TestCaseResult::Integer(1),
+                    TestCaseResult::Integer(2),
+                ],
+                expected_instructions: vec![
+                    make(OpCodeType::Constant, vec![0]),
+                    make(OpCodeType::Constant, vec![1]),
+                    make(OpCodeType::Constant, vec![2]),
+                    make(OpCodeType::Constant, vec![3]),
+                    make(OpCodeType::Hash, vec![4]),
+                    make(OpCodeType::Pop, vec![]),
+                ],
+            },
         ];
 
         run_compiler_tests(expected);
@@ -1,2 +1,3 @@
+pub mod bytecode;
 pub mod compiler;
 pub mod symbol_table;
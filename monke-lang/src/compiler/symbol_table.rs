@@ -24,6 +24,7 @@ pub struct SymbolTable {
     pub outer: Option<SymbolTableRef>,
     pub definitions_num: usize,
     pub free_symbols: Vec<Symbol>,
+    pub builtins_num: usize,
 }
 
 pub type SymbolTableRef = Rc<RefCell<SymbolTable>>;
@@ -46,17 +47,26 @@ impl SymbolTable {
             outer: None,
             definitions_num: 0,
             free_symbols: vec![],
+            builtins_num: 0,
         }))
     }
 
     pub fn define(&mut self, name: String) -> Symbol {
+        let scope = match self.outer {
+            Some(_) => SymbolScope::Local,
+            None => SymbolScope::Global,
+        };
+
+        if let Some(existing) = self.store.get(&name) {
+            if existing.scope == scope {
+                return existing.clone();
+            }
+        }
+
         let symbol = Symbol {
             name: name.clone(),
             index: self.definitions_num,
-            scope: match self.outer {
-                Some(_) => SymbolScope::Local,
-                None => SymbolScope::Global,
-            },
+            scope,
         };
 
         self.store.insert(name, symbol.clone());
@@ -103,6 +113,7 @@ impl SymbolTable {
             scope: SymbolScope::Builtin,
         };
 
+        self.builtins_num = self.builtins_num.max(index + 1);
         self.store.insert(name, symbol.clone());
         symbol
     }
@@ -171,6 +182,28 @@ mod test {
         assert_eq!(expected["b"], b);
     }
 
+    #[test]
+    fn redefine_test() {
+        let global = SymbolTable::new();
+        let a = global.borrow_mut().define(String::from("a"));
+        global.borrow_mut().define(String::from("b"));
+
+        assert_eq!(a, global.borrow_mut().define(String::from("a")));
+        assert_eq!(global.borrow().definitions_num, 2);
+
+        global.borrow_mut().populate_symbol_table_with_builtins();
+        let len = global.borrow_mut().define(String::from("len"));
+
+        assert_eq!(
+            len,
+            Symbol {
+                name: String::from("len"),
+                scope: SymbolScope::Global,
+                index: 2,
+            }
+        );
+    }
+
     #[test]
     fn resolve_global_test() {
         let table = SymbolTable::new();
@@ -1,6 +1,12 @@
-use std::{cell::RefCell, collections::HashMap, hash::Hash, ops::Deref, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    hash::Hash,
+    ops::Deref,
+    rc::{Rc, Weak},
+};
 
-use crate::types::Object;
+use crate::types::{BuiltinFunction, Function, Object};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Environment {
@@ -27,6 +33,35 @@ impl Hash for OuterEnvWrapper {
     }
 }
 
+#[derive(Debug, Clone)]
+pub enum FunctionEnv {
+    Strong(OuterEnvWrapper),
+    Weak(Weak<RefCell<Environment>>),
+}
+
+impl FunctionEnv {
+    pub fn upgrade(&self) -> Option<EnvironmentRef> {
+        match self {
+            FunctionEnv::Strong(env) => Some(env.0.clone()),
+            FunctionEnv::Weak(env) => env.upgrade(),
+        }
+    }
+}
+
+impl PartialEq for FunctionEnv {
+    fn eq(&self, other: &Self) -> bool {
+        self.upgrade() == other.upgrade()
+    }
+}
+
+impl Eq for FunctionEnv {}
+
+impl Hash for FunctionEnv {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.upgrade().map(OuterEnvWrapper).hash(state)
+    }
+}
+
 impl Environment {
     pub fn new() -> Environment {
         Environment {
@@ -44,7 +79,7 @@ impl Environment {
 
     pub fn get(&self, name: &String) -> Option<Object> {
         match self.store.get(name) {
-            Some(value) => Some(value).cloned(),
+            Some(value) => Some(attach(value.clone())),
             None => match &self.outer {
                 Some(value) => value.borrow().get(name),
                 None => None,
@@ -53,9 +88,58 @@ impl Environment {
     }
 
     pub fn set(&mut self, name: String, val: Object) -> Object {
-        self.store.insert(name, val.clone());
+        self.store.insert(name, self.detach(val.clone()));
         val
     }
+
+    pub fn register_builtin(&mut self, name: &str, builtin: BuiltinFunction) {
+        self.store
+            .insert(String::from(name), Object::Builtin(builtin));
+    }
+
+    pub fn assign(&mut self, name: &String, val: Object) -> Option<Object> {
+        let detached = self.detach(val.clone());
+
+        match self.store.get_mut(name) {
+            Some(value) => {
+                *value = detached;
+                Some(val)
+            }
+            None => match &self.outer {
+                Some(outer) => outer.borrow_mut().assign(name, val),
+                None => None,
+            },
+        }
+    }
+
+    // a function stored in the environment it has captured (e.g. a recursive closure) would
+    // keep that environment alive forever, so the store keeps only a weak link back to it.
+    // cycles going through arrays, hashes or closures of nested scopes are still not collected
+    fn detach(&self, val: Object) -> Object {
+        match val {
+            Object::Function(func) => match &func.env {
+                FunctionEnv::Strong(env) if std::ptr::eq(env.as_ptr(), self) => {
+                    let env = FunctionEnv::Weak(Rc::downgrade(env));
+                    Object::Function(Function { env, ..func })
+                }
+                _ => Object::Function(func),
+            },
+            val => val,
+        }
+    }
+}
+
+fn attach(val: Object) -> Object {
+    match val {
+        Object::Function(func) => match func.env.upgrade() {
+            Some(env) => {
+                let env = FunctionEnv::Strong(OuterEnvWrapper(env));
+                Object::Function(Function { env, ..func })
+            }
+            None => Object::Function(func),
+        },
+        val => val,
+    }
 }
 
 impl Hash for Environment {
@@ -1,22 +1,66 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    io::{self, Write},
+    rc::Rc,
+};
 
 use crate::{
-    builtins::get_builtin_function,
-    lexer::token::Token,
+    builtins::{get_builtin_function, BuiltinContext, MAX_BUILTIN_CALL_DEPTH},
+    lexer::token::{Position, Token},
     parser::ast::{
         CallExpression, Expression, HashLiteral, IfExpression, IndexExpression, InfixExpression,
-        Program, Statement,
+        Program, Statement, WhileStatement,
     },
-    result::MonkeyResult,
-    types::{Array, Boolean, Function, HashTable, Integer, Null, Object, Return, Str},
+    result::{MonkeyError, MonkeyResult},
+    types::{Array, Boolean, Float, Function, HashTable, Integer, Null, Object, Return, Str},
 };
 
 use super::{
     ast_traversal::{AstTraverse, AstTraverseNodeRef},
-    environment::{Environment, EnvironmentRef, OuterEnvWrapper},
+    environment::{Environment, EnvironmentRef, FunctionEnv, OuterEnvWrapper},
 };
 
-pub fn eval(program: Program, env: &EnvironmentRef) -> MonkeyResult<Object> {
+pub const MAX_CALL_DEPTH: usize = 1024;
+
+#[derive(Debug, Clone, Copy)]
+struct CallLimits {
+    max_call_depth: usize,
+    max_builtin_call_depth: usize,
+}
+
+pub fn eval(program: Program, env: &EnvironmentRef) -> Result<Object, MonkeyError> {
+    eval_with_output(program, env, &mut io::stdout())
+}
+
+pub fn eval_with_output(
+    program: Program,
+    env: &EnvironmentRef,
+    output: &mut dyn Write,
+) -> Result<Object, MonkeyError> {
+    eval_with_max_call_depth(program, env, output, MAX_CALL_DEPTH)
+}
+
+pub fn eval_with_max_call_depth(
+    program: Program,
+    env: &EnvironmentRef,
+    output: &mut dyn Write,
+    max_call_depth: usize,
+) -> Result<Object, MonkeyError> {
+    let limits = CallLimits {
+        max_call_depth,
+        max_builtin_call_depth: MAX_BUILTIN_CALL_DEPTH,
+    };
+
+    eval_with_limits(program, env, output, limits)
+}
+
+fn eval_with_limits(
+    program: Program,
+    env: &EnvironmentRef,
+    output: &mut dyn Write,
+    limits: CallLimits,
+) -> Result<Object, MonkeyError> {
     let mut nodes_stack = vec![AstTraverse::new(program, None)];
     let mut env_stack = vec![Rc::clone(env)];
 
@@ -27,7 +71,12 @@ pub fn eval(program: Program, env: &EnvironmentRef) -> MonkeyResult<Object> {
 
         match nodes_stack.pop().unwrap() {
             AstTraverse::Node(cur_node) => {
-                let evaluated_node = eval_ast_node(&cur_node, &mut nodes_stack, &mut env_stack)?;
+                let evaluated_node =
+                    eval_ast_node(&cur_node, &mut nodes_stack, &mut env_stack, output, limits)
+                        .map_err(|message| MonkeyError::Runtime {
+                            message,
+                            position: node_position(&cur_node.borrow().node),
+                        })?;
 
                 match evaluated_node {
                     Some(obj) => {
@@ -55,10 +104,20 @@ pub fn eval(program: Program, env: &EnvironmentRef) -> MonkeyResult<Object> {
     }
 }
 
+fn node_position(node: &Program) -> Option<Position> {
+    match node {
+        Program::Statement(statement) => Some(statement.span().start),
+        Program::Expression(expression) => Some(expression.span().start),
+        Program::Statements(_) => None,
+    }
+}
+
 fn eval_ast_node(
     cur_node: &AstTraverseNodeRef,
     nodes_stack: &mut Vec<AstTraverse>,
     env_stack: &mut Vec<EnvironmentRef>,
+    output: &mut dyn Write,
+    limits: CallLimits,
 ) -> MonkeyResult<Option<Object>> {
     let env = env_stack.last().unwrap();
 
@@ -97,6 +156,9 @@ fn eval_ast_node(
                     }
                 }
             }
+            Statement::While(while_statement) => {
+                eval_while_statement(statement, while_statement, cur_node, nodes_stack)
+            }
             Statement::Let(let_statement) => match cur_node.borrow().evaluated_children.last() {
                 Some(let_value) => {
                     let value_key = let_statement.name.token.to_string();
@@ -121,6 +183,9 @@ fn eval_ast_node(
             Expression::IntegerLiteral(int) => {
                 Ok(Some(Object::Integer(Integer { value: int.value })))
             }
+            Expression::FloatLiteral(float) => {
+                Ok(Some(Object::Float(Float { value: float.value })))
+            }
             Expression::Boolean(bool) => Ok(Some(Object::Boolean(Boolean { value: bool.value }))),
             Expression::Prefix(prefix) => match cur_node.borrow().evaluated_children.last() {
                 Some(right) => Ok(Some(eval_prefix_expression(&prefix.token, right)?)),
@@ -143,18 +208,18 @@ fn eval_ast_node(
                     Some(obj) => Ok(Some(obj)),
                     None => match get_builtin_function(&value_key) {
                         Some(builtin) => Ok(Some(builtin)),
-                        None => Err(format!(
-                            "unable to evaluate identifier, identifier \"{value_key}\" not found"
-                        ))?,
+                        None => Err(format!("identifier not found: {value_key}"))?,
                     },
                 }
             }
             Expression::FunctionLiteral(func) => Ok(Some(Object::Function(Function {
                 parameters: func.parameters.clone(),
                 body: func.body.clone(),
-                env: OuterEnvWrapper(env.clone()),
+                env: FunctionEnv::Strong(OuterEnvWrapper(env.clone())),
             }))),
-            Expression::Call(call) => apply_function(call, cur_node, nodes_stack, env_stack),
+            Expression::Call(call) => {
+                apply_function(call, cur_node, nodes_stack, env_stack, output, limits)
+            }
             Expression::StringLiteral(string) => Ok(Some(Object::String(Str {
                 value: string.token.to_string(),
             }))),
@@ -178,6 +243,27 @@ fn eval_ast_node(
             Expression::HashLiteral(hash_literal) => {
                 eval_hash_literal(hash_literal, cur_node, nodes_stack)
             }
+            Expression::Assign(assign) => match cur_node.borrow().evaluated_children.last() {
+                Some(value) => {
+                    let value_key = assign.name.token.to_string();
+
+                    match env.borrow_mut().assign(&value_key, value.clone()) {
+                        Some(value) => Ok(Some(value)),
+                        None => Err(format!(
+                            "unable to assign to undefined identifier: {value_key}"
+                        )),
+                    }
+                }
+                None => {
+                    add_current_and_new_nodes_to_stack(
+                        Rc::clone(&assign.value).into(),
+                        cur_node,
+                        nodes_stack,
+                    );
+
+                    Ok(None)
+                }
+            },
         },
     }
 }
@@ -189,31 +275,21 @@ fn eval_hash_literal(
 ) -> MonkeyResult<Option<Object>> {
     match cur_node.borrow().evaluated_children.len() {
         l if l < 2 * hash_literal.pairs.len() && l & 0x1 == 0 => {
-            let (key, _) = hash_literal
-                .pairs
-                .iter()
-                .enumerate()
-                .find(|(idx, _)| *idx == l / 2)
-                .unwrap()
-                .1;
+            let (key, _) = hash_literal.pairs.get(l / 2).unwrap();
 
             add_current_and_new_nodes_to_stack(Rc::clone(key).into(), cur_node, nodes_stack);
 
             Ok(None)
         }
         l if l < 2 * hash_literal.pairs.len() && l & 0x1 == 1 => {
-            match cur_node.borrow().evaluated_children.last().unwrap() {
-                Object::String(_) | Object::Integer(_) | Object::Boolean(_) => (),
-                actual => return Err(format!("unable to evaluate hash literal; only Integer, String or Boolean could be used as key, but got \"{actual}\"")),
-            }
-
-            let (_, value) = hash_literal
-                .pairs
-                .iter()
-                .enumerate()
-                .find(|(idx, _)| *idx == l / 2)
+            cur_node
+                .borrow()
+                .evaluated_children
+                .last()
                 .unwrap()
-                .1;
+                .validate_hash_key()?;
+
+            let (_, value) = hash_literal.pairs.get(l / 2).unwrap();
 
             add_current_and_new_nodes_to_stack(Rc::clone(value).into(), cur_node, nodes_stack);
 
@@ -288,21 +364,17 @@ fn eval_index_expression(
                 .clone();
 
             match (left, index) {
-                (Object::Array(array), Object::Integer(idx)) => {
-                    let max_idx = array.elements.len() - 1;
-
-                    if idx.value < 0 || idx.value as usize > max_idx {
-                        return Ok(Some(Object::Null(Null {})));
-                    }
-
-                    Ok(Some(
-                        array.elements.get(idx.value as usize).cloned().unwrap(),
-                    ))
+                (Object::Array(array), Object::Integer(idx)) => Ok(Some(
+                    array
+                        .get(idx.value)
+                        .cloned()
+                        .unwrap_or(Object::Null(Null {})),
+                )),
+                (Object::String(string), Object::Integer(idx)) => {
+                    Ok(Some(string.get(idx.value).unwrap_or(Object::Null(Null {}))))
                 }
                 (Object::HashTable(hash_table), idx) => {
-                    match idx {
-                    Object::String(_) | Object::Integer(_) | Object::Boolean(_) => (),
-                    actual => return Err(format!("unable to index hash table; only Integer, String or Boolean could be used as key, but got \"{actual}\"")), };
+                    idx.validate_hash_key()?;
 
                     Ok(Some(
                         hash_table
@@ -335,12 +407,38 @@ fn eval_infix_expression(
             Ok(None)
         }
         1 => {
-            add_current_and_new_nodes_to_stack(
-                Rc::clone(&infix.right).into(),
-                cur_node,
-                nodes_stack,
-            );
-            Ok(None)
+            let left_is_truthy = cur_node
+                .borrow()
+                .evaluated_children
+                .last()
+                .unwrap()
+                .is_truthy();
+
+            match (&infix.token, left_is_truthy) {
+                (Token::And, false) | (Token::Or, true) => Ok(Some(Object::Boolean(Boolean {
+                    value: left_is_truthy,
+                }))),
+                _ => {
+                    add_current_and_new_nodes_to_stack(
+                        Rc::clone(&infix.right).into(),
+                        cur_node,
+                        nodes_stack,
+                    );
+                    Ok(None)
+                }
+            }
+        }
+        _ if infix.token == Token::And || infix.token == Token::Or => {
+            let right_is_truthy = cur_node
+                .borrow()
+                .evaluated_children
+                .last()
+                .unwrap()
+                .is_truthy();
+
+            Ok(Some(Object::Boolean(Boolean {
+                value: right_is_truthy,
+            })))
         }
         _ => {
             let left = cur_node
@@ -369,7 +467,11 @@ fn apply_function(
     cur_node: &AstTraverseNodeRef,
     nodes_stack: &mut Vec<AstTraverse>,
     env_stack: &mut Vec<EnvironmentRef>,
+    output: &mut dyn Write,
+    limits: CallLimits,
 ) -> MonkeyResult<Option<Object>> {
+    let call_depth = env_stack.len() - 1;
+
     match cur_node.borrow().evaluated_children.len() {
         0 => {
             add_current_and_new_nodes_to_stack(
@@ -403,7 +505,26 @@ fn apply_function(
 
             match function {
                 Object::Function(func) => {
-                    env_stack.push(extend_function_environment(func.clone(), args));
+                    validate_arguments_num(&func, &args)?;
+
+                    let env = extend_function_environment(func.clone(), args)?;
+
+                    if let Some(caller) = take_tail_call_frame(cur_node, nodes_stack) {
+                        env_stack.pop();
+                        env_stack.push(env);
+                        nodes_stack.push(AstTraverse::new(
+                            Rc::clone(&func.body).into(),
+                            Some(AstTraverse::Node(caller)),
+                        ));
+
+                        return Ok(None);
+                    }
+
+                    if call_depth >= limits.max_call_depth {
+                        return Err(String::from("maximum call depth exceeded"));
+                    }
+
+                    env_stack.push(env);
                     add_current_and_new_nodes_to_stack(
                         Rc::clone(&func.body).into(),
                         cur_node,
@@ -412,9 +533,18 @@ fn apply_function(
 
                     Ok(None)
                 }
-                Object::Builtin(builtin) => Ok(Some(builtin.0(args)?)),
+                Object::Builtin(builtin) => Ok(Some(builtin.0(
+                    args,
+                    &mut EvalContext {
+                        output,
+                        limits: CallLimits {
+                            max_call_depth: limits.max_call_depth.saturating_sub(call_depth),
+                            ..limits
+                        },
+                    },
+                )?)),
                 actual => Err(format!(
-                    "unable to evaluate function call, function excpected, but got \"{actual}\""
+                    "unable to evaluate function call, function expected, but got \"{actual}\""
                 )),
             }
         }
@@ -430,27 +560,151 @@ fn apply_function(
     }
 }
 
-fn extend_function_environment(func: Function, args: Vec<Object>) -> EnvironmentRef {
-    let mut env = Environment::new_outer(func.env.0);
+fn take_tail_call_frame(
+    cur_node: &AstTraverseNodeRef,
+    nodes_stack: &mut Vec<AstTraverse>,
+) -> Option<AstTraverseNodeRef> {
+    let parent_of = |node: &AstTraverseNodeRef| {
+        node.borrow()
+            .parent_node
+            .as_ref()
+            .and_then(|parent| parent.as_node().cloned())
+    };
+
+    let mut passed_nodes = 0;
+    let mut after_return = false;
+    let mut node = parent_of(cur_node)?;
+
+    loop {
+        let children_num = node.borrow().evaluated_children.len();
+
+        let is_tail = match &node.borrow().node {
+            Program::Statement(statement) => match statement.as_ref() {
+                Statement::Expression(_) => true,
+                Statement::Return(_) => {
+                    after_return = true;
+                    true
+                }
+                Statement::Block(block) => {
+                    after_return || children_num + 1 == block.statements.len()
+                }
+                _ => false,
+            },
+            Program::Expression(expr) => match expr.as_ref() {
+                Expression::If(_) => children_num == 1,
+                Expression::Call(call) if children_num == call.arguments.len() + 1 => break,
+                _ => false,
+            },
+            Program::Statements(_) => false,
+        };
+
+        if !is_tail {
+            return None;
+        }
+
+        passed_nodes += 1;
+        node = parent_of(&node)?;
+    }
+
+    let caller_idx = nodes_stack.len().checked_sub(passed_nodes + 1)?;
+
+    match nodes_stack[caller_idx].as_node() {
+        Some(caller) if Rc::ptr_eq(caller, &node) => {
+            nodes_stack.truncate(caller_idx + 1);
+            Some(node)
+        }
+        _ => None,
+    }
+}
+
+fn validate_arguments_num(func: &Function, args: &[Object]) -> MonkeyResult<()> {
+    if args.len() != func.parameters.len() {
+        return Err(format!(
+            "wrong number of arguments: want={}, got={}",
+            func.parameters.len(),
+            args.len()
+        ));
+    }
+
+    Ok(())
+}
+
+struct EvalContext<'a> {
+    output: &'a mut dyn Write,
+    limits: CallLimits,
+}
+
+impl BuiltinContext for EvalContext<'_> {
+    fn output(&mut self) -> &mut dyn Write {
+        self.output
+    }
+
+    fn call_function(&mut self, func: Object, args: Vec<Object>) -> MonkeyResult<Object> {
+        match func {
+            Object::Function(func) => {
+                validate_arguments_num(&func, &args)?;
+
+                if self.limits.max_call_depth == 0 || self.limits.max_builtin_call_depth == 0 {
+                    return Err(String::from("maximum call depth exceeded"));
+                }
+
+                let env = extend_function_environment(func.clone(), args)?;
+                let limits = CallLimits {
+                    max_call_depth: self.limits.max_call_depth - 1,
+                    max_builtin_call_depth: self.limits.max_builtin_call_depth - 1,
+                };
+                let evaluated =
+                    eval_with_limits(Rc::clone(&func.body).into(), &env, self.output, limits)
+                        .map_err(|err| err.message().to_string())?;
+
+                match evaluated {
+                    Object::Return(return_obj) => Ok(*return_obj.value),
+                    _ => Ok(evaluated),
+                }
+            }
+            Object::Builtin(builtin) => builtin.0(args, self),
+            actual => Err(format!(
+                "unable to evaluate function call, function expected, but got \"{actual}\""
+            )),
+        }
+    }
+}
+
+fn extend_function_environment(func: Function, args: Vec<Object>) -> MonkeyResult<EnvironmentRef> {
+    let outer = func
+        .env
+        .upgrade()
+        .ok_or_else(|| String::from("unable to call function, its environment was dropped"))?;
+    let mut env = Environment::new_outer(outer);
 
-    for (idx, param) in func.parameters.iter().enumerate() {
-        env.set(param.token.to_string(), args.get(idx).unwrap().clone());
+    for (param, arg) in func.parameters.iter().zip(args) {
+        env.set(param.token.to_string(), arg);
     }
 
-    Rc::new(RefCell::new(env))
+    Ok(Rc::new(RefCell::new(env)))
 }
 
 fn eval_prefix_expression(token: &Token, right: &Object) -> MonkeyResult<Object> {
     match token {
-        Token::Bang => match right {
-            Object::Boolean(bool) => Ok(Object::Boolean(Boolean { value: !bool.value })),
-            Object::Null(_) => Ok(Object::Boolean(Boolean { value: true })),
-            _ => Ok(Object::Boolean(Boolean { value: false })),
-        },
+        Token::Bang => Ok(Object::Boolean(Boolean {
+            value: !right.is_truthy(),
+        })),
         Token::Minus => match right {
-            Object::Integer(int) => Ok(Object::Integer(Integer { value: -int.value })),
+            Object::Integer(int) => match int.value.checked_neg() {
+                Some(value) => Ok(Object::Integer(Integer { value })),
+                None => Err(String::from("integer overflow in negation")),
+            },
+            Object::Float(float) => Ok(Object::Float(Float {
+                value: -float.value,
+            })),
+            expr => Err(format!(
+                "unable to evaluate prefix expression, Integer or Float number must follow Minus token, but got \"{expr}\""
+            )),
+        },
+        Token::Plus => match right {
+            Object::Integer(_) | Object::Float(_) => Ok(right.clone()),
             expr => Err(format!(
-                "unable to evaluate prefix expression, Integer number must follow Minus token, but got \"{expr}\""
+                "unable to evaluate prefix expression, Integer or Float number must follow Plus token, but got \"{expr}\""
             )),
         },
         t => Err(format!(
@@ -462,18 +716,31 @@ fn eval_prefix_expression(token: &Token, right: &Object) -> MonkeyResult<Object>
 fn calculate_infix_expression(token: &Token, left: Object, right: Object) -> MonkeyResult<Object> {
     match (left, right) {
         (Object::Integer(int_left), Object::Integer(int_right)) => match token {
-            Token::Plus => Ok(Object::Integer(Integer {
-                value: int_left.value + int_right.value,
-            })),
-            Token::Minus => Ok(Object::Integer(Integer {
-                value: int_left.value - int_right.value,
-            })),
-            Token::Asterisk => Ok(Object::Integer(Integer {
-                value: int_left.value * int_right.value,
-            })),
-            Token::Slash => Ok(Object::Integer(Integer {
-                value: int_left.value / int_right.value,
-            })),
+            Token::Plus => match int_left.value.checked_add(int_right.value) {
+                Some(value) => Ok(Object::Integer(Integer { value })),
+                None => Err(String::from("integer overflow in addition")),
+            },
+            Token::Minus => match int_left.value.checked_sub(int_right.value) {
+                Some(value) => Ok(Object::Integer(Integer { value })),
+                None => Err(String::from("integer overflow in subtraction")),
+            },
+            Token::Asterisk => match int_left.value.checked_mul(int_right.value) {
+                Some(value) => Ok(Object::Integer(Integer { value })),
+                None => Err(String::from("integer overflow in multiplication")),
+            },
+            Token::Slash => match int_right.value {
+                0 => Err(String::from("division by zero")),
+                _ => match int_left.value.checked_div(int_right.value) {
+                    Some(value) => Ok(Object::Integer(Integer { value })),
+                    None => Err(String::from("integer overflow in division")),
+                },
+            },
+            Token::Percent => match int_right.value {
+                0 => Err(String::from("division by zero")),
+                _ => Ok(Object::Integer(Integer {
+                    value: int_left.value.wrapping_rem(int_right.value),
+                })),
+            },
             Token::Lt => Ok(Object::Boolean(Boolean {
                 value: int_left.value < int_right.value,
             })),
@@ -487,9 +754,17 @@ fn calculate_infix_expression(token: &Token, left: Object, right: Object) -> Mon
                 value: int_left.value != int_right.value,
             })),
             t => Err(format!(
-                "unable to evaluate infix expression for Integers; +,-,*,/,<,>,==,!= Tokens expected, but got \"{t}\""
+                "unable to evaluate infix expression for Integers; +,-,*,/,%,<,>,==,!= Tokens expected, but got \"{t}\""
             )),
         },
+        (
+            left @ (Object::Integer(_) | Object::Float(_)),
+            right @ (Object::Integer(_) | Object::Float(_)),
+        ) => calculate_float_infix_expression(
+            token,
+            left.as_float().unwrap(),
+            right.as_float().unwrap(),
+        ),
         (Object::Boolean(bool_left),Object::Boolean(bool_right)) => match token {
             Token::Eq => Ok(Object::Boolean(Boolean { value: bool_left.value == bool_right.value })),
             Token::Ne=> Ok(Object::Boolean(Boolean { value: bool_left.value != bool_right.value })),
@@ -499,10 +774,33 @@ fn calculate_infix_expression(token: &Token, left: Object, right: Object) -> Mon
         },
         (Object::String(string_left), Object::String(string_right)) => match token {
             Token::Plus => Ok(Object::String(Str { value: format!("{string_left}{string_right}") })),
-            t => Err(format!("unable to evaluate infix expression for Strings; + Token expected, but got \"{t}\""))
+            Token::Eq => Ok(Object::Boolean(Boolean { value: string_left.value == string_right.value })),
+            Token::Ne => Ok(Object::Boolean(Boolean { value: string_left.value != string_right.value })),
+            Token::Lt => Ok(Object::Boolean(Boolean { value: string_left.value < string_right.value })),
+            Token::Gt => Ok(Object::Boolean(Boolean { value: string_left.value > string_right.value })),
+            t => Err(format!("unable to evaluate infix expression for Strings; +,<,>,== or != Tokens expected, but got \"{t}\""))
         }
         (left, right) => Err(format!(
-            "unable to evaluate infix expression; Integers, Booleans or Strings expected, but got \"{left}\" \"{right}\""
+            "unable to evaluate infix expression; Integers, Floats, Booleans or Strings expected, but got \"{left}\" \"{right}\""
+        )),
+    }
+}
+
+fn calculate_float_infix_expression(token: &Token, left: f64, right: f64) -> MonkeyResult<Object> {
+    match token {
+        Token::Plus => Ok(Object::Float(Float { value: left + right })),
+        Token::Minus => Ok(Object::Float(Float { value: left - right })),
+        Token::Asterisk => Ok(Object::Float(Float { value: left * right })),
+        Token::Slash if right == 0.0 => Err(String::from("division by zero")),
+        Token::Slash => Ok(Object::Float(Float { value: left / right })),
+        Token::Percent if right == 0.0 => Err(String::from("division by zero")),
+        Token::Percent => Ok(Object::Float(Float { value: left % right })),
+        Token::Lt => Ok(Object::Boolean(Boolean { value: left < right })),
+        Token::Gt => Ok(Object::Boolean(Boolean { value: left > right })),
+        Token::Eq => Ok(Object::Boolean(Boolean { value: left == right })),
+        Token::Ne => Ok(Object::Boolean(Boolean { value: left != right })),
+        t => Err(format!(
+            "unable to evaluate infix expression for Floats; +,-,*,/,%,<,>,==,!= Tokens expected, but got \"{t}\""
         )),
     }
 }
@@ -535,10 +833,12 @@ fn eval_if_expression(
                 return None;
             }
 
-            let is_truthy = match cur_node.borrow().evaluated_children.last().unwrap() {
-                Object::Boolean(bool) => bool.value,
-                _ => true,
-            };
+            let is_truthy = cur_node
+                .borrow()
+                .evaluated_children
+                .last()
+                .unwrap()
+                .is_truthy();
 
             match is_truthy {
                 true => {
@@ -568,6 +868,62 @@ fn eval_if_expression(
     }
 }
 
+fn eval_while_statement(
+    statement: &Rc<Statement>,
+    while_statement: &WhileStatement,
+    cur_node: &AstTraverseNodeRef,
+    nodes_stack: &mut Vec<AstTraverse>,
+) -> MonkeyResult<Option<Object>> {
+    match cur_node.borrow().evaluated_children.len() {
+        0 => {
+            add_current_and_new_nodes_to_stack(
+                Rc::clone(&while_statement.condition).into(),
+                cur_node,
+                nodes_stack,
+            );
+
+            Ok(None)
+        }
+        1 => {
+            let is_truthy = cur_node
+                .borrow()
+                .evaluated_children
+                .last()
+                .unwrap()
+                .is_truthy();
+
+            match is_truthy {
+                true => {
+                    add_current_and_new_nodes_to_stack(
+                        Rc::clone(&while_statement.body).into(),
+                        cur_node,
+                        nodes_stack,
+                    );
+
+                    Ok(None)
+                }
+                false => Ok(Some(Object::Null(Null {}))),
+            }
+        }
+        _ => {
+            if let Some(Object::Return(return_obj)) = cur_node.borrow().evaluated_children.last() {
+                return Ok(Some(Object::Return(return_obj.clone())));
+            }
+
+            // start the next iteration from a fresh node, so evaluated children don't pile up
+            let parent_node = cur_node
+                .borrow()
+                .parent_node
+                .as_ref()
+                .and_then(|parent| parent.as_node())
+                .map(|parent| AstTraverse::Node(Rc::clone(parent)));
+            nodes_stack.push(AstTraverse::new(Rc::clone(statement).into(), parent_node));
+
+            Ok(None)
+        }
+    }
+}
+
 fn eval_program(
     statements: &Vec<Rc<Statement>>,
     cur_node: &AstTraverseNodeRef,
@@ -575,7 +931,7 @@ fn eval_program(
     unwrap_return: bool,
 ) -> Option<Object> {
     if statements.len() == 0 {
-        return None;
+        return Some(Object::Null(Null {}));
     }
 
     match cur_node.borrow().evaluated_children.len() {
@@ -604,6 +960,8 @@ fn eval_program(
                     cur_node,
                     nodes_stack,
                 );
+
+                return None;
             }
 
             Some(cur_node.borrow().evaluated_children.last().unwrap().clone())
@@ -628,10 +986,15 @@ mod test {
     use std::{cell::RefCell, rc::Rc};
 
     use crate::{
-        evaluator::{environment::Environment, evaluator::eval},
+        builtins::BuiltinContext,
+        evaluator::{
+            environment::Environment,
+            evaluator::{eval, eval_with_max_call_depth, eval_with_output, MAX_CALL_DEPTH},
+        },
         lexer::lexer::Lexer,
         parser::parser::Parser,
-        types::{Boolean, Integer, Null, Object, Str},
+        result::MonkeyResult,
+        types::{Boolean, BuiltinFunction, Integer, Null, Object, Str},
     };
 
     fn evaluate_input(input: String) -> Object {
@@ -659,6 +1022,28 @@ mod test {
         result.unwrap()
     }
 
+    fn evaluate_input_error(input: String) -> String {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        if let Err(err) = &program {
+            println!("{err}");
+        }
+
+        assert!(program.is_ok());
+        let program = program.unwrap();
+
+        let env = Environment::new();
+        let result = eval(program, &Rc::new(RefCell::new(env)));
+
+        match result {
+            Ok(obj) => panic!("error expected, but got {obj}"),
+            Err(err) => err.message().to_string(),
+        }
+    }
+
     #[test]
     fn integer_expression_evaluation_test() {
         let expected = vec![
@@ -666,6 +1051,8 @@ mod test {
             ("10", 10),
             ("-5", -5),
             ("-10", -10),
+            ("+5", 5),
+            ("+(-3)", -3),
             ("5 + 5 + 5 + 5 - 10", 10),
             ("2 * 2 * 2 * 2 * 2", 32),
             ("-50 + 100 + -50", 0),
@@ -677,6 +1064,9 @@ mod test {
             ("3 * 3 * 3 + 10", 37),
             ("3 * (3 * 3) + 10", 37),
             ("(5 + 10 * 2 + 15 / 3) * 2 + -10", 50),
+            ("0xFF", 255),
+            ("0b111", 7),
+            ("0o17 + 0x10 - 0b1", 30),
         ];
 
         for (input, expected_result) in expected {
@@ -687,241 +1077,1186 @@ mod test {
                 actual => panic!("integer expected, but got {actual}"),
             }
         }
+
+        assert_eq!(
+            evaluate_input_error(String::from("+true")),
+            "unable to evaluate prefix expression, Integer or Float number must follow Plus token, but got \"true\""
+        );
     }
 
     #[test]
-    fn boolean_expression_evaluation_test() {
+    fn modulo_evaluation_test() {
         let expected = vec![
-            ("true", true),
-            ("false", false),
-            ("1 < 2", true),
-            ("1 > 2", false),
-            ("1 < 1", false),
-            ("1 > 1", false),
-            ("1 == 1", true),
-            ("1 != 1", false),
-            ("1 == 2", false),
-            ("1 != 2", true),
-            ("true == true", true),
-            ("false == false", true),
-            ("true == false", false),
-            ("true != false", true),
-            ("false != true", true),
-            ("(1 < 2) == true", true),
-            ("(1 < 2) == false", false),
-            ("(1 > 2) == true", false),
-            ("(1 > 2) == false", true),
+            ("10 % 3", 1),
+            ("9 % 3", 0),
+            ("-7 % 3", -1),
+            ("2 + 7 % 4 * 2", 8),
         ];
 
         for (input, expected_result) in expected {
             let result = evaluate_input(input.to_string());
 
             match result {
-                Object::Boolean(bool) => assert_eq!(bool.value, expected_result),
+                Object::Integer(int) => assert_eq!(int.value, expected_result),
                 actual => panic!("integer expected, but got {actual}"),
             }
         }
+
+        assert_eq!(
+            evaluate_input_error(String::from("7 % 0")),
+            "division by zero"
+        );
     }
 
     #[test]
-    fn bang_operator_evaluation_test() {
-        let expected = vec![
-            ("!true", false),
-            ("!false", true),
-            ("!5", false),
-            ("!!true", true),
-            ("!!false", false),
-            ("!!5", true),
-        ];
-
-        for (input, expected_result) in expected {
-            let result = evaluate_input(input.to_string());
+    fn division_by_zero_evaluation_test() {
+        let expected = vec!["5 / 0", "let zero = 1 - 1; 10 / zero", "fn(x) { x / 0 }(1)"];
 
-            match result {
-                Object::Boolean(bool) => assert_eq!(bool.value, expected_result),
-                actual => panic!("integer expected, but got {actual}"),
-            }
+        for input in expected {
+            assert_eq!(evaluate_input_error(input.to_string()), "division by zero");
         }
     }
 
     #[test]
-    fn if_else_evaluation_test() {
+    fn integer_overflow_evaluation_test() {
         let expected = vec![
-            ("if (true) { 10 }", Object::Integer(Integer { value: 10 })),
-            ("if (false) { 10 }", Object::Null(Null {})),
-            ("if (1) { 10 }", Object::Integer(Integer { value: 10 })),
-            ("if (1 < 2) { 10 }", Object::Integer(Integer { value: 10 })),
-            ("if (1 > 2) { 10 }", Object::Null(Null {})),
             (
-                "if (1 > 2) { 10 } else { 20 }",
+                "9223372036854775807 * 2",
+                "integer overflow in multiplication",
+            ),
+            ("9223372036854775807 + 1", "integer overflow in addition"),
+            (
+                "let min = -9223372036854775807 - 1; min - 1",
+                "integer overflow in subtraction",
+            ),
+            (
+                "let min = -9223372036854775807 - 1; min / -1",
+                "integer overflow in division",
+            ),
+            (
+                "let min = -9223372036854775807 - 1; -min",
+                "integer overflow in negation",
+            ),
+        ];
+
+        for (input, error) in expected {
+            assert_eq!(evaluate_input_error(input.to_string()), error);
+        }
+    }
+
+    #[test]
+    fn float_evaluation_test() {
+        let expected = vec![
+            ("2.75", "2.75"),
+            ("1.0 + 2", "3.0"),
+            ("10.0 / 4.0", "2.5"),
+            ("2 * 1.5", "3.0"),
+            ("-1.5 - 1", "-2.5"),
+            ("7.5 % 2", "1.5"),
+            ("1.5 < 2", "true"),
+            ("2.5 > 3.5", "false"),
+            ("2 == 2.0", "true"),
+            ("0.1 != 0.1", "false"),
+        ];
+
+        for (input, expected_result) in expected {
+            let result = evaluate_input(input.to_string());
+            assert_eq!(result.to_string(), expected_result);
+        }
+
+        assert_eq!(
+            evaluate_input_error(String::from("1.0 / 0")),
+            "division by zero"
+        );
+    }
+
+    #[test]
+    fn logical_operators_evaluation_test() {
+        let expected = vec![
+            ("true && true", true),
+            ("true && false", false),
+            ("false && true", false),
+            ("false && false", false),
+            ("true || true", true),
+            ("true || false", true),
+            ("false || true", true),
+            ("false || false", false),
+            ("1 && \"a\"", true),
+            ("if (false) { 1 } || 0", true),
+            ("if (false) { 1 } && true", false),
+            ("false && undefined_fn()", false),
+            ("true || undefined_fn()", true),
+            ("1 < 2 && 2 < 3 || false", true),
+            (
+                "let i = 0; let f = fn() { i = i + 1; true }; false && f(); true || f(); i == 0",
+                true,
+            ),
+        ];
+
+        for (input, expected_result) in expected {
+            assert_eq!(
+                evaluate_input(input.to_string()),
+                Object::Boolean(Boolean {
+                    value: expected_result
+                })
+            );
+        }
+
+        assert_eq!(
+            evaluate_input_error(String::from("true && undefined_fn()")),
+            "identifier not found: undefined_fn"
+        );
+    }
+
+    #[test]
+    fn boolean_expression_evaluation_test() {
+        let expected = vec![
+            ("true", true),
+            ("false", false),
+            ("1 < 2", true),
+            ("1 > 2", false),
+            ("1 < 1", false),
+            ("1 > 1", false),
+            ("1 == 1", true),
+            ("1 != 1", false),
+            ("1 == 2", false),
+            ("1 != 2", true),
+            ("true == true", true),
+            ("false == false", true),
+            ("true == false", false),
+            ("true != false", true),
+            ("false != true", true),
+            ("(1 < 2) == true", true),
+            ("(1 < 2) == false", false),
+            ("(1 > 2) == true", false),
+            ("(1 > 2) == false", true),
+        ];
+
+        for (input, expected_result) in expected {
+            let result = evaluate_input(input.to_string());
+
+            match result {
+                Object::Boolean(bool) => assert_eq!(bool.value, expected_result),
+                actual => panic!("integer expected, but got {actual}"),
+            }
+        }
+
+        let expected_errors = vec![
+            ("true > false", ">"),
+            ("true < false", "<"),
+            ("(1 < 2) > (2 < 3)", ">"),
+        ];
+
+        for (input, operator) in expected_errors {
+            assert_eq!(
+                evaluate_input_error(input.to_string()),
+                format!("unable to evaluate infix expression for Booleans; == or != Tokens expected, but got \"{operator}\"")
+            );
+        }
+    }
+
+    #[test]
+    fn bang_operator_evaluation_test() {
+        let expected = vec![
+            ("!true", false),
+            ("!false", true),
+            ("!5", false),
+            ("!!true", true),
+            ("!!false", false),
+            ("!!5", true),
+        ];
+
+        for (input, expected_result) in expected {
+            let result = evaluate_input(input.to_string());
+
+            match result {
+                Object::Boolean(bool) => assert_eq!(bool.value, expected_result),
+                actual => panic!("integer expected, but got {actual}"),
+            }
+        }
+    }
+
+    #[test]
+    fn if_else_evaluation_test() {
+        let expected = vec![
+            ("if (true) { 10 }", Object::Integer(Integer { value: 10 })),
+            ("if (false) { 10 }", Object::Null(Null {})),
+            ("if (1) { 10 }", Object::Integer(Integer { value: 10 })),
+            ("if (1 < 2) { 10 }", Object::Integer(Integer { value: 10 })),
+            ("if (1 > 2) { 10 }", Object::Null(Null {})),
+            (
+                "if (1 > 2) { 10 } else { 20 }",
+                Object::Integer(Integer { value: 20 }),
+            ),
+            (
+                "if (1 < 2) { 10 } else { 20 }",
+                Object::Integer(Integer { value: 10 }),
+            ),
+            ("if (true) { }", Object::Null(Null {})),
+            ("if (false) { 10 } else { }", Object::Null(Null {})),
+            ("fn() { }()", Object::Null(Null {})),
+            (
+                "if ((if (false) { 10 })) { 10 } else { 20 }",
                 Object::Integer(Integer { value: 20 }),
             ),
             (
-                "if (1 < 2) { 10 } else { 20 }",
-                Object::Integer(Integer { value: 10 }),
+                "let x = if (false) { 10 }; if (!x) { 30 }",
+                Object::Integer(Integer { value: 30 }),
+            ),
+        ];
+
+        for (input, expected_result) in expected {
+            let result = evaluate_input(input.to_string());
+
+            match (result, expected_result) {
+                (Object::Integer(int), Object::Integer(exp)) => assert_eq!(int.value, exp.value),
+                (Object::Null(_), Object::Null(_)) => (),
+                (actual, exp) => panic!("integers or nulls expected, but got {actual} and {exp}"),
+            }
+        }
+    }
+
+    #[test]
+    fn conditional_expression_evaluation_test() {
+        let expected = vec![
+            (r#"let x = 5; x > 0 ? "pos" : "neg""#, "pos"),
+            (r#"let x = -5; x > 0 ? "pos" : "neg""#, "neg"),
+            (
+                r#"let sign = fn(x) { x > 0 ? 1 : x < 0 ? -1 : 0 }; [sign(3), sign(-3), sign(0)]"#,
+                "[1, -1, 0]",
+            ),
+            ("let x = 0; true ? 1 : (x = 2); x", "0"),
+            ("let x = 0; x = false ? 1 : 2; x", "2"),
+        ];
+
+        for (input, expected_result) in expected {
+            let result = evaluate_input(input.to_string());
+            assert_eq!(result.to_string(), expected_result);
+        }
+    }
+
+    #[test]
+    fn assign_expression_evaluation_test() {
+        let expected = vec![
+            ("let x = 1; x = 2; x;", 2),
+            ("let x = 1; x = x + 41;", 42),
+            ("let x = 1; let y = 2; x = y = 3; x + y", 6),
+            ("let x = 1; let set = fn() { x = 5; }; set(); x", 5),
+            ("let x = 1; let f = fn(x) { x = 10; x }; f(2) + x", 11),
+            ("let i = 0; while (i < 5) { i = i + 1; } i", 5),
+        ];
+
+        for (input, expected_result) in expected {
+            assert_eq!(
+                evaluate_input(input.to_string()),
+                Object::Integer(Integer {
+                    value: expected_result
+                })
+            );
+        }
+
+        assert_eq!(
+            evaluate_input_error(String::from("x = 2;")),
+            "unable to assign to undefined identifier: x"
+        );
+        assert_eq!(
+            evaluate_input_error(String::from("let f = fn() { let y = 1; }; f(); y = 2;")),
+            "unable to assign to undefined identifier: y"
+        );
+    }
+
+    #[test]
+    fn while_evaluation_test() {
+        let expected = vec![
+            (
+                "let i = 0; while (i < 3) { let i = i + 1; } i;",
+                Object::Integer(Integer { value: 3 }),
+            ),
+            ("while (false) { 1 }", Object::Null(Null {})),
+            (
+                "let sum = fn(n) { let i = 0; let acc = 0; while (i < n) { let i = i + 1; let acc = acc + i; } acc }; sum(4)",
+                Object::Integer(Integer { value: 10 }),
+            ),
+            (
+                "let f = fn() { let i = 0; while (true) { let i = i + 1; if (i == 5) { return i * 10; } } }; f()",
+                Object::Integer(Integer { value: 50 }),
+            ),
+            (
+                "let i = 0; while (true) { let i = i + 1; if (i > 2) { return i; } } 99;",
+                Object::Integer(Integer { value: 3 }),
+            ),
+        ];
+
+        for (input, expected_result) in expected {
+            assert_eq!(evaluate_input(input.to_string()), expected_result);
+        }
+    }
+
+    #[test]
+    fn return_evaluation_test() {
+        let expected = vec![
+            ("return 10;", 10),
+            ("return 10; 9;", 10),
+            ("return 2 * 5; 9;", 10),
+            ("9; return 2 * 5; 9;", 10),
+            (
+                r#"
+if (10 > 1) {
+    if (10 > 1) {
+        return 10;
+    }
+
+    return 1;
+}
+                "#,
+                10,
+            ),
+            ("if (false) { 1 } else { return 3; 4 }", 3),
+            (
+                r#"
+let f = fn(x) {
+    if (x > 1) {
+        if (x > 5) { return 10; } else { return 5; }
+    }
+    return 1;
+};
+f(7) + f(3) + f(0)
+                "#,
+                16,
+            ),
+            ("let f = fn() { return 1; 2 }; f(); 5", 5),
+        ];
+
+        for (input, expected_result) in expected {
+            let result = evaluate_input(input.to_string());
+
+            match result {
+                Object::Integer(int) => {
+                    assert_eq!(int.value, expected_result)
+                }
+                actual => panic!("integer expected, but got {actual}"),
+            }
+        }
+    }
+
+    #[test]
+    fn let_statement_evaluation_test() {
+        let expected = vec![
+            ("let a = 5; a;", 5),
+            ("let a = 5 * 5; a;", 25),
+            ("let a = 5; let b = a; b;", 5),
+            ("let a = 5; let b = a; let c = a + b + 5; c;", 15),
+        ];
+
+        for (input, expected_result) in expected {
+            let result = evaluate_input(input.to_string());
+
+            match result {
+                Object::Integer(int) => assert_eq!(int.value, expected_result),
+                actual => panic!("integer expected, but got {actual}"),
+            }
+        }
+    }
+
+    #[test]
+    fn unknown_identifier_evaluation_test() {
+        let expected = vec![
+            ("foobar", "identifier not found: foobar"),
+            ("let a = 5; b;", "identifier not found: b"),
+            ("fn(x) { y }(1)", "identifier not found: y"),
+            (
+                "let f = fn() { let y = 1; y }; f(); y",
+                "identifier not found: y",
+            ),
+        ];
+
+        for (input, error) in expected {
+            assert_eq!(evaluate_input_error(input.to_string()), error);
+        }
+    }
+
+    #[test]
+    fn function_evaluation_test() {
+        let input = "fn(x) { x + 2; };";
+
+        let result = evaluate_input(input.to_string());
+
+        match result {
+            Object::Function(func) => {
+                assert_eq!(func.parameters.len(), 1);
+                assert_eq!(func.parameters.first().unwrap().to_string(), "x");
+                assert_eq!(func.body.to_string(), "(x + 2)");
+            }
+            actual => panic!("function expected, but got {actual}"),
+        }
+    }
+
+    #[test]
+    fn function_application_evaluation_test() {
+        let expected = vec![
+            ("let identity = fn(x) { x; }; identity(5);", 5),
+            ("let identity = fn(x) { return x; }; identity(5);", 5),
+            ("let double = fn(x) { x * 2; }; double(5);", 10),
+            ("let add = fn(x, y) { x + y; }; add(5, 5);", 10),
+            ("let add = fn(x, y) { x + y; }; add(5 + 5, add(5, 5));", 20),
+            ("fn(x) { x; }(5)", 5),
+        ];
+
+        for (input, expected_result) in expected {
+            let result = evaluate_input(input.to_string());
+
+            match result {
+                Object::Integer(int) => assert_eq!(int.value, expected_result),
+                actual => panic!("integer expected, but got {actual}"),
+            }
+        }
+    }
+
+    #[test]
+    fn function_call_evaluation_test() {
+        let expected = vec![
+            ("let add = fn(a, b) { a + b; }; add(2, 3);", 5),
+            ("fn(x) { fn(y) { x + y } }(2)(3)", 5),
+            (
+                "let apply = fn(f, x) { f(x) }; apply(fn(x) { x * 2 }, 4)",
+                8,
+            ),
+        ];
+
+        for (input, expected_result) in expected {
+            match evaluate_input(input.to_string()) {
+                Object::Integer(int) => assert_eq!(int.value, expected_result),
+                actual => panic!("integer expected, but got {actual}"),
+            }
+        }
+
+        let expected = vec![
+            (
+                "fn(a, b) { a + b }(1)",
+                "wrong number of arguments: want=2, got=1",
+            ),
+            ("fn() { 1 }(1)", "wrong number of arguments: want=0, got=1"),
+            (
+                "let f = 5; f(1)",
+                "unable to evaluate function call, function expected, but got \"5\"",
+            ),
+        ];
+
+        for (input, error) in expected {
+            assert_eq!(evaluate_input_error(input.to_string()), error);
+        }
+    }
+
+    #[test]
+    fn closure_test() {
+        let input = r#"
+let newAdder = fn(x) {
+    fn(y) { x + y };
+};
+let addTwo = newAdder(2);
+addTwo(2);"#;
+
+        let result = evaluate_input(input.to_string());
+
+        match result {
+            Object::Integer(int) => assert_eq!(int.value, 4),
+            actual => panic!("integer expected, but got {actual}"),
+        }
+    }
+
+    #[test]
+    fn string_literal_evaluation_test() {
+        let input = r#""Hello World!""#;
+
+        let result = evaluate_input(input.to_string());
+
+        match result {
+            Object::String(string) => assert_eq!(string.value, "Hello World!"),
+            actual => panic!("string expected, but got {actual}"),
+        }
+    }
+
+    #[test]
+    fn string_concatination_evaluation_test() {
+        let input = r#""Hello" + " " + "World!""#;
+
+        let result = evaluate_input(input.to_string());
+
+        match result {
+            Object::String(string) => assert_eq!(string.value, "Hello World!"),
+            actual => panic!("string expected, but got {actual}"),
+        }
+    }
+
+    #[test]
+    fn string_comparison_evaluation_test() {
+        let expected = vec![
+            (r#""a" == "a""#, true),
+            (r#""a" == "b""#, false),
+            (r#""a" != "b""#, true),
+            (r#""foo" + "bar" == "foobar""#, true),
+            (r#""abc" == "abc""#, true),
+            (r#""a" < "b""#, true),
+            (r#""b" > "a""#, true),
+            (r#""b" < "a""#, false),
+            (r#""ab" < "b""#, true),
+            (r#""a" < "ab""#, true),
+            (r#""a" > "a""#, false),
+        ];
+
+        for (input, expected_result) in expected {
+            match evaluate_input(input.to_string()) {
+                Object::Boolean(bool) => assert_eq!(bool.value, expected_result),
+                actual => panic!("boolean expected, but got {actual}"),
+            }
+        }
+
+        assert_eq!(
+            evaluate_input_error(r#""a" - "b""#.to_string()),
+            "unable to evaluate infix expression for Strings; +,<,>,== or != Tokens expected, but got \"-\""
+        );
+    }
+
+    #[test]
+    fn builtin_evaluation_test() {
+        let expected = vec![
+            ("len(\"\")", 0),
+            ("len(\"four\")", 4),
+            ("len(\"hello world\")", 11),
+            ("len([1, 4, 9, 5])", 4),
+            ("first([1, 4, 9, 5])", 1),
+            ("last([1, 4, 9, 5])", 5),
+            ("len(\"hello\")", 5),
+            ("len(\"héllo\")", 5),
+            ("len([1, 2, 3])", 3),
+            ("first([1, 2, 3])", 1),
+        ];
+
+        for (input, expected_result) in expected {
+            let result = evaluate_input(input.to_string());
+
+            match result {
+                Object::Integer(int) => assert_eq!(int.value, expected_result),
+                actual => panic!("integer expected, but got {actual}"),
+            }
+        }
+
+        let expected = vec![
+            ("push([1], 2)", "[1, 2]"),
+            ("rest([1, 2, 3])", "[2, 3]"),
+            ("first([])", "null"),
+            ("last([])", "null"),
+            ("rest([])", "null"),
+            ("rest([1])", "[]"),
+            ("first(\"héllo\")", "h"),
+            ("last(\"héllo\")", "o"),
+            ("rest(\"héllo\")", "éllo"),
+            ("first(rest(\"héllo\"))", "é"),
+            ("last(\"日本\")", "本"),
+            ("type(rest(\"a\"))", "String"),
+            ("len(rest(\"a\"))", "0"),
+            ("first(\"\")", "null"),
+            ("last(\"\")", "null"),
+            ("rest(\"\")", "null"),
+            ("puts(\"hello\")", "null"),
+        ];
+
+        for (input, expected_result) in expected {
+            let result = evaluate_input(input.to_string());
+            assert_eq!(result.to_string(), expected_result);
+        }
+
+        let expected = vec![
+            (
+                "len(1)",
+                "argument to len function is not supported, String or Array expected, but got \"1\"",
+            ),
+            (
+                "len(\"one\", \"two\")",
+                "wrong number of arguments for len function, 1 argument expected, but got 2",
+            ),
+            (
+                "push([1])",
+                "wrong number of arguments for push function, 2 arguments expected, but got 1",
+            ),
+            (
+                "first(1)",
+                "argument to first function is not supported, String or Array expected, but got \"1\"",
+            ),
+        ];
+
+        for (input, error) in expected {
+            assert_eq!(evaluate_input_error(input.to_string()), error);
+        }
+    }
+
+    #[test]
+    fn range_builtin_evaluation_test() {
+        let expected = vec![
+            ("range(3)", "[0, 1, 2]"),
+            ("range(2, 5)", "[2, 3, 4]"),
+            ("range(0, 6, 2)", "[0, 2, 4]"),
+            ("range(0, 7, 2)", "[0, 2, 4, 6]"),
+            ("range(5, 0, -2)", "[5, 3, 1]"),
+            ("range(-2, 1)", "[-2, -1, 0]"),
+            ("range(0)", "[]"),
+            ("range(-3)", "[]"),
+            ("range(5, 2)", "[]"),
+            ("range(2, 5, -1)", "[]"),
+            ("reduce(range(1, 101), 0, fn(acc, x) { acc + x })", "5050"),
+        ];
+
+        for (input, expected_result) in expected {
+            assert_eq!(
+                evaluate_input(input.to_string()).to_string(),
+                expected_result,
+                "evaluating {input}"
+            );
+        }
+
+        let expected = vec![
+            (
+                "range()",
+                "wrong number of arguments for range function, 1 to 3 arguments expected, but got 0",
+            ),
+            (
+                "range(1, 2, 3, 4)",
+                "wrong number of arguments for range function, 1 to 3 arguments expected, but got 4",
+            ),
+            (
+                "range(\"a\")",
+                "argument to range function is not supported, Integer expected, but got \"a\"",
+            ),
+            (
+                "range(0, 5, 0)",
+                "argument to range function is not supported, step can't be zero",
+            ),
+            (
+                "range(-9223372036854775807, 9223372036854775807)",
+                "argument to range function is not supported, range is longer than 10000000 elements",
+            ),
+            (
+                "range(10000001)",
+                "argument to range function is not supported, range is longer than 10000000 elements",
+            ),
+        ];
+
+        for (input, error) in expected {
+            assert_eq!(evaluate_input_error(input.to_string()), error);
+        }
+    }
+
+    #[test]
+    fn numeric_builtins_evaluation_test() {
+        let expected = vec![
+            ("abs(-5)", "5"),
+            ("abs(5)", "5"),
+            ("abs(0)", "0"),
+            ("abs(-2.5)", "2.5"),
+            ("min(3, 1, 2)", "1"),
+            ("max(3, 1, 2)", "3"),
+            ("min(2, -7)", "-7"),
+            ("max(1.5, 0.5, 2.5)", "2.5"),
+            ("min(\"b\", \"a\", \"c\")", "a"),
+            ("max(abs(-10), min(20, 30))", "20"),
+        ];
+
+        for (input, expected_result) in expected {
+            assert_eq!(
+                evaluate_input(input.to_string()).to_string(),
+                expected_result,
+                "evaluating {input}"
+            );
+        }
+
+        let expected = vec![
+            (
+                "min(1)",
+                "wrong number of arguments for min function, at least 2 arguments expected, but got 1",
+            ),
+            (
+                "max()",
+                "wrong number of arguments for max function, at least 2 arguments expected, but got 0",
+            ),
+            (
+                "max(1, \"a\")",
+                "arguments to max function are not supported, couldn't compare \"1\" and \"a\"",
+            ),
+            (
+                "min(1, 2.5)",
+                "arguments to min function are not supported, couldn't compare \"1\" and \"2.5\"",
+            ),
+            (
+                "abs(\"a\")",
+                "argument to abs function is not supported, Integer or Float expected, but got \"a\"",
+            ),
+            ("abs(-9223372036854775807 - 1)", "integer overflow in abs"),
+        ];
+
+        for (input, error) in expected {
+            assert_eq!(evaluate_input_error(input.to_string()), error);
+        }
+    }
+
+    #[test]
+    fn assert_builtins_evaluation_test() {
+        let expected = vec![
+            "assert(true)",
+            "assert(1 < 2)",
+            "assert_eq(1 + 1, 2)",
+            "assert_eq([1, \"a\"], [1, \"a\"])",
+            "let f = fn(x) { assert(x > 0); x * 2 }; assert_eq(f(3), 6)",
+        ];
+
+        for input in expected {
+            match evaluate_input(input.to_string()) {
+                Object::Null(_) => (),
+                actual => panic!("null expected while evaluating {input}, but got {actual}"),
+            }
+        }
+
+        let expected = vec![
+            ("assert(false)", "assertion failed"),
+            ("assert(1 > 2)", "assertion failed"),
+            (
+                "assert_eq(1 + 1, 3)",
+                "assertion failed, Integer \"2\" is not equal to Integer \"3\"",
+            ),
+            (
+                "assert_eq(1, \"1\")",
+                "assertion failed, Integer \"1\" is not equal to String \"1\"",
+            ),
+            (
+                "assert()",
+                "wrong number of arguments for assert function, 1 argument expected, but got 0",
+            ),
+            (
+                "assert_eq(1)",
+                "wrong number of arguments for assert_eq function, 2 arguments expected, but got 1",
+            ),
+        ];
+
+        for (input, error) in expected {
+            assert_eq!(evaluate_input_error(input.to_string()), error);
+        }
+    }
+
+    #[test]
+    fn search_builtins_evaluation_test() {
+        let expected = vec![
+            ("contains([1, 2, 3], 2)", "true"),
+            ("contains([1, 2, 3], 4)", "false"),
+            ("contains([[1], \"a\"], [1])", "true"),
+            ("contains([1], \"1\")", "false"),
+            ("contains(\"monkey\", \"key\")", "true"),
+            ("contains(\"monkey\", \"banana\")", "false"),
+            ("contains({\"a\": 1}, \"a\")", "true"),
+            ("contains({\"a\": 1}, 1)", "false"),
+            ("index_of([1, 2, 3, 2], 2)", "1"),
+            ("index_of([1, 2, 3], 4)", "-1"),
+            ("index_of([], 1)", "-1"),
+            ("index_of(\"monkey\", \"key\")", "3"),
+            ("index_of(\"äbc\", \"c\")", "2"),
+            ("index_of(\"monkey\", \"x\")", "-1"),
+        ];
+
+        for (input, expected_result) in expected {
+            assert_eq!(
+                evaluate_input(input.to_string()).to_string(),
+                expected_result,
+                "evaluating {input}"
+            );
+        }
+
+        let expected = vec![
+            (
+                "contains(1, 1)",
+                "argument to contains function is not supported, Array, String or HashTable expected, but got \"1\"",
+            ),
+            (
+                "contains(\"abc\", 1)",
+                "argument to contains function is not supported, String expected, but got \"1\"",
+            ),
+            (
+                "index_of(true, 1)",
+                "argument to index_of function is not supported, String or Array expected, but got \"true\"",
+            ),
+            (
+                "contains([1])",
+                "wrong number of arguments for contains function, 2 arguments expected, but got 1",
+            ),
+        ];
+
+        for (input, error) in expected {
+            assert_eq!(evaluate_input_error(input.to_string()), error);
+        }
+    }
+
+    #[test]
+    fn slice_builtin_evaluation_test() {
+        let expected = vec![
+            ("slice([1, 2, 3, 4], 1, 3)", "[2, 3]"),
+            ("slice([1, 2, 3, 4], 0, 10)", "[1, 2, 3, 4]"),
+            ("slice([1, 2, 3, 4], -2, 4)", "[3, 4]"),
+            ("slice([1, 2, 3, 4], -10, 1)", "[1]"),
+            ("slice([1, 2, 3, 4], 3, 1)", "[]"),
+            ("slice([1, 2, 3, 4], 5, 10)", "[]"),
+            ("slice(\"hello\", 1, 4)", "ell"),
+            ("slice(\"hello\", -3, 100)", "llo"),
+            ("slice(\"hello\", 4, 2)", ""),
+            ("slice(\"äöü\", 1, 2)", "ö"),
+        ];
+
+        for (input, expected_result) in expected {
+            assert_eq!(
+                evaluate_input(input.to_string()).to_string(),
+                expected_result,
+                "evaluating {input}"
+            );
+        }
+
+        let expected = vec![
+            (
+                "slice(1, 0, 1)",
+                "argument to slice function is not supported, String or Array expected, but got \"1\"",
+            ),
+            (
+                "slice([1], \"a\", 1)",
+                "argument to slice function is not supported, Integer expected, but got \"a\"",
+            ),
+            (
+                "slice([1], 0)",
+                "wrong number of arguments for slice function, 3 arguments expected, but got 2",
+            ),
+        ];
+
+        for (input, error) in expected {
+            assert_eq!(evaluate_input_error(input.to_string()), error);
+        }
+    }
+
+    #[test]
+    fn hash_builtins_evaluation_test() {
+        let hash = r#"let h = {1: "a", 2: "b"};"#;
+
+        let sorted = |obj: Object| match obj {
+            Object::Array(array) => {
+                let mut elements: Vec<_> = array.elements.iter().map(|el| el.to_string()).collect();
+                elements.sort();
+                elements
+            }
+            actual => panic!("array expected, but got {actual}"),
+        };
+
+        assert_eq!(
+            sorted(evaluate_input(format!("{hash} keys(h)"))),
+            vec!["1", "2"]
+        );
+        assert_eq!(
+            sorted(evaluate_input(format!("{hash} values(h)"))),
+            vec!["a", "b"]
+        );
+
+        let expected = vec![
+            ("delete(h, 1)", "{ 2: b }"),
+            ("delete(h, 3)[1]", "a"),
+            ("delete(h, 1); h[1]", "a"),
+            ("len(keys(delete(h, 2)))", "1"),
+            ("delete(h, 2)[2]", "null"),
+        ];
+
+        for (input, expected_result) in expected {
+            let result = evaluate_input(format!("{hash} {input}"));
+            assert_eq!(result.to_string(), expected_result);
+        }
+
+        let expected = vec![
+            (
+                "keys([1, 2])",
+                "argument to keys function is not supported, HashTable expected, but got \"[1, 2]\"",
+            ),
+            (
+                "values(1)",
+                "argument to values function is not supported, HashTable expected, but got \"1\"",
+            ),
+            (
+                "delete(\"a\", 1)",
+                "argument to delete function is not supported, HashTable expected, but got \"a\"",
+            ),
+            (
+                "delete({1: 2})",
+                "wrong number of arguments for delete function, 2 arguments expected, but got 1",
+            ),
+        ];
+
+        for (input, error) in expected {
+            assert_eq!(evaluate_input_error(input.to_string()), error);
+        }
+    }
+
+    #[test]
+    fn type_builtin_evaluation_test() {
+        let expected = vec![
+            ("type(5)", "Integer"),
+            ("type(\"a\")", "String"),
+            ("type([1])", "Array"),
+            ("type(1.5)", "Float"),
+            ("type(true)", "Boolean"),
+            ("type({1: 2})", "HashTable"),
+            ("type(fn(x) { x })", "Function"),
+            ("type(len)", "Builtin"),
+            ("type(if (false) { 1 })", "Null"),
+        ];
+
+        for (input, expected_result) in expected {
+            let result = evaluate_input(input.to_string());
+            assert_eq!(result.to_string(), expected_result);
+        }
+
+        assert_eq!(
+            evaluate_input_error(String::from("type()")),
+            "wrong number of arguments for type function, 1 argument expected, but got 0"
+        );
+    }
+
+    #[test]
+    fn conversion_builtins_evaluation_test() {
+        let expected = vec![
+            (
+                "str(42)",
+                Object::String(Str {
+                    value: String::from("42"),
+                }),
+            ),
+            (
+                "str(true)",
+                Object::String(Str {
+                    value: String::from("true"),
+                }),
+            ),
+            (
+                "str([1, \"a\"])",
+                Object::String(Str {
+                    value: String::from("[1, a]"),
+                }),
+            ),
+            (
+                "str(4) + str(2)",
+                Object::String(Str {
+                    value: String::from("42"),
+                }),
             ),
+            ("int(\"10\")", Object::Integer(Integer { value: 10 })),
+            ("int(\"-7\") * 2", Object::Integer(Integer { value: -14 })),
+            ("int(str(42))", Object::Integer(Integer { value: 42 })),
         ];
 
         for (input, expected_result) in expected {
-            let result = evaluate_input(input.to_string());
-
-            match (result, expected_result) {
-                (Object::Integer(int), Object::Integer(exp)) => assert_eq!(int.value, exp.value),
-                (Object::Null(_), Object::Null(_)) => (),
-                (actual, exp) => panic!("integers or nulls expected, but got {actual} and {exp}"),
-            }
+            assert_eq!(evaluate_input(input.to_string()), expected_result);
         }
-    }
 
-    #[test]
-    fn return_evaluation_test() {
         let expected = vec![
-            ("return 10;", 10),
-            ("return 10; 9;", 10),
-            ("return 2 * 5; 9;", 10),
-            ("9; return 2 * 5; 9;", 10),
             (
-                r#"
-if (10 > 1) {
-    if (10 > 1) {
-        return 10;
-    }
-
-    return 1;
-}
-                "#,
-                10,
+                "int(\"abc\")",
+                "argument to int function is not a valid integer, but got \"abc\"",
+            ),
+            (
+                "int(\"1.5\")",
+                "argument to int function is not a valid integer, but got \"1.5\"",
+            ),
+            (
+                "int(10)",
+                "argument to int function is not supported, String expected, but got \"10\"",
+            ),
+            (
+                "str(1, 2)",
+                "wrong number of arguments for str function, 1 argument expected, but got 2",
             ),
         ];
 
-        for (input, expected_result) in expected {
-            let result = evaluate_input(input.to_string());
-
-            match result {
-                Object::Integer(int) => {
-                    assert_eq!(int.value, expected_result)
-                }
-                actual => panic!("integer expected, but got {actual}"),
-            }
+        for (input, error) in expected {
+            assert_eq!(evaluate_input_error(input.to_string()), error);
         }
     }
 
     #[test]
-    fn let_statement_evaluation_test() {
+    fn higher_order_builtins_evaluation_test() {
         let expected = vec![
-            ("let a = 5; a;", 5),
-            ("let a = 5 * 5; a;", 25),
-            ("let a = 5; let b = a; b;", 5),
-            ("let a = 5; let b = a; let c = a + b + 5; c;", 15),
+            ("map([1, 2, 3], fn(x) { x * 2 })", "[2, 4, 6]"),
+            ("filter([1, 2, 3, 4], fn(x) { x > 2 })", "[3, 4]"),
+            ("reduce([1, 2, 3, 4], 0, fn(acc, x) { acc + x })", "10"),
+            ("map([], fn(x) { x })", "[]"),
+            ("map([[1], [2, 3]], len)", "[1, 2]"),
+            (
+                "let n = 10; map([1, 2], fn(x) { return x + n; })",
+                "[11, 12]",
+            ),
+            (
+                "reduce(map([1, 2, 3], fn(x) { x * x }), \"\", fn(acc, x) { acc + str(x) })",
+                "149",
+            ),
         ];
 
         for (input, expected_result) in expected {
             let result = evaluate_input(input.to_string());
-
-            match result {
-                Object::Integer(int) => assert_eq!(int.value, expected_result),
-                actual => panic!("integer expected, but got {actual}"),
-            }
+            assert_eq!(result.to_string(), expected_result);
         }
-    }
-
-    #[test]
-    fn function_evaluation_test() {
-        let input = "fn(x) { x + 2; };";
 
-        let result = evaluate_input(input.to_string());
+        let expected = vec![
+            (
+                "map(1, fn(x) { x })",
+                "argument to map function is not supported, Array expected, but got \"1\"",
+            ),
+            (
+                "filter([1], 2)",
+                "argument to filter function is not supported, Function expected, but got \"2\"",
+            ),
+            (
+                "reduce([1], 0)",
+                "wrong number of arguments for reduce function, 3 arguments expected, but got 2",
+            ),
+            (
+                "map([1], fn(x, y) { x })",
+                "wrong number of arguments: want=2, got=1",
+            ),
+            ("map([1, 0], fn(x) { 1 / x })", "division by zero"),
+        ];
 
-        match result {
-            Object::Function(func) => {
-                assert_eq!(func.parameters.len(), 1);
-                assert_eq!(func.parameters.first().unwrap().to_string(), "x");
-                assert_eq!(func.body.to_string(), "(x + 2)");
-            }
-            actual => panic!("function expected, but got {actual}"),
+        for (input, error) in expected {
+            assert_eq!(evaluate_input_error(input.to_string()), error);
         }
     }
 
     #[test]
-    fn function_application_evaluation_test() {
+    fn sort_builtin_evaluation_test() {
         let expected = vec![
-            ("let identity = fn(x) { x; }; identity(5);", 5),
-            ("let identity = fn(x) { return x; }; identity(5);", 5),
-            ("let double = fn(x) { x * 2; }; double(5);", 10),
-            ("let add = fn(x, y) { x + y; }; add(5, 5);", 10),
-            ("let add = fn(x, y) { x + y; }; add(5 + 5, add(5, 5));", 20),
-            ("fn(x) { x; }(5)", 5),
+            ("sort([3, 1, 2])", "[1, 2, 3]"),
+            ("sort([\"b\", \"a\"])", "[a, b]"),
+            ("sort([2.5, -1.0, 0.5])", "[-1.0, 0.5, 2.5]"),
+            ("sort([])", "[]"),
+            ("let a = [2, 1]; sort(a); a", "[2, 1]"),
         ];
 
         for (input, expected_result) in expected {
             let result = evaluate_input(input.to_string());
-
-            match result {
-                Object::Integer(int) => assert_eq!(int.value, expected_result),
-                actual => panic!("integer expected, but got {actual}"),
-            }
+            assert_eq!(result.to_string(), expected_result);
         }
-    }
-
-    #[test]
-    fn closure_test() {
-        let input = r#"
-let newAdder = fn(x) {
-    fn(y) { x + y };
-};
-let addTwo = newAdder(2);
-addTwo(2);"#;
 
-        let result = evaluate_input(input.to_string());
+        let expected = vec![
+            (
+                "sort([1, \"a\"])",
+                "argument to sort function is not supported, couldn't compare \"1\" and \"a\"",
+            ),
+            (
+                "sort([true, false])",
+                "argument to sort function is not supported, couldn't compare \"true\" and \"false\"",
+            ),
+            (
+                "sort(\"ba\")",
+                "argument to sort function is not supported, Array expected, but got \"ba\"",
+            ),
+        ];
 
-        match result {
-            Object::Integer(int) => assert_eq!(int.value, 4),
-            actual => panic!("integer expected, but got {actual}"),
+        for (input, error) in expected {
+            assert_eq!(evaluate_input_error(input.to_string()), error);
         }
     }
 
     #[test]
-    fn string_literal_evaluation_test() {
-        let input = r#""Hello World!""#;
+    fn string_builtins_evaluation_test() {
+        let expected = vec![
+            ("split(\"a,b,c\", \",\")", "[a, b, c]"),
+            ("len(split(\"a,,b\", \",\"))", "3"),
+            ("split(\"ab\", \"\")", "[a, b]"),
+            ("split(\"abc\", \"-\")", "[abc]"),
+            ("join([\"a\", \"b\"], \"-\")", "a-b"),
+            ("join([], \"-\")", ""),
+            ("join(split(\"a b c\", \" \"), \", \")", "a, b, c"),
+            ("trim(\"  hi  \")", "hi"),
+            ("len(trim(\"\t hi\n\"))", "2"),
+        ];
 
-        let result = evaluate_input(input.to_string());
+        for (input, expected_result) in expected {
+            let result = evaluate_input(input.to_string());
+            assert_eq!(result.to_string(), expected_result);
+        }
 
-        match result {
-            Object::String(string) => assert_eq!(string.value, "Hello World!"),
-            actual => panic!("string expected, but got {actual}"),
+        let expected = vec![
+            (
+                "join([\"a\", 1], \"-\")",
+                "argument to join function is not supported, String elements expected, but got \"1\"",
+            ),
+            (
+                "join(\"ab\", \"-\")",
+                "arguments to join function are not supported, Array and String expected, but got \"ab\" and \"-\"",
+            ),
+            (
+                "split(\"a,b\", 1)",
+                "arguments to split function are not supported, String and String expected, but got \"a,b\" and \"1\"",
+            ),
+            (
+                "trim(1)",
+                "argument to trim function is not supported, String expected, but got \"1\"",
+            ),
+        ];
+
+        for (input, error) in expected {
+            assert_eq!(evaluate_input_error(input.to_string()), error);
         }
     }
 
     #[test]
-    fn string_concatination_evaluation_test() {
-        let input = r#""Hello" + " " + "World!""#;
+    fn puts_output_evaluation_test() {
+        let program = Parser::new(Lexer::new(String::from(r#"puts("hello", 42)"#)))
+            .parse_program()
+            .unwrap();
 
-        let result = evaluate_input(input.to_string());
+        let mut output = Vec::new();
+        let env = Rc::new(RefCell::new(Environment::new()));
+        let result = eval_with_output(program, &env, &mut output).unwrap();
 
-        match result {
-            Object::String(string) => assert_eq!(string.value, "Hello World!"),
-            actual => panic!("string expected, but got {actual}"),
-        }
+        assert_eq!(result.to_string(), "null");
+        assert_eq!(String::from_utf8(output).unwrap(), "hello\n42\n");
     }
 
     #[test]
-    fn builtin_evaluation_test() {
+    fn statements_side_effects_evaluation_test() {
         let expected = vec![
-            ("len(\"\")", 0),
-            ("len(\"four\")", 4),
-            ("len(\"hello world\")", 11),
-            ("len([1, 4, 9, 5])", 4),
-            ("first([1, 4, 9, 5])", 1),
-            ("last([1, 4, 9, 5])", 5),
+            (r#"puts("a"); puts("b"); 42"#, "42", "a\nb\n"),
+            (r#"if (true) { puts("a"); puts("b"); 42 }"#, "42", "a\nb\n"),
+            (
+                r#"let f = fn() { puts("a"); 1; puts("b"); 2 }; f() + f()"#,
+                "4",
+                "a\nb\na\nb\n",
+            ),
+            (
+                r#"let f = fn() { puts("a"); return 1; puts("b"); 2 }; f()"#,
+                "1",
+                "a\n",
+            ),
+            (r#"puts("a"); return 7; puts("b")"#, "7", "a\n"),
+            (
+                r#"let f = fn(x) { if (x) { puts("in"); return 1; } puts("out"); 2 }; f(true) + f(false)"#,
+                "3",
+                "in\nout\n",
+            ),
         ];
 
-        for (input, expected_result) in expected {
-            println!("here");
-            let result = evaluate_input(input.to_string());
+        for (input, expected_result, expected_output) in expected {
+            let program = Parser::new(Lexer::new(String::from(input)))
+                .parse_program()
+                .unwrap();
 
-            match result {
-                Object::Integer(int) => assert_eq!(int.value, expected_result),
-                actual => panic!("integer expected, but got {actual}"),
-            }
+            let mut output = Vec::new();
+            let env = Rc::new(RefCell::new(Environment::new()));
+            let result = eval_with_output(program, &env, &mut output).unwrap();
+
+            assert_eq!(result.to_string(), expected_result, "{input}");
+            assert_eq!(
+                String::from_utf8(output).unwrap(),
+                expected_output,
+                "{input}"
+            );
         }
     }
 
@@ -1104,6 +2439,61 @@ sum([1, 2, 3, 4, 5]);
             let result = evaluate_input(input.to_string());
             assert_eq!(result.to_string().as_str(), expected_result);
         }
+
+        let expected = vec![
+            ("{fn(x) { x }: 1}", "unusable as hash key: Function"),
+            ("{len: 1}", "unusable as hash key: Builtin"),
+            ("{1: 2}[fn() { 1 }]", "unusable as hash key: Function"),
+            ("{1.5: 2}", "unusable as hash key: Float"),
+            ("delete({1: 2}, [1])", "unusable as hash key: Array"),
+        ];
+
+        for (input, error) in expected {
+            assert_eq!(evaluate_input_error(input.to_string()), error);
+        }
+    }
+
+    #[test]
+    fn index_expression_edge_cases_test() {
+        let expected = vec![
+            ("[][0]", "null"),
+            ("[1, 2, 3][99]", "null"),
+            ("[1][-1]", "1"),
+            ("[1, 2, 3][-1]", "3"),
+            ("[1, 2, 3][-3]", "1"),
+            ("[1, 2, 3][-4]", "null"),
+            ("[1, 2, 3][3]", "null"),
+            ("[][-1]", "null"),
+            ("let a = [1, 2, 3]; a[-len(a)]", "1"),
+            ("\"hello\"[1]", "e"),
+            ("\"héllo\"[1]", "é"),
+            ("\"héllo\"[-1]", "o"),
+            ("\"hello\"[5]", "null"),
+            ("\"\"[0]", "null"),
+            ("chars(\"ab\")", "[a, b]"),
+            ("chars(\"héllo\")[1]", "é"),
+            ("chars(\"\")", "[]"),
+            ("{1: 1}[0]", "null"),
+            ("{}[0]", "null"),
+        ];
+
+        for (input, expected_result) in expected {
+            let result = evaluate_input(input.to_string());
+            assert_eq!(result.to_string().as_str(), expected_result);
+        }
+
+        let expected = vec![
+            ("{1: 2}[[1]]", "unusable as hash key: Array"),
+            ("1[0]", "index operator not supported for \"1\" and \"0\""),
+            (
+                "[1][true]",
+                "index operator not supported for \"[1]\" and \"true\"",
+            ),
+        ];
+
+        for (input, error) in expected {
+            assert_eq!(evaluate_input_error(input.to_string()), error);
+        }
     }
 
     #[test]
@@ -1122,4 +2512,177 @@ counter(0);
 
         _ = evaluate_input(input.to_string());
     }
+
+    #[test]
+    fn max_call_depth_test() {
+        let expected = vec![
+            ("let f = fn(x) { 1 + f(x + 1) }; f(0)", MAX_CALL_DEPTH),
+            ("let f = fn(x) { map([x], f) }; f(0)", MAX_CALL_DEPTH),
+            (
+                "let f = fn(x) { if (x == 10) { x } else { 0 + f(x + 1) } }; f(0)",
+                5,
+            ),
+        ];
+
+        for (input, max_call_depth) in expected {
+            let program = Parser::new(Lexer::new(String::from(input)))
+                .parse_program()
+                .unwrap();
+            let env = Rc::new(RefCell::new(Environment::new()));
+
+            assert_eq!(
+                eval_with_max_call_depth(program, &env, &mut Vec::new(), max_call_depth)
+                    .map_err(|err| err.message().to_string()),
+                Err(String::from("maximum call depth exceeded"))
+            );
+        }
+
+        let program = Parser::new(Lexer::new(String::from(
+            "let f = fn(x) { if (x == 10) { x } else { 0 + f(x + 1) } }; f(0)",
+        )))
+        .parse_program()
+        .unwrap();
+        let env = Rc::new(RefCell::new(Environment::new()));
+        let result = eval_with_max_call_depth(program, &env, &mut Vec::new(), 11).unwrap();
+
+        assert_eq!(result.to_string(), "10");
+    }
+
+    #[test]
+    fn tail_call_test() {
+        let expected = vec![
+            (
+                "let countdown = fn(n) { if (n == 0) { 0 } else { countdown(n - 1) } }; countdown(100000)",
+                "0",
+            ),
+            (
+                "let sum = fn(n, acc) { if (n == 0) { return acc; } return sum(n - 1, acc + n); }; sum(100000, 0)",
+                "5000050000",
+            ),
+            (
+                "let isEven = fn(n) { if (n == 0) { true } else { isOdd(n - 1) } }; let isOdd = fn(n) { if (n == 0) { false } else { isEven(n - 1) } }; isEven(100001)",
+                "false",
+            ),
+            (
+                "let f = fn(n) { if (n == 0) { 0 } else { let x = f(n - 1); x + 1 } }; f(500)",
+                "500",
+            ),
+            (
+                "let f = fn(n) { if (n == 0) { 0 } else { 1 + f(n - 1) } }; f(500)",
+                "500",
+            ),
+        ];
+
+        for (input, expected_result) in expected {
+            let program = Parser::new(Lexer::new(String::from(input)))
+                .parse_program()
+                .unwrap();
+            let env = Rc::new(RefCell::new(Environment::new()));
+
+            assert_eq!(
+                eval(program, &env).unwrap().to_string(),
+                expected_result,
+                "evaluating {input}"
+            );
+        }
+
+        let program = Parser::new(Lexer::new(String::from(
+            "let f = fn(n) { if (n == 0) { 0 } else { 1 + f(n - 1) } }; f(100000)",
+        )))
+        .parse_program()
+        .unwrap();
+        let env = Rc::new(RefCell::new(Environment::new()));
+
+        assert_eq!(
+            eval(program, &env).map_err(|err| err.message().to_string()),
+            Err(String::from("maximum call depth exceeded"))
+        );
+    }
+
+    fn double_builtin(args: Vec<Object>, _ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
+        match args.as_slice() {
+            [Object::Integer(int)] => Ok(Object::Integer(Integer {
+                value: int.value * 2,
+            })),
+            _ => Err(String::from(
+                "argument to double function is not supported, Integer expected",
+            )),
+        }
+    }
+
+    #[test]
+    fn register_builtin_test() {
+        let program = Parser::new(Lexer::new(String::from(
+            "let f = fn(x) { double(x) + 1 }; map([1, 2], f)",
+        )))
+        .parse_program()
+        .unwrap();
+
+        let mut env = Environment::new();
+        env.register_builtin("double", BuiltinFunction(double_builtin));
+
+        assert_eq!(
+            eval(program, &Rc::new(RefCell::new(env))).map(|result| result.to_string()),
+            Ok(String::from("[3, 5]"))
+        );
+
+        let program = Parser::new(Lexer::new(String::from("double(true)")))
+            .parse_program()
+            .unwrap();
+
+        let mut env = Environment::new();
+        env.register_builtin("double", BuiltinFunction(double_builtin));
+
+        assert_eq!(
+            eval(program, &Rc::new(RefCell::new(env))).map_err(|err| err.message().to_string()),
+            Err(String::from(
+                "argument to double function is not supported, Integer expected"
+            ))
+        );
+    }
+
+    // objects are plain data without a way to hook a drop counter into them, but every value a
+    // closure captures is owned by the environment it closes over, so checking that the
+    // environment itself is freed covers the captured values as well
+    #[test]
+    fn recursive_closure_drop_test() {
+        let expected = vec![
+            ("let f = fn(n) { if (n == 0) { 0 } else { f(n - 1) } }; f(3)", "0"),
+            (
+                "let f = 0; f = fn(n) { if (n == 0) { 0 } else { f(n - 1) } }; f(2)",
+                "0",
+            ),
+            (
+                "let mk = fn() { let g = fn(n) { if (n == 0) { 0 } else { n + g(n - 1) } }; g }; mk()(3)",
+                "6",
+            ),
+            (
+                "let isEven = fn(n) { if (n == 0) { true } else { isOdd(n - 1) } }; let isOdd = fn(n) { if (n == 0) { false } else { isEven(n - 1) } }; isEven(4)",
+                "true",
+            ),
+        ];
+
+        for (input, expected_result) in expected {
+            let program = Parser::new(Lexer::new(String::from(input)))
+                .parse_program()
+                .unwrap();
+
+            let env = Rc::new(RefCell::new(Environment::new()));
+            let result = eval(program, &env).map(|result| result.to_string());
+
+            assert_eq!(
+                result,
+                Ok(String::from(expected_result)),
+                "evaluating {input}"
+            );
+
+            let weak_env = Rc::downgrade(&env);
+            drop(env);
+
+            assert!(
+                weak_env.upgrade().is_none(),
+                "environment leaked after {input}"
+            );
+        }
+    }
 }
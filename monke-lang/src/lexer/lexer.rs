@@ -1,18 +1,22 @@
 use std::str::FromStr;
 
-use super::token::Token;
+use super::token::{Position, Span, Token};
+use crate::result::MonkeyError;
 
 #[derive(Debug, Default)]
 pub struct Lexer {
     pub input: Vec<char>,
     pub read_position: usize,
     pub ch: Option<char>,
+    pub line: usize,
+    pub column: usize,
 }
 
 impl Lexer {
     pub fn new(input: String) -> Self {
         let mut lexer = Lexer {
             input: input.chars().collect(),
+            line: 1,
             ..Default::default()
         };
         lexer.advance();
@@ -20,8 +24,58 @@ impl Lexer {
         lexer
     }
 
+    pub fn next_token_with_position(&mut self) -> (Option<Token>, Position) {
+        let (token, span) = self.next_token_with_span();
+        (token, span.start)
+    }
+
+    pub fn next_token_with_span(&mut self) -> (Option<Token>, Span) {
+        let skipped = self.skip_whitespaces();
+
+        let start = self.cur_position();
+
+        let token = match skipped {
+            Ok(()) => self.next_token(),
+            Err(err) => Some(Token::Illegal(err)),
+        };
+
+        (
+            token,
+            Span {
+                start,
+                end: self.cur_position(),
+            },
+        )
+    }
+
+    fn cur_position(&self) -> Position {
+        Position {
+            line: self.line,
+            column: self.column,
+        }
+    }
+
+    pub fn tokenize_all(&mut self) -> Result<Vec<Token>, MonkeyError> {
+        let mut tokens = vec![];
+
+        loop {
+            match self.next_token_with_position() {
+                (None, _) => return Ok(tokens),
+                (Some(Token::Illegal(message)), position) => {
+                    return Err(MonkeyError::Lex {
+                        message,
+                        position: Some(position),
+                    })
+                }
+                (Some(token), _) => tokens.push(token),
+            }
+        }
+    }
+
     pub fn next_token(&mut self) -> Option<Token> {
-        self.skip_whitespaces();
+        if let Err(err) = self.skip_whitespaces() {
+            return Some(Token::Illegal(err));
+        }
 
         match self.ch {
             None => None,
@@ -36,10 +90,22 @@ impl Lexer {
                 '-' => self.advance_and_return(Token::Minus),
                 '*' => self.advance_and_return(Token::Asterisk),
                 '/' => self.advance_and_return(Token::Slash),
+                '%' => self.advance_and_return(Token::Percent),
+                '?' => self.advance_and_return(Token::Question),
                 '<' => self.advance_and_return(Token::Lt),
                 '>' => self.advance_and_return(Token::Gt),
                 '=' => self.peek_conditional('=', Token::Eq, Token::Assign),
                 '!' => self.peek_conditional('=', Token::Ne, Token::Bang),
+                '&' => self.peek_conditional(
+                    '&',
+                    Token::And,
+                    Token::Illegal(String::from("unexpected character \"&\"")),
+                ),
+                '|' => self.peek_conditional(
+                    '|',
+                    Token::Or,
+                    Token::Illegal(String::from("unexpected character \"|\"")),
+                ),
                 '[' => self.advance_and_return(Token::Lbracket),
                 ']' => self.advance_and_return(Token::Rbracket),
                 ':' => self.advance_and_return(Token::Colon),
@@ -49,20 +115,30 @@ impl Lexer {
                     self.advance();
                     Some(Token::String(string))
                 }
+                '_' if self.peek().is_some_and(is_digit) => {
+                    let number = self.read_while(is_number_part);
+                    Some(Token::Illegal(format!(
+                        "invalid number literal \"{number}\""
+                    )))
+                }
                 ch if is_letter(ch) => {
                     let ident = self.read_while(is_letter);
                     Some(lookup_ident(ident))
                 }
-                ch if is_digit(ch) => {
-                    let number = self.read_while(is_digit);
-                    Some(Token::Int(number))
-                }
-                ch => panic!("Unknown character {ch}"),
+                ch if is_digit(ch) => Some(self.read_number().unwrap_or_else(Token::Illegal)),
+                ch => self.advance_and_return(Token::Illegal(format!("illegal character '{ch}'"))),
             },
         }
     }
 
     fn advance(&mut self) {
+        if self.ch == Some('\n') {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+
         self.ch = self.input.get(self.read_position).copied();
         self.read_position += 1;
     }
@@ -91,6 +167,59 @@ impl Lexer {
         token
     }
 
+    fn read_number(&mut self) -> Result<Token, String> {
+        let radix = match (self.ch, self.peek()) {
+            (Some('0'), Some('x')) => Some(16),
+            (Some('0'), Some('o')) => Some(8),
+            (Some('0'), Some('b')) => Some(2),
+            _ => None,
+        };
+
+        if let Some(radix) = radix {
+            return self.read_radix_number(radix);
+        }
+
+        let mut number = self.read_digits()?;
+
+        match (self.ch, self.peek()) {
+            (Some('.'), Some(next)) if is_digit(next) => {
+                self.advance();
+                number.push('.');
+                number.push_str(&self.read_digits()?);
+
+                Ok(Token::Float(number))
+            }
+            _ => Ok(Token::Int(number)),
+        }
+    }
+
+    fn read_radix_number(&mut self, radix: u32) -> Result<Token, String> {
+        self.advance();
+        let prefix = self.ch.unwrap_or_default();
+        self.advance();
+
+        let digits = self.read_while(|c| c.is_ascii_alphanumeric() || c == '_');
+        let invalid = || format!("invalid number literal \"0{prefix}{digits}\"");
+
+        if digits.starts_with('_') || digits.ends_with('_') || digits.contains("__") {
+            return Err(invalid());
+        }
+
+        let value = i64::from_str_radix(&digits.replace('_', ""), radix).map_err(|_| invalid())?;
+
+        Ok(Token::Int(value.to_string()))
+    }
+
+    fn read_digits(&mut self) -> Result<String, String> {
+        let digits = self.read_while(is_number_part);
+
+        if digits.ends_with('_') || digits.contains("__") {
+            return Err(format!("invalid number literal \"{digits}\""));
+        }
+
+        Ok(digits.replace('_', ""))
+    }
+
     fn read_while(&mut self, condition: fn(char) -> bool) -> String {
         let mut buffer = String::new();
 
@@ -107,7 +236,7 @@ impl Lexer {
         buffer
     }
 
-    fn skip_whitespaces(&mut self) {
+    fn skip_whitespaces(&mut self) -> Result<(), String> {
         loop {
             if self.ch.is_none() {
                 break;
@@ -119,8 +248,48 @@ impl Lexer {
                 continue;
             }
 
+            if ch == '/' && self.peek() == Some('*') {
+                self.skip_block_comment()?;
+                continue;
+            }
+
             break;
         }
+
+        Ok(())
+    }
+
+    fn skip_block_comment(&mut self) -> Result<(), String> {
+        let mut depth = 0;
+
+        loop {
+            match (self.ch, self.peek()) {
+                (Some('/'), Some('*')) => {
+                    self.advance();
+                    self.advance();
+                    depth += 1;
+                }
+                (Some('*'), Some('/')) => {
+                    self.advance();
+                    self.advance();
+                    depth -= 1;
+
+                    if depth == 0 {
+                        return Ok(());
+                    }
+                }
+                (Some(_), _) => self.advance(),
+                (None, _) => return Err(String::from("unterminated block comment")),
+            }
+        }
+    }
+}
+
+impl Iterator for Lexer {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_token()
     }
 }
 
@@ -131,6 +300,10 @@ fn is_digit(ch: char) -> bool {
     }
 }
 
+fn is_number_part(ch: char) -> bool {
+    is_digit(ch) || ch == '_'
+}
+
 fn is_letter(ch: char) -> bool {
     match ch {
         'a'..='z' | 'A'..='Z' | '_' => true,
@@ -160,7 +333,7 @@ let add = fn(x, y) {
 };
 
 let result = add(five, ten);
-!-/*5;
+!-/ *5;
 5 < 10 > 5;
 
 if (5 < 10) {
@@ -171,6 +344,7 @@ if (5 < 10) {
 
 10 == 10;
 10 != 9;
+10 % 3;
 "foobar"
 "foo bar"
 [1, 2];
@@ -253,6 +427,10 @@ if (5 < 10) {
             Token::Ne,
             Token::Int(String::from("9")),
             Token::Semicolon,
+            Token::Int(String::from("10")),
+            Token::Percent,
+            Token::Int(String::from("3")),
+            Token::Semicolon,
             Token::String(String::from("foobar")),
             Token::String(String::from("foo bar")),
             Token::Lbracket,
@@ -274,4 +452,258 @@ if (5 < 10) {
 
         assert_eq!(lexer.next_token(), None);
     }
+
+    #[test]
+    fn lexer_float_test() {
+        let mut lexer = Lexer::new(String::from("2.75 + 10 * 0.5;"));
+
+        let expected_tokens = vec![
+            Token::Float(String::from("2.75")),
+            Token::Plus,
+            Token::Int(String::from("10")),
+            Token::Asterisk,
+            Token::Float(String::from("0.5")),
+            Token::Semicolon,
+        ];
+
+        for expected_token in expected_tokens {
+            assert_eq!(lexer.next_token().unwrap(), expected_token);
+        }
+
+        assert_eq!(lexer.next_token(), None);
+    }
+
+    #[test]
+    fn lexer_digit_separator_test() {
+        let tests = vec![
+            ("1_000", Token::Int(String::from("1000"))),
+            ("12_34_56", Token::Int(String::from("123456"))),
+            ("1_000.000_5", Token::Float(String::from("1000.0005"))),
+            (
+                "_5",
+                Token::Illegal(String::from("invalid number literal \"_5\"")),
+            ),
+            (
+                "5_",
+                Token::Illegal(String::from("invalid number literal \"5_\"")),
+            ),
+            (
+                "1__0",
+                Token::Illegal(String::from("invalid number literal \"1__0\"")),
+            ),
+        ];
+
+        for (input, expected_token) in tests {
+            let mut lexer = Lexer::new(String::from(input));
+
+            assert_eq!(lexer.next_token().unwrap(), expected_token);
+            assert_eq!(lexer.next_token(), None);
+        }
+    }
+
+    #[test]
+    fn lexer_radix_literal_test() {
+        let tests = vec![
+            ("0xFF", Token::Int(String::from("255"))),
+            ("0x1f", Token::Int(String::from("31"))),
+            ("0o17", Token::Int(String::from("15"))),
+            ("0b1010", Token::Int(String::from("10"))),
+            ("0b1111_0000", Token::Int(String::from("240"))),
+            (
+                "0b102",
+                Token::Illegal(String::from("invalid number literal \"0b102\"")),
+            ),
+            (
+                "0o8",
+                Token::Illegal(String::from("invalid number literal \"0o8\"")),
+            ),
+            (
+                "0xG",
+                Token::Illegal(String::from("invalid number literal \"0xG\"")),
+            ),
+            (
+                "0x",
+                Token::Illegal(String::from("invalid number literal \"0x\"")),
+            ),
+        ];
+
+        for (input, expected_token) in tests {
+            let mut lexer = Lexer::new(String::from(input));
+
+            assert_eq!(lexer.next_token().unwrap(), expected_token);
+            assert_eq!(lexer.next_token(), None);
+        }
+    }
+
+    #[test]
+    fn lexer_logical_operators_test() {
+        let mut lexer = Lexer::new(String::from("a && b || c & d | e"));
+
+        let expected_tokens = vec![
+            Token::Ident(String::from("a")),
+            Token::And,
+            Token::Ident(String::from("b")),
+            Token::Or,
+            Token::Ident(String::from("c")),
+            Token::Illegal(String::from("unexpected character \"&\"")),
+            Token::Ident(String::from("d")),
+            Token::Illegal(String::from("unexpected character \"|\"")),
+            Token::Ident(String::from("e")),
+        ];
+
+        for expected_token in expected_tokens {
+            assert_eq!(lexer.next_token().unwrap(), expected_token);
+        }
+
+        assert_eq!(lexer.next_token(), None);
+    }
+
+    #[test]
+    fn lexer_conditional_operator_test() {
+        let mut lexer = Lexer::new(String::from("x ? 1 : 2"));
+
+        let expected_tokens = vec![
+            Token::Ident(String::from("x")),
+            Token::Question,
+            Token::Int(String::from("1")),
+            Token::Colon,
+            Token::Int(String::from("2")),
+        ];
+
+        for expected_token in expected_tokens {
+            assert_eq!(lexer.next_token().unwrap(), expected_token);
+        }
+
+        assert_eq!(lexer.next_token(), None);
+    }
+
+    #[test]
+    fn lexer_iterator_test() {
+        let tokens = Lexer::new(String::from("let x = 5;")).collect::<Vec<_>>();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Let,
+                Token::Ident(String::from("x")),
+                Token::Assign,
+                Token::Int(String::from("5")),
+                Token::Semicolon,
+            ]
+        );
+
+        let idents = Lexer::new(String::from("fn(a, b) { a + b }"))
+            .filter(|token| matches!(token, Token::Ident(_)))
+            .count();
+
+        assert_eq!(idents, 4);
+    }
+
+    #[test]
+    fn tokenize_all_test() {
+        assert_eq!(
+            Lexer::new(String::from("let x = 5;")).tokenize_all(),
+            Ok(vec![
+                Token::Let,
+                Token::Ident(String::from("x")),
+                Token::Assign,
+                Token::Int(String::from("5")),
+                Token::Semicolon,
+            ])
+        );
+
+        let expected = vec![
+            ("let x = @;", "1:9: illegal character '@'"),
+            ("let x = 1;\n  x # 2", "2:5: illegal character '#'"),
+            ("a & b", "1:3: unexpected character \"&\""),
+            ("1 + /* 2", "1:9: unterminated block comment"),
+        ];
+
+        for (input, expected_error) in expected {
+            let err = Lexer::new(String::from(input)).tokenize_all().unwrap_err();
+
+            assert!(matches!(err, MonkeyError::Lex { .. }));
+            assert_eq!(err.to_string(), expected_error);
+        }
+    }
+
+    #[test]
+    fn lexer_block_comment_test() {
+        let tests = vec![
+            (
+                "1 /* comment */ + 2",
+                vec![
+                    Token::Int(String::from("1")),
+                    Token::Plus,
+                    Token::Int(String::from("2")),
+                ],
+            ),
+            (
+                "/* outer /* inner */ still outer */ 5 / 2;",
+                vec![
+                    Token::Int(String::from("5")),
+                    Token::Slash,
+                    Token::Int(String::from("2")),
+                    Token::Semicolon,
+                ],
+            ),
+            (
+                r#""/* not a comment */""#,
+                vec![Token::String(String::from("/* not a comment */"))],
+            ),
+            (
+                "let a = 1; /* never closed",
+                vec![
+                    Token::Let,
+                    Token::Ident(String::from("a")),
+                    Token::Assign,
+                    Token::Int(String::from("1")),
+                    Token::Semicolon,
+                    Token::Illegal(String::from("unterminated block comment")),
+                ],
+            ),
+            (
+                "/* /* nested but unbalanced */",
+                vec![Token::Illegal(String::from("unterminated block comment"))],
+            ),
+        ];
+
+        for (input, expected_tokens) in tests {
+            let mut lexer = Lexer::new(String::from(input));
+
+            for expected_token in expected_tokens {
+                assert_eq!(lexer.next_token().unwrap(), expected_token);
+            }
+
+            assert_eq!(lexer.next_token(), None);
+        }
+    }
+
+    #[test]
+    fn lexer_position_test() {
+        let mut lexer = Lexer::new(String::from("let a = 1;\nlet b = 2;\n  a +\tb;"));
+
+        for _ in 0..10 {
+            lexer.next_token();
+        }
+
+        assert_eq!(
+            lexer.next_token_with_position(),
+            (
+                Some(Token::Ident(String::from("a"))),
+                Position { line: 3, column: 3 }
+            )
+        );
+        assert_eq!(
+            lexer.next_token_with_position(),
+            (Some(Token::Plus), Position { line: 3, column: 5 })
+        );
+        assert_eq!(
+            lexer.next_token_with_position(),
+            (
+                Some(Token::Ident(String::from("b"))),
+                Position { line: 3, column: 7 }
+            )
+        );
+    }
 }
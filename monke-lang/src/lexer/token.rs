@@ -1,11 +1,12 @@
-use std::{fmt::Display, str::FromStr};
+use std::{fmt::Display, hash::Hash, str::FromStr};
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 pub enum Token {
-    Illegal,
+    Illegal(String),
     // Identifiers + literals
     Ident(String),
     Int(String),
+    Float(String),
     String(String),
     // Operators
     Assign,
@@ -14,10 +15,14 @@ pub enum Token {
     Bang,
     Asterisk,
     Slash,
+    Percent,
     Lt,
     Gt,
     Eq,
     Ne,
+    And,
+    Or,
+    Question,
     // Delimiters
     Comma,
     Colon,
@@ -36,6 +41,7 @@ pub enum Token {
     If,
     Else,
     Return,
+    While,
 }
 
 impl FromStr for Token {
@@ -50,6 +56,7 @@ impl FromStr for Token {
             "if" => Ok(Token::If),
             "else" => Ok(Token::Else),
             "return" => Ok(Token::Return),
+            "while" => Ok(Token::While),
             ident => Err(format!("Display not implemented for identifier {ident}")),
         }
     }
@@ -58,19 +65,24 @@ impl FromStr for Token {
 impl Display for Token {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Token::Illegal => write!(f, "Javascript lol"),
+            Token::Illegal(err) => write!(f, "{err}"),
             Token::Ident(ident) => write!(f, "{ident}"),
             Token::Int(int) => write!(f, "{int}"),
+            Token::Float(float) => write!(f, "{float}"),
             Token::Assign => write!(f, "="),
             Token::Plus => write!(f, "+"),
             Token::Minus => write!(f, "-"),
             Token::Bang => write!(f, "!"),
             Token::Asterisk => write!(f, "*"),
             Token::Slash => write!(f, "/"),
+            Token::Percent => write!(f, "%"),
+            Token::Question => write!(f, "?"),
             Token::Lt => write!(f, "<"),
             Token::Gt => write!(f, ">"),
             Token::Eq => write!(f, "=="),
             Token::Ne => write!(f, "!="),
+            Token::And => write!(f, "&&"),
+            Token::Or => write!(f, "||"),
             Token::Comma => write!(f, ","),
             Token::Semicolon => write!(f, ";"),
             Token::Lparen => write!(f, "("),
@@ -84,6 +96,7 @@ impl Display for Token {
             Token::If => write!(f, "if"),
             Token::Else => write!(f, "else"),
             Token::Return => write!(f, "return"),
+            Token::While => write!(f, "while"),
             Token::String(string) => write!(f, "{string}"),
             Token::Lbracket => write!(f, "["),
             Token::Rbracket => write!(f, "]"),
@@ -91,3 +104,27 @@ impl Display for Token {
         }
     }
 }
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub start: Position,
+    pub end: Position,
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
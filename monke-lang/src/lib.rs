@@ -4,6 +4,68 @@ pub mod compiler;
 pub mod evaluator;
 pub mod lexer;
 pub mod parser;
+pub mod repl;
 pub mod result;
 pub mod types;
 pub mod vm;
+
+use std::{cell::RefCell, rc::Rc};
+
+use compiler::compiler::Compiler;
+use evaluator::{environment::Environment, evaluator::eval};
+use lexer::lexer::Lexer;
+use parser::parser::Parser;
+use result::MonkeyError;
+use types::Object;
+use vm::vm::Vm;
+
+pub fn run_source(src: &str) -> Result<Object, MonkeyError> {
+    let program = Parser::new(Lexer::new(String::from(src))).parse_program()?;
+
+    let mut compiler = Compiler::new();
+    compiler.compile(program)?;
+
+    let byte_code = compiler
+        .byte_code()
+        .map_err(|message| MonkeyError::Compile {
+            message,
+            position: None,
+        })?;
+
+    let mut vm = Vm::new(byte_code);
+    vm.run()?;
+
+    Ok(vm.last_popped_stack_elem()?)
+}
+
+pub fn eval_source(src: &str) -> Result<Object, MonkeyError> {
+    let program = Parser::new(Lexer::new(String::from(src))).parse_program()?;
+
+    eval(program, &Rc::new(RefCell::new(Environment::new())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{eval_source, run_source};
+    use crate::{
+        result::MonkeyError,
+        types::{Integer, Object},
+    };
+
+    #[test]
+    fn run_source_test() {
+        for run in [run_source, eval_source] {
+            assert_eq!(run("1 + 2"), Ok(Object::Integer(Integer { value: 3 })));
+            assert_eq!(
+                run("let f = fn(x) { x * 2 }; f(21)"),
+                Ok(Object::Integer(Integer { value: 42 }))
+            );
+
+            assert!(matches!(run("let x = ;"), Err(MonkeyError::Parse { .. })));
+            assert!(matches!(run("1 / 0"), Err(MonkeyError::Runtime { .. })));
+        }
+
+        assert!(matches!(run_source("y"), Err(MonkeyError::Compile { .. })));
+        assert!(matches!(eval_source("y"), Err(MonkeyError::Runtime { .. })));
+    }
+}
@@ -1,6 +1,5 @@
-use crate::lexer::token::Token;
+use crate::lexer::token::{Span, Token};
 use std::{
-    collections::HashMap,
     fmt::{Debug, Display},
     hash::Hash,
     rc::Rc,
@@ -8,6 +7,10 @@ use std::{
 
 pub enum ExpressionType {
     Lowest = 1,
+    Assign,      // =
+    Conditional, // ?:
+    Or,          // ||
+    And,         // &&
     Equals,      // ==
     LessGreater, // > or <
     Sum,         // +
@@ -28,6 +31,7 @@ pub enum Program {
 pub enum Expression {
     Identifier(Identifier),
     IntegerLiteral(IntegerLiteral),
+    FloatLiteral(FloatLiteral),
     StringLiteral(StringLiteral),
     Prefix(PrefixExpression),
     Infix(InfixExpression),
@@ -38,26 +42,7 @@ pub enum Expression {
     ArrayLiteral(ArrayLiteral),
     IndexExpression(IndexExpression),
     HashLiteral(HashLiteral),
-}
-
-impl Expression {
-    fn same_type(&self, other: &Self) -> bool {
-        match (self, other) {
-            (Expression::Identifier(_), Expression::Identifier(_)) => true,
-            (Expression::IntegerLiteral(_), Expression::IntegerLiteral(_)) => true,
-            (Expression::StringLiteral(_), Expression::StringLiteral(_)) => true,
-            (Expression::Prefix(_), Expression::Prefix(_)) => true,
-            (Expression::Infix(_), Expression::Infix(_)) => true,
-            (Expression::Boolean(_), Expression::Boolean(_)) => true,
-            (Expression::If(_), Expression::If(_)) => true,
-            (Expression::FunctionLiteral(_), Expression::FunctionLiteral(_)) => true,
-            (Expression::Call(_), Expression::Call(_)) => true,
-            (Expression::ArrayLiteral(_), Expression::ArrayLiteral(_)) => true,
-            (Expression::IndexExpression(_), Expression::IndexExpression(_)) => true,
-            (Expression::HashLiteral(_), Expression::HashLiteral(_)) => true,
-            _ => false,
-        }
-    }
+    Assign(AssignExpression),
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -66,6 +51,7 @@ pub enum Statement {
     Return(ReturnStatement),
     Expression(ExpressionStatement),
     Block(BlockStatement),
+    While(WhileStatement),
 }
 
 impl Display for Program {
@@ -93,6 +79,7 @@ impl Display for Expression {
         match self {
             Expression::Identifier(ident) => write!(f, "{ident}"),
             Expression::IntegerLiteral(int) => write!(f, "{int}"),
+            Expression::FloatLiteral(float) => write!(f, "{float}"),
             Expression::Prefix(prefix) => write!(f, "{prefix}"),
             Expression::Infix(infix) => write!(f, "{infix}"),
             Expression::Boolean(boolean) => write!(f, "{boolean}"),
@@ -103,6 +90,7 @@ impl Display for Expression {
             Expression::ArrayLiteral(array) => write!(f, "{array}"),
             Expression::IndexExpression(index_expr) => write!(f, "{index_expr}"),
             Expression::HashLiteral(hash_literal) => write!(f, "{hash_literal}"),
+            Expression::Assign(assign) => write!(f, "{assign}"),
         }
     }
 }
@@ -114,6 +102,40 @@ impl Display for Statement {
             Statement::Return(return_statement) => write!(f, "{return_statement}"),
             Statement::Expression(expr) => write!(f, "{expr}"),
             Statement::Block(block) => write!(f, "{block}"),
+            Statement::While(while_statement) => write!(f, "{while_statement}"),
+        }
+    }
+}
+
+impl Expression {
+    pub fn span(&self) -> Span {
+        match self {
+            Expression::Identifier(ident) => ident.span,
+            Expression::IntegerLiteral(int) => int.span,
+            Expression::FloatLiteral(float) => float.span,
+            Expression::StringLiteral(string) => string.span,
+            Expression::Prefix(prefix) => prefix.span,
+            Expression::Infix(infix) => infix.span,
+            Expression::Boolean(boolean) => boolean.span,
+            Expression::If(if_expr) => if_expr.span,
+            Expression::FunctionLiteral(func) => func.span,
+            Expression::Call(call) => call.span,
+            Expression::ArrayLiteral(array) => array.span,
+            Expression::IndexExpression(index_expr) => index_expr.span,
+            Expression::HashLiteral(hash_literal) => hash_literal.span,
+            Expression::Assign(assign) => assign.span,
+        }
+    }
+}
+
+impl Statement {
+    pub fn span(&self) -> Span {
+        match self {
+            Statement::Let(let_statement) => let_statement.span,
+            Statement::Return(return_statement) => return_statement.span,
+            Statement::Expression(expr) => expr.span,
+            Statement::Block(block) => block.span,
+            Statement::While(while_statement) => while_statement.span,
         }
     }
 }
@@ -139,6 +161,7 @@ impl From<Vec<Rc<Statement>>> for Program {
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct Identifier {
     pub token: Token,
+    pub span: Span,
 }
 
 impl Display for Identifier {
@@ -150,6 +173,7 @@ impl Display for Identifier {
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct IntegerLiteral {
     pub token: Token,
+    pub span: Span,
     pub value: i64,
 }
 
@@ -159,9 +183,38 @@ impl Display for IntegerLiteral {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct FloatLiteral {
+    pub token: Token,
+    pub span: Span,
+    pub value: f64,
+}
+
+impl Hash for FloatLiteral {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.token.hash(state);
+        self.value.to_bits().hash(state);
+    }
+}
+
+impl PartialEq for FloatLiteral {
+    fn eq(&self, other: &Self) -> bool {
+        self.token == other.token && self.value.to_bits() == other.value.to_bits()
+    }
+}
+
+impl Eq for FloatLiteral {}
+
+impl Display for FloatLiteral {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.token)
+    }
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct StringLiteral {
     pub token: Token,
+    pub span: Span,
 }
 
 impl Display for StringLiteral {
@@ -173,6 +226,7 @@ impl Display for StringLiteral {
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct PrefixExpression {
     pub token: Token,
+    pub span: Span,
     pub right: Rc<Expression>,
 }
 
@@ -185,6 +239,7 @@ impl Display for PrefixExpression {
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct InfixExpression {
     pub token: Token,
+    pub span: Span,
     pub left: Rc<Expression>,
     pub right: Rc<Expression>,
 }
@@ -198,6 +253,7 @@ impl Display for InfixExpression {
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct Boolean {
     pub token: Token,
+    pub span: Span,
     pub value: bool,
 }
 
@@ -210,6 +266,7 @@ impl Display for Boolean {
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct IfExpression {
     pub token: Token,
+    pub span: Span,
     pub condition: Rc<Expression>,
     pub consequence: Rc<Statement>,
     pub alternative: Option<Rc<Statement>>,
@@ -220,7 +277,7 @@ impl Display for IfExpression {
         let mut buffer = format!("if {} {}", self.condition, self.consequence);
 
         if let Some(alt) = self.alternative.as_ref() {
-            buffer.push_str(&format!("else {}", alt));
+            buffer.push_str(&format!(" else {}", alt));
         }
 
         write!(f, "{}", buffer)
@@ -230,6 +287,7 @@ impl Display for IfExpression {
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct FunctionLiteral {
     pub token: Token,
+    pub span: Span,
     pub parameters: Vec<Identifier>,
     pub body: Rc<Statement>,
     pub name: String,
@@ -255,6 +313,7 @@ impl Display for FunctionLiteral {
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct CallExpression {
     pub token: Token,
+    pub span: Span,
     pub function: Rc<Expression>,
     pub arguments: Vec<Rc<Expression>>,
 }
@@ -275,6 +334,7 @@ impl Display for CallExpression {
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct ArrayLiteral {
     pub token: Token,
+    pub span: Span,
     pub elements: Vec<Rc<Expression>>,
 }
 
@@ -294,6 +354,7 @@ impl Display for ArrayLiteral {
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct IndexExpression {
     pub token: Token,
+    pub span: Span,
     pub left: Rc<Expression>,
     pub index: Rc<Expression>,
 }
@@ -304,59 +365,11 @@ impl Display for IndexExpression {
     }
 }
 
-#[derive(Debug, Clone, Eq)]
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct HashLiteral {
     pub token: Token,
-    pub pairs: HashMap<Rc<Expression>, Rc<Expression>>,
-}
-
-impl Hash for HashLiteral {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.token.hash(state);
-        let mut to_vec: Vec<_> = self
-            .pairs
-            .iter()
-            .map(|(key, value)| (key.to_string(), value.to_string()))
-            .collect();
-        to_vec.sort_by(|(a, _), (b, _)| a.cmp(b));
-
-        to_vec.hash(state);
-    }
-}
-
-impl PartialEq for HashLiteral {
-    fn eq(&self, other: &Self) -> bool {
-        if self.token != other.token {
-            return false;
-        }
-
-        let mut pairs_a: Vec<_> = self
-            .pairs
-            .iter()
-            .map(|(key, value)| (key.to_string(), key, value))
-            .collect();
-        pairs_a.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
-
-        let mut pairs_b: Vec<_> = self
-            .pairs
-            .iter()
-            .map(|(key, value)| (key.to_string(), key, value))
-            .collect();
-        pairs_b.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
-
-        for ((key_a, key_expr_a, value_expr_a), (key_b, key_expr_b, value_expr_b)) in
-            pairs_a.iter().zip(pairs_b)
-        {
-            if key_a != &key_b
-                || !key_expr_a.same_type(key_expr_b)
-                || !value_expr_a.same_type(value_expr_b)
-            {
-                return false;
-            }
-        }
-
-        true
-    }
+    pub span: Span,
+    pub pairs: Vec<(Rc<Expression>, Rc<Expression>)>,
 }
 
 impl Display for HashLiteral {
@@ -372,9 +385,24 @@ impl Display for HashLiteral {
     }
 }
 
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct AssignExpression {
+    pub token: Token,
+    pub span: Span,
+    pub name: Identifier,
+    pub value: Rc<Expression>,
+}
+
+impl Display for AssignExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({} {} {})", self.name, self.token, self.value)
+    }
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct LetStatement {
     pub token: Token,
+    pub span: Span,
     pub name: Identifier,
     pub value: Rc<Expression>,
 }
@@ -388,6 +416,7 @@ impl Display for LetStatement {
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct ReturnStatement {
     pub token: Token,
+    pub span: Span,
     pub return_value: Rc<Expression>,
 }
 
@@ -400,6 +429,7 @@ impl Display for ReturnStatement {
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct ExpressionStatement {
     pub token: Token,
+    pub span: Span,
     pub expression: Rc<Expression>,
 }
 
@@ -412,6 +442,7 @@ impl Display for ExpressionStatement {
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct BlockStatement {
     pub token: Token,
+    pub span: Span,
     pub statements: Vec<Rc<Statement>>,
 }
 
@@ -427,3 +458,17 @@ impl Display for BlockStatement {
         write!(f, "{block}")
     }
 }
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct WhileStatement {
+    pub token: Token,
+    pub span: Span,
+    pub condition: Rc<Expression>,
+    pub body: Rc<Statement>,
+}
+
+impl Display for WhileStatement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.token, self.condition, self.body)
+    }
+}
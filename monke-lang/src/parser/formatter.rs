@@ -0,0 +1,249 @@
+use super::ast::{BlockStatement, Expression, ExpressionType, Program, Statement};
+use super::parser::get_precedence;
+
+const INDENT: &str = "    ";
+
+impl Program {
+    pub fn format(&self) -> String {
+        match self {
+            Program::Statement(statement) => format_statement(statement, 0),
+            Program::Statements(statements) => statements
+                .iter()
+                .map(|statement| format_statement(statement, 0))
+                .reduce(|acc, cur| format!("{acc}\n{cur}"))
+                .unwrap_or(String::new()),
+            Program::Expression(expression) => format_expression(expression, 0),
+        }
+    }
+}
+
+fn format_statement(statement: &Statement, indent: usize) -> String {
+    match statement {
+        Statement::Let(let_statement) => format!(
+            "let {} = {};",
+            let_statement.name,
+            format_expression(&let_statement.value, indent)
+        ),
+        Statement::Return(return_statement) => format!(
+            "return {};",
+            format_expression(&return_statement.return_value, indent)
+        ),
+        Statement::Expression(expression_statement) => {
+            let expression = format_expression(&expression_statement.expression, indent);
+
+            match expression_statement.expression.as_ref() {
+                Expression::If(_) => expression,
+                _ => format!("{expression};"),
+            }
+        }
+        Statement::Block(block) => format_block(block, indent),
+        Statement::While(while_statement) => format!(
+            "while ({}) {}",
+            format_expression(&while_statement.condition, indent),
+            format_body(&while_statement.body, indent)
+        ),
+    }
+}
+
+fn format_block(block: &BlockStatement, indent: usize) -> String {
+    if block.statements.is_empty() {
+        return String::from("{}");
+    }
+
+    let statements = block
+        .statements
+        .iter()
+        .map(|statement| {
+            format!(
+                "{}{}",
+                INDENT.repeat(indent + 1),
+                format_statement(statement, indent + 1)
+            )
+        })
+        .reduce(|acc, cur| format!("{acc}\n{cur}"))
+        .unwrap_or(String::new());
+
+    format!("{{\n{statements}\n{}}}", INDENT.repeat(indent))
+}
+
+fn format_body(body: &Statement, indent: usize) -> String {
+    match body {
+        Statement::Block(block) => format_block(block, indent),
+        statement => format_statement(statement, indent),
+    }
+}
+
+fn format_expression(expression: &Expression, indent: usize) -> String {
+    match expression {
+        Expression::Identifier(ident) => ident.to_string(),
+        Expression::IntegerLiteral(int) => int.to_string(),
+        Expression::FloatLiteral(float) => float.to_string(),
+        Expression::StringLiteral(string) => format!("\"{string}\""),
+        Expression::Boolean(boolean) => boolean.to_string(),
+        Expression::Prefix(prefix) => format!(
+            "{}{}",
+            prefix.token,
+            format_operand(&prefix.right, ExpressionType::Prefix as usize, indent)
+        ),
+        Expression::Infix(infix) => {
+            let precedence = precedence(expression);
+
+            format!(
+                "{} {} {}",
+                format_operand(&infix.left, precedence, indent),
+                infix.token,
+                format_operand(&infix.right, precedence + 1, indent)
+            )
+        }
+        Expression::If(if_expr) => {
+            let mut buffer = format!(
+                "if ({}) {}",
+                format_expression(&if_expr.condition, indent),
+                format_body(&if_expr.consequence, indent)
+            );
+
+            if let Some(alternative) = if_expr.alternative.as_ref() {
+                buffer.push_str(&format!(" else {}", format_body(alternative, indent)));
+            }
+
+            buffer
+        }
+        Expression::FunctionLiteral(func) => format!(
+            "fn({}) {}",
+            func.parameters
+                .iter()
+                .map(|p| p.to_string())
+                .reduce(|acc, cur| format!("{acc}, {cur}"))
+                .unwrap_or(String::new()),
+            format_body(&func.body, indent)
+        ),
+        Expression::Call(call) => format!(
+            "{}({})",
+            format_operand(&call.function, ExpressionType::Call as usize, indent),
+            format_list(call.arguments.iter().map(|a| a.as_ref()), indent)
+        ),
+        Expression::ArrayLiteral(array) => format!(
+            "[{}]",
+            format_list(array.elements.iter().map(|e| e.as_ref()), indent)
+        ),
+        Expression::IndexExpression(index_expr) => format!(
+            "{}[{}]",
+            format_operand(&index_expr.left, ExpressionType::Index as usize, indent),
+            format_expression(&index_expr.index, indent)
+        ),
+        Expression::HashLiteral(hash_literal) => format!(
+            "{{{}}}",
+            hash_literal
+                .pairs
+                .iter()
+                .map(|(key, value)| format!(
+                    "{}: {}",
+                    format_expression(key, indent),
+                    format_expression(value, indent)
+                ))
+                .reduce(|acc, cur| format!("{acc}, {cur}"))
+                .unwrap_or(String::new())
+        ),
+        Expression::Assign(assign) => format!(
+            "{} = {}",
+            assign.name,
+            format_expression(&assign.value, indent)
+        ),
+    }
+}
+
+fn format_list<'a>(expressions: impl Iterator<Item = &'a Expression>, indent: usize) -> String {
+    expressions
+        .map(|expression| format_expression(expression, indent))
+        .reduce(|acc, cur| format!("{acc}, {cur}"))
+        .unwrap_or(String::new())
+}
+
+fn format_operand(operand: &Expression, min_precedence: usize, indent: usize) -> String {
+    let formatted = format_expression(operand, indent);
+
+    match precedence(operand) < min_precedence {
+        true => format!("({formatted})"),
+        false => formatted,
+    }
+}
+
+fn precedence(expression: &Expression) -> usize {
+    match expression {
+        Expression::Infix(infix) => get_precedence(&Some(infix.token.clone())),
+        Expression::Assign(_) => ExpressionType::Assign as usize,
+        Expression::Prefix(_) => ExpressionType::Prefix as usize,
+        _ => ExpressionType::Index as usize + 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{lexer::lexer::Lexer, parser::parser::Parser};
+
+    fn format_input(input: &str) -> String {
+        Parser::new(Lexer::new(String::from(input)))
+            .parse_program()
+            .unwrap()
+            .format()
+    }
+
+    #[test]
+    fn format_nested_blocks_test() {
+        let input = r#"let   max=fn(a,b){if(a>b){return a;}else{let c=b;c}};
+while(x<10){x=x+1;}"#;
+
+        let expected = r#"let max = fn(a, b) {
+    if (a > b) {
+        return a;
+    } else {
+        let c = b;
+        c;
+    }
+};
+while (x < 10) {
+    x = x + 1;
+}"#;
+
+        assert_eq!(format_input(input), expected);
+    }
+
+    #[test]
+    fn format_expressions_test() {
+        let expected = vec![
+            ("1+2*3", "1 + 2 * 3;"),
+            ("(1+2)*3", "(1 + 2) * 3;"),
+            ("1-(2-3)", "1 - (2 - 3);"),
+            ("(1-2)-3", "1 - 2 - 3;"),
+            ("-(a+b)", "-(a + b);"),
+            ("!-a", "!-a;"),
+            ("a||b&&c", "a || b && c;"),
+            ("(a||b)&&c", "(a || b) && c;"),
+            ("add(1,2*3)[0]", "add(1, 2 * 3)[0];"),
+            (
+                "[1,\"two\",{\"a\":1,2:[]}]",
+                "[1, \"two\", {\"a\": 1, 2: []}];",
+            ),
+            ("fn(){}", "fn() {};"),
+            ("a ? b : c", "if (a) {\n    b;\n} else {\n    c;\n}"),
+        ];
+
+        for (input, expected_result) in expected {
+            assert_eq!(format_input(input), expected_result, "formatting {input}");
+        }
+    }
+
+    #[test]
+    fn format_is_stable_test() {
+        let inputs = vec![
+            "let fib = fn(n) { if (n < 2) { n } else { fib(n - 1) + fib(n - 2) } }; fib(10);",
+            "let f = fn(x) { fn(y) { x * (y + 1) } }; f(2)(3) == 8",
+            "while (i < len(arr)) { let x = arr[i]; i = i + 1; }",
+        ];
+
+        for input in inputs {
+            let formatted = format_input(input);
+            assert_eq!(format_input(&formatted), formatted, "formatting {input}");
+        }
+    }
+}
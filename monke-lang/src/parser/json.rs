@@ -0,0 +1,269 @@
+use super::ast::{BlockStatement, Expression, Identifier, Program, Statement};
+
+impl Program {
+    pub fn to_json(&self) -> String {
+        match self {
+            Program::Statement(statement) => statement_to_json(statement),
+            Program::Statements(statements) => object(&[
+                ("type", string("Program")),
+                (
+                    "statements",
+                    array(statements.iter().map(|s| statement_to_json(s))),
+                ),
+            ]),
+            Program::Expression(expression) => expression_to_json(expression),
+        }
+    }
+}
+
+fn statement_to_json(statement: &Statement) -> String {
+    match statement {
+        Statement::Let(let_statement) => object(&[
+            ("type", string("LetStatement")),
+            ("name", identifier(&let_statement.name)),
+            ("value", expression_to_json(&let_statement.value)),
+        ]),
+        Statement::Return(return_statement) => object(&[
+            ("type", string("ReturnStatement")),
+            ("value", expression_to_json(&return_statement.return_value)),
+        ]),
+        Statement::Expression(expression_statement) => object(&[
+            ("type", string("ExpressionStatement")),
+            (
+                "expression",
+                expression_to_json(&expression_statement.expression),
+            ),
+        ]),
+        Statement::Block(block) => block_to_json(block),
+        Statement::While(while_statement) => object(&[
+            ("type", string("WhileStatement")),
+            ("condition", expression_to_json(&while_statement.condition)),
+            ("body", statement_to_json(&while_statement.body)),
+        ]),
+    }
+}
+
+fn block_to_json(block: &BlockStatement) -> String {
+    object(&[
+        ("type", string("BlockStatement")),
+        (
+            "statements",
+            array(block.statements.iter().map(|s| statement_to_json(s))),
+        ),
+    ])
+}
+
+fn expression_to_json(expression: &Expression) -> String {
+    match expression {
+        Expression::Identifier(ident) => identifier(ident),
+        Expression::IntegerLiteral(int) => object(&[
+            ("type", string("IntegerLiteral")),
+            ("value", int.value.to_string()),
+        ]),
+        Expression::FloatLiteral(float) => object(&[
+            ("type", string("FloatLiteral")),
+            (
+                "value",
+                match float.value.is_finite() {
+                    true => format!("{:?}", float.value),
+                    false => String::from("null"),
+                },
+            ),
+        ]),
+        Expression::StringLiteral(literal) => object(&[
+            ("type", string("StringLiteral")),
+            ("value", string(&literal.token.to_string())),
+        ]),
+        Expression::Boolean(boolean) => object(&[
+            ("type", string("Boolean")),
+            ("value", boolean.value.to_string()),
+        ]),
+        Expression::Prefix(prefix) => object(&[
+            ("type", string("PrefixExpression")),
+            ("operator", string(&prefix.token.to_string())),
+            ("right", expression_to_json(&prefix.right)),
+        ]),
+        Expression::Infix(infix) => object(&[
+            ("type", string("InfixExpression")),
+            ("operator", string(&infix.token.to_string())),
+            ("left", expression_to_json(&infix.left)),
+            ("right", expression_to_json(&infix.right)),
+        ]),
+        Expression::If(if_expr) => object(&[
+            ("type", string("IfExpression")),
+            ("condition", expression_to_json(&if_expr.condition)),
+            ("consequence", statement_to_json(&if_expr.consequence)),
+            (
+                "alternative",
+                if_expr
+                    .alternative
+                    .as_ref()
+                    .map(|alt| statement_to_json(alt))
+                    .unwrap_or(String::from("null")),
+            ),
+        ]),
+        Expression::FunctionLiteral(func) => object(&[
+            ("type", string("FunctionLiteral")),
+            ("name", string(&func.name)),
+            ("parameters", array(func.parameters.iter().map(identifier))),
+            ("body", statement_to_json(&func.body)),
+        ]),
+        Expression::Call(call) => object(&[
+            ("type", string("CallExpression")),
+            ("function", expression_to_json(&call.function)),
+            (
+                "arguments",
+                array(call.arguments.iter().map(|a| expression_to_json(a))),
+            ),
+        ]),
+        Expression::ArrayLiteral(array_literal) => object(&[
+            ("type", string("ArrayLiteral")),
+            (
+                "elements",
+                array(array_literal.elements.iter().map(|e| expression_to_json(e))),
+            ),
+        ]),
+        Expression::IndexExpression(index_expr) => object(&[
+            ("type", string("IndexExpression")),
+            ("left", expression_to_json(&index_expr.left)),
+            ("index", expression_to_json(&index_expr.index)),
+        ]),
+        Expression::HashLiteral(hash_literal) => object(&[
+            ("type", string("HashLiteral")),
+            (
+                "pairs",
+                array(hash_literal.pairs.iter().map(|(key, value)| {
+                    object(&[
+                        ("key", expression_to_json(key)),
+                        ("value", expression_to_json(value)),
+                    ])
+                })),
+            ),
+        ]),
+        Expression::Assign(assign) => object(&[
+            ("type", string("AssignExpression")),
+            ("name", identifier(&assign.name)),
+            ("value", expression_to_json(&assign.value)),
+        ]),
+    }
+}
+
+fn identifier(ident: &Identifier) -> String {
+    object(&[
+        ("type", string("Identifier")),
+        ("name", string(&ident.to_string())),
+    ])
+}
+
+fn object(fields: &[(&str, String)]) -> String {
+    let fields = fields
+        .iter()
+        .map(|(key, value)| format!("{}:{value}", string(key)))
+        .reduce(|acc, cur| format!("{acc},{cur}"))
+        .unwrap_or(String::new());
+
+    format!("{{{fields}}}")
+}
+
+fn array(elements: impl Iterator<Item = String>) -> String {
+    let elements = elements
+        .reduce(|acc, cur| format!("{acc},{cur}"))
+        .unwrap_or(String::new());
+
+    format!("[{elements}]")
+}
+
+fn string(value: &str) -> String {
+    let mut buffer = String::from("\"");
+
+    for ch in value.chars() {
+        match ch {
+            '"' => buffer.push_str("\\\""),
+            '\\' => buffer.push_str("\\\\"),
+            '\n' => buffer.push_str("\\n"),
+            '\r' => buffer.push_str("\\r"),
+            '\t' => buffer.push_str("\\t"),
+            ch if ch.is_control() => buffer.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => buffer.push(ch),
+        }
+    }
+
+    buffer.push('"');
+    buffer
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{lexer::lexer::Lexer, parser::parser::Parser};
+
+    fn parse_to_json(input: &str) -> String {
+        Parser::new(Lexer::new(String::from(input)))
+            .parse_program()
+            .unwrap()
+            .to_json()
+    }
+
+    #[test]
+    fn let_statement_json_test() {
+        let expected = concat!(
+            r#"{"type":"Program","statements":[{"type":"LetStatement","#,
+            r#""name":{"type":"Identifier","name":"x"},"#,
+            r#""value":{"type":"InfixExpression","operator":"+","#,
+            r#""left":{"type":"IntegerLiteral","value":1},"#,
+            r#""right":{"type":"IntegerLiteral","value":2}}}]}"#
+        );
+
+        assert_eq!(parse_to_json("let x = 1 + 2;"), expected);
+    }
+
+    #[test]
+    fn expressions_json_test() {
+        let expected = vec![
+            ("true", r#"{"type":"Boolean","value":true}"#),
+            ("2.5", r#"{"type":"FloatLiteral","value":2.5}"#),
+            (
+                "\"tab\tand\\\"",
+                r#"{"type":"StringLiteral","value":"tab\tand\\"}"#,
+            ),
+            (
+                "-a",
+                r#"{"type":"PrefixExpression","operator":"-","right":{"type":"Identifier","name":"a"}}"#,
+            ),
+            (
+                "if (a) { 1 }",
+                concat!(
+                    r#"{"type":"IfExpression","condition":{"type":"Identifier","name":"a"},"#,
+                    r#""consequence":{"type":"BlockStatement","statements":[{"type":"ExpressionStatement","#,
+                    r#""expression":{"type":"IntegerLiteral","value":1}}]},"alternative":null}"#
+                ),
+            ),
+            (
+                "f(x)[0]",
+                concat!(
+                    r#"{"type":"IndexExpression","left":{"type":"CallExpression","#,
+                    r#""function":{"type":"Identifier","name":"f"},"#,
+                    r#""arguments":[{"type":"Identifier","name":"x"}]},"#,
+                    r#""index":{"type":"IntegerLiteral","value":0}}"#
+                ),
+            ),
+            (
+                "{\"a\": [fn(b) { b }]}",
+                concat!(
+                    r#"{"type":"HashLiteral","pairs":[{"key":{"type":"StringLiteral","value":"a"},"#,
+                    r#""value":{"type":"ArrayLiteral","elements":[{"type":"FunctionLiteral","name":"","#,
+                    r#""parameters":[{"type":"Identifier","name":"b"}],"#,
+                    r#""body":{"type":"BlockStatement","statements":[{"type":"ExpressionStatement","#,
+                    r#""expression":{"type":"Identifier","name":"b"}}]}}]}}]}"#
+                ),
+            ),
+        ];
+
+        for (input, expected_result) in expected {
+            let expected_result = format!(
+                r#"{{"type":"Program","statements":[{{"type":"ExpressionStatement","expression":{expected_result}}}]}}"#
+            );
+
+            assert_eq!(parse_to_json(input), expected_result, "json of {input}");
+        }
+    }
+}
@@ -0,0 +1,402 @@
+use std::fmt::Display;
+
+use super::ast::{BlockStatement, Expression, FunctionLiteral, Program, Statement};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiagnosticKind {
+    UnreachableStatement,
+    UnusedBinding,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub kind: DiagnosticKind,
+    pub message: String,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Program {
+    pub fn lint_unreachable(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![];
+
+        match self {
+            Program::Statement(statement) => unreachable_in_statement(statement, &mut diagnostics),
+            Program::Statements(statements) => statements
+                .iter()
+                .for_each(|statement| unreachable_in_statement(statement, &mut diagnostics)),
+            Program::Expression(expression) => {
+                unreachable_in_expression(expression, &mut diagnostics)
+            }
+        }
+
+        diagnostics
+    }
+
+    pub fn lint_unused(&self) -> Vec<Diagnostic> {
+        let mut lint = UnusedLint {
+            scopes: vec![],
+            diagnostics: vec![],
+        };
+
+        lint.push_scope();
+
+        match self {
+            Program::Statement(statement) => lint.visit_statement(statement),
+            Program::Statements(statements) => statements
+                .iter()
+                .for_each(|statement| lint.visit_statement(statement)),
+            Program::Expression(expression) => lint.visit_expression(expression),
+        }
+
+        lint.pop_scope();
+        lint.diagnostics
+    }
+}
+
+fn unreachable_in_statement(statement: &Statement, diagnostics: &mut Vec<Diagnostic>) {
+    match statement {
+        Statement::Let(let_statement) => {
+            unreachable_in_expression(&let_statement.value, diagnostics)
+        }
+        Statement::Return(return_statement) => {
+            unreachable_in_expression(&return_statement.return_value, diagnostics)
+        }
+        Statement::Expression(expression_statement) => {
+            unreachable_in_expression(&expression_statement.expression, diagnostics)
+        }
+        Statement::Block(block) => unreachable_in_block(block, diagnostics),
+        Statement::While(while_statement) => {
+            unreachable_in_expression(&while_statement.condition, diagnostics);
+            unreachable_in_statement(&while_statement.body, diagnostics);
+        }
+    }
+}
+
+fn unreachable_in_block(block: &BlockStatement, diagnostics: &mut Vec<Diagnostic>) {
+    let first_unreachable = block
+        .statements
+        .iter()
+        .position(|statement| matches!(statement.as_ref(), Statement::Return(_)))
+        .and_then(|return_idx| block.statements.get(return_idx + 1));
+
+    if let Some(statement) = first_unreachable {
+        diagnostics.push(Diagnostic {
+            kind: DiagnosticKind::UnreachableStatement,
+            message: format!("unreachable statement after return: {statement}"),
+        });
+    }
+
+    for statement in &block.statements {
+        unreachable_in_statement(statement, diagnostics);
+    }
+}
+
+fn unreachable_in_expression(expression: &Expression, diagnostics: &mut Vec<Diagnostic>) {
+    match expression {
+        Expression::Identifier(_)
+        | Expression::IntegerLiteral(_)
+        | Expression::FloatLiteral(_)
+        | Expression::StringLiteral(_)
+        | Expression::Boolean(_) => (),
+        Expression::Prefix(prefix) => unreachable_in_expression(&prefix.right, diagnostics),
+        Expression::Infix(infix) => {
+            unreachable_in_expression(&infix.left, diagnostics);
+            unreachable_in_expression(&infix.right, diagnostics);
+        }
+        Expression::If(if_expr) => {
+            unreachable_in_expression(&if_expr.condition, diagnostics);
+            unreachable_in_statement(&if_expr.consequence, diagnostics);
+
+            if let Some(alternative) = if_expr.alternative.as_ref() {
+                unreachable_in_statement(alternative, diagnostics);
+            }
+        }
+        Expression::FunctionLiteral(func) => unreachable_in_statement(&func.body, diagnostics),
+        Expression::Call(call) => {
+            unreachable_in_expression(&call.function, diagnostics);
+            call.arguments
+                .iter()
+                .for_each(|arg| unreachable_in_expression(arg, diagnostics));
+        }
+        Expression::ArrayLiteral(array) => array
+            .elements
+            .iter()
+            .for_each(|element| unreachable_in_expression(element, diagnostics)),
+        Expression::IndexExpression(index_expr) => {
+            unreachable_in_expression(&index_expr.left, diagnostics);
+            unreachable_in_expression(&index_expr.index, diagnostics);
+        }
+        Expression::HashLiteral(hash_literal) => {
+            for (key, value) in &hash_literal.pairs {
+                unreachable_in_expression(key, diagnostics);
+                unreachable_in_expression(value, diagnostics);
+            }
+        }
+        Expression::Assign(assign) => unreachable_in_expression(&assign.value, diagnostics),
+    }
+}
+
+struct Binding {
+    name: String,
+    used: bool,
+    parameter: bool,
+}
+
+struct Scope<'a> {
+    bindings: Vec<Binding>,
+    functions: Vec<&'a FunctionLiteral>,
+}
+
+struct UnusedLint<'a> {
+    scopes: Vec<Scope<'a>>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> UnusedLint<'a> {
+    fn push_scope(&mut self) {
+        self.scopes.push(Scope {
+            bindings: vec![],
+            functions: vec![],
+        });
+    }
+
+    fn pop_scope(&mut self) {
+        // function bodies are resolved at the end of their enclosing scope, the same
+        // way closures see every binding of that scope once they are called
+        while let Some(func) = self
+            .scopes
+            .last_mut()
+            .and_then(|scope| scope.functions.pop())
+        {
+            self.visit_function(func);
+        }
+
+        let Some(scope) = self.scopes.pop() else {
+            return;
+        };
+
+        for binding in scope.bindings {
+            if !binding.used && !binding.parameter {
+                self.diagnostics.push(Diagnostic {
+                    kind: DiagnosticKind::UnusedBinding,
+                    message: format!("unused variable: {}", binding.name),
+                });
+            }
+        }
+    }
+
+    fn define(&mut self, name: String, parameter: bool) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.bindings.push(Binding {
+                name,
+                used: false,
+                parameter,
+            });
+        }
+    }
+
+    fn resolve(&mut self, name: &str) {
+        let binding = self
+            .scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.bindings.iter_mut().rev().find(|b| b.name == name));
+
+        if let Some(binding) = binding {
+            binding.used = true;
+        }
+    }
+
+    fn visit_function(&mut self, func: &'a FunctionLiteral) {
+        self.push_scope();
+
+        for parameter in &func.parameters {
+            self.define(parameter.to_string(), true);
+        }
+
+        self.visit_statement(&func.body);
+        self.pop_scope();
+    }
+
+    fn visit_statement(&mut self, statement: &'a Statement) {
+        match statement {
+            Statement::Let(let_statement) => {
+                self.visit_expression(&let_statement.value);
+                self.define(let_statement.name.to_string(), false);
+            }
+            Statement::Return(return_statement) => {
+                self.visit_expression(&return_statement.return_value)
+            }
+            Statement::Expression(expression_statement) => {
+                self.visit_expression(&expression_statement.expression)
+            }
+            Statement::Block(block) => block
+                .statements
+                .iter()
+                .for_each(|statement| self.visit_statement(statement)),
+            Statement::While(while_statement) => {
+                self.visit_expression(&while_statement.condition);
+                self.visit_statement(&while_statement.body);
+            }
+        }
+    }
+
+    fn visit_expression(&mut self, expression: &'a Expression) {
+        match expression {
+            Expression::IntegerLiteral(_)
+            | Expression::FloatLiteral(_)
+            | Expression::StringLiteral(_)
+            | Expression::Boolean(_) => (),
+            Expression::Identifier(ident) => self.resolve(&ident.to_string()),
+            Expression::Prefix(prefix) => self.visit_expression(&prefix.right),
+            Expression::Infix(infix) => {
+                self.visit_expression(&infix.left);
+                self.visit_expression(&infix.right);
+            }
+            Expression::If(if_expr) => {
+                self.visit_expression(&if_expr.condition);
+                self.visit_statement(&if_expr.consequence);
+
+                if let Some(alternative) = if_expr.alternative.as_ref() {
+                    self.visit_statement(alternative);
+                }
+            }
+            Expression::FunctionLiteral(func) => {
+                if let Some(scope) = self.scopes.last_mut() {
+                    scope.functions.push(func);
+                }
+            }
+            Expression::Call(call) => {
+                self.visit_expression(&call.function);
+                call.arguments
+                    .iter()
+                    .for_each(|arg| self.visit_expression(arg));
+            }
+            Expression::ArrayLiteral(array) => array
+                .elements
+                .iter()
+                .for_each(|element| self.visit_expression(element)),
+            Expression::IndexExpression(index_expr) => {
+                self.visit_expression(&index_expr.left);
+                self.visit_expression(&index_expr.index);
+            }
+            Expression::HashLiteral(hash_literal) => {
+                for (key, value) in &hash_literal.pairs {
+                    self.visit_expression(key);
+                    self.visit_expression(value);
+                }
+            }
+            Expression::Assign(assign) => self.visit_expression(&assign.value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Diagnostic, DiagnosticKind};
+    use crate::{lexer::lexer::Lexer, parser::parser::Parser};
+
+    fn lint_input(input: &str) -> Vec<Diagnostic> {
+        Parser::new(Lexer::new(String::from(input)))
+            .parse_program()
+            .unwrap()
+            .lint_unreachable()
+    }
+
+    #[test]
+    fn lint_unreachable_test() {
+        let diagnostics = lint_input("let f = fn() { return 1; 2; };");
+
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic {
+                kind: DiagnosticKind::UnreachableStatement,
+                message: String::from("unreachable statement after return: 2"),
+            }]
+        );
+
+        let expected = vec![
+            ("let f = fn() { return 1; };", vec![]),
+            ("let f = fn(x) { if (x) { return 1; } 2; };", vec![]),
+            (
+                "let f = fn(x) { if (x) { return 1; x; x; } else { return 2; let y = 3; } };",
+                vec![
+                    "unreachable statement after return: x",
+                    "unreachable statement after return: let y = 3;",
+                ],
+            ),
+            (
+                "while (true) { return 1; puts(x); f(fn() { return 2; 3; }); }",
+                vec![
+                    "unreachable statement after return: puts(x)",
+                    "unreachable statement after return: 3",
+                ],
+            ),
+        ];
+
+        for (input, expected_messages) in expected {
+            let messages: Vec<_> = lint_input(input)
+                .iter()
+                .map(|diagnostic| diagnostic.to_string())
+                .collect();
+
+            assert_eq!(messages, expected_messages, "linting {input}");
+        }
+    }
+
+    fn lint_unused_input(input: &str) -> Vec<String> {
+        Parser::new(Lexer::new(String::from(input)))
+            .parse_program()
+            .unwrap()
+            .lint_unused()
+            .iter()
+            .map(|diagnostic| diagnostic.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn lint_unused_test() {
+        assert_eq!(
+            Parser::new(Lexer::new(String::from("let x = 1; let y = 2; x;")))
+                .parse_program()
+                .unwrap()
+                .lint_unused(),
+            vec![Diagnostic {
+                kind: DiagnosticKind::UnusedBinding,
+                message: String::from("unused variable: y"),
+            }]
+        );
+
+        let expected = vec![
+            ("let x = 1; x;", vec![]),
+            ("let x = 1; let x = x + 1; x;", vec![]),
+            ("let x = 1; let x = 2; x;", vec!["unused variable: x"]),
+            ("let x = 1; let f = fn(x) { x }; f(2);", vec!["unused variable: x"]),
+            ("let x = 1; let f = fn() { x }; f();", vec![]),
+            ("let f = fn() { x }; let x = 1; f();", vec![]),
+            (
+                "let f = fn(a) { let b = a; let c = 1; fn() { b } }; f(1);",
+                vec!["unused variable: c"],
+            ),
+            (
+                "let isEven = fn(n) { if (n == 0) { true } else { isOdd(n - 1) } }; let isOdd = fn(n) { if (n == 0) { false } else { isEven(n - 1) } }; isEven(2);",
+                vec![],
+            ),
+            ("let x = 0; while (x < 3) { x = x + 1; }", vec![]),
+            ("let x = 0; x = 1;", vec!["unused variable: x"]),
+        ];
+
+        for (input, expected_messages) in expected {
+            assert_eq!(
+                lint_unused_input(input),
+                expected_messages,
+                "linting {input}"
+            );
+        }
+    }
+}
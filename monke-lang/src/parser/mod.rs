@@ -1,2 +1,5 @@
 pub mod ast;
+mod formatter;
+mod json;
+pub mod lint;
 pub mod parser;
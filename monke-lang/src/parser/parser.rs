@@ -1,13 +1,16 @@
-use std::collections::HashMap;
 use std::rc::Rc;
 
-use super::super::result::MonkeyResult;
+use super::super::result::{MonkeyError, MonkeyResult};
 use super::ast::{
-    ArrayLiteral, BlockStatement, Boolean, CallExpression, Expression, FunctionLiteral,
-    HashLiteral, Identifier, IfExpression, IndexExpression, InfixExpression, IntegerLiteral,
-    LetStatement, PrefixExpression, Program, ReturnStatement, Statement, StringLiteral,
+    ArrayLiteral, AssignExpression, BlockStatement, Boolean, CallExpression, Expression,
+    FloatLiteral, FunctionLiteral, HashLiteral, Identifier, IfExpression, IndexExpression,
+    InfixExpression, IntegerLiteral, LetStatement, PrefixExpression, Program, ReturnStatement,
+    Statement, StringLiteral, WhileStatement,
+};
+use crate::lexer::{
+    lexer::Lexer,
+    token::{Position, Span, Token},
 };
-use crate::lexer::{lexer::Lexer, token::Token};
 use crate::parser::ast::{ExpressionStatement, ExpressionType};
 
 #[derive(Debug)]
@@ -15,6 +18,9 @@ pub struct Parser {
     lexer: Lexer,
     cur_token: Option<Token>,
     peek_token: Option<Token>,
+    cur_span: Span,
+    peek_span: Span,
+    errors: Vec<MonkeyError>,
 }
 
 type ParsePrefixFn = fn(&mut Parser) -> MonkeyResult<Expression>;
@@ -22,27 +28,66 @@ type ParseInfixFn = fn(&mut Parser, Expression) -> MonkeyResult<Expression>;
 
 impl Parser {
     pub fn new(mut lexer: Lexer) -> Self {
-        let cur_token = lexer.next_token();
-        let peek_token = lexer.next_token();
+        let (cur_token, cur_span) = lexer.next_token_with_span();
+        let (peek_token, peek_span) = lexer.next_token_with_span();
 
         Parser {
             lexer,
             cur_token,
             peek_token,
+            cur_span,
+            peek_span,
+            errors: vec![],
         }
     }
 
-    pub fn parse_program(&mut self) -> MonkeyResult<Program> {
+    pub fn parse_program(&mut self) -> Result<Program, MonkeyError> {
         let mut statements = vec![];
 
         while self.cur_token.is_some() {
-            let statement = self.parse_statement()?;
-            statements.push(Rc::new(statement));
+            match self.parse_statement() {
+                Ok(statement) => statements.push(Rc::new(statement)),
+                Err(message) => {
+                    let position = Some(self.cur_span.start);
+
+                    self.errors.push(match self.cur_token {
+                        Some(Token::Illegal(_)) => MonkeyError::Lex { message, position },
+                        _ => MonkeyError::Parse { message, position },
+                    });
+                    self.skip_to_next_statement();
+                    continue;
+                }
+            }
 
             self.next_token();
         }
 
-        Ok(Program::Statements(statements))
+        match self.errors.as_slice() {
+            [] => Ok(Program::Statements(statements)),
+            [err] => Err(err.clone()),
+            [first, rest @ ..] => Err(MonkeyError::Parse {
+                message: std::iter::once(first.message().to_string())
+                    .chain(rest.iter().map(|err| err.to_string()))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                position: first.position(),
+            }),
+        }
+    }
+
+    pub fn errors(&self) -> &[MonkeyError] {
+        &self.errors
+    }
+
+    fn skip_to_next_statement(&mut self) {
+        while let Some(token) = &self.cur_token {
+            let is_semicolon = token == &Token::Semicolon;
+            self.next_token();
+
+            if is_semicolon || matches!(self.cur_token, Some(Token::Let) | Some(Token::Return)) {
+                return;
+            }
+        }
     }
 
     fn parse_statement(&mut self) -> MonkeyResult<Statement> {
@@ -50,6 +95,7 @@ impl Parser {
             Some(token) => match token {
                 Token::Let => Ok(self.parse_let_statement()?),
                 Token::Return => Ok(self.parse_return_statement()?),
+                Token::While => Ok(self.parse_while_statement()?),
                 _ => Ok(self.parse_expression_statement()?),
             },
             None => Err(String::from(
@@ -60,10 +106,20 @@ impl Parser {
 
     fn next_token(&mut self) {
         self.cur_token = self.peek_token.clone();
-        self.peek_token = self.lexer.next_token();
+        self.cur_span = self.peek_span;
+        (self.peek_token, self.peek_span) = self.lexer.next_token_with_span();
+    }
+
+    fn span_from(&self, start: Position) -> Span {
+        Span {
+            start,
+            end: self.cur_span.end,
+        }
     }
 
     fn parse_let_statement(&mut self) -> MonkeyResult<Statement> {
+        let start = self.cur_span.start;
+
         if !self.expect_peek(Token::Ident(String::new())) {
             return Err(String::from(
                 "unable to parse let statement, identifier expected",
@@ -71,6 +127,7 @@ impl Parser {
         }
 
         let statement_name = self.cur_token.clone().unwrap();
+        let name_span = self.cur_span;
 
         if !self.expect_peek(Token::Assign) {
             return Err(String::from(
@@ -89,6 +146,7 @@ impl Parser {
 
                 Rc::new(Expression::FunctionLiteral(FunctionLiteral {
                     token: func_literal_copy.token,
+                    span: func_literal_copy.span,
                     parameters: func_literal_copy.parameters,
                     body: func_literal_copy.body,
                     name: match statement_name.clone() {
@@ -110,8 +168,10 @@ impl Parser {
 
         Ok(Statement::Let(LetStatement {
             token: Token::Let,
+            span: self.span_from(start),
             name: Identifier {
                 token: statement_name,
+                span: name_span,
             },
             value,
         }))
@@ -119,6 +179,7 @@ impl Parser {
 
     fn parse_return_statement(&mut self) -> MonkeyResult<Statement> {
         let token = self.cur_token.clone().unwrap();
+        let start = self.cur_span.start;
 
         self.next_token();
 
@@ -134,12 +195,49 @@ impl Parser {
 
         Ok(Statement::Return(ReturnStatement {
             token,
+            span: self.span_from(start),
             return_value,
         }))
     }
 
+    fn parse_while_statement(&mut self) -> MonkeyResult<Statement> {
+        let token = self.cur_token.clone().unwrap();
+        let start = self.cur_span.start;
+
+        if !self.expect_peek(Token::Lparen) {
+            return Err(String::from(
+                "unable to parse while statement, couldn't find opening parentheses",
+            ));
+        }
+
+        self.next_token();
+        let condition = Rc::new(self.parse_expression(ExpressionType::Lowest as usize)?);
+
+        if !self.expect_peek(Token::Rparen) {
+            return Err(String::from(
+                "unable to parse while statement, couldn't find closing parentheses",
+            ));
+        }
+
+        if !self.expect_peek(Token::Lbrace) {
+            return Err(String::from(
+                "unable to parse while statement, couldn't find opening brace",
+            ));
+        }
+
+        let body = Rc::new(self.parse_block_statement()?);
+
+        Ok(Statement::While(WhileStatement {
+            token,
+            span: self.span_from(start),
+            condition,
+            body,
+        }))
+    }
+
     fn parse_expression_statement(&mut self) -> MonkeyResult<Statement> {
         let cur_token = self.cur_token.clone().unwrap();
+        let start = self.cur_span.start;
         let statement_expression = Rc::new(self.parse_expression(ExpressionType::Lowest as usize)?);
 
         if self
@@ -152,12 +250,14 @@ impl Parser {
 
         Ok(Statement::Expression(ExpressionStatement {
             token: cur_token,
+            span: self.span_from(start),
             expression: statement_expression,
         }))
     }
 
     fn parse_block_statement(&mut self) -> MonkeyResult<Statement> {
         let token = self.cur_token.clone().unwrap();
+        let start = self.cur_span.start;
         let mut statements = vec![];
 
         self.next_token();
@@ -167,7 +267,17 @@ impl Parser {
             self.next_token();
         }
 
-        Ok(Statement::Block(BlockStatement { token, statements }))
+        if self.cur_token.is_none() {
+            return Err(String::from(
+                "unable to parse block, closing brace expected",
+            ));
+        }
+
+        Ok(Statement::Block(BlockStatement {
+            token,
+            span: self.span_from(start),
+            statements,
+        }))
     }
 
     fn parse_expression(&mut self, precedence: usize) -> MonkeyResult<Expression> {
@@ -210,17 +320,19 @@ impl Parser {
             Some(t) => match t {
                 Token::Ident(_) => Ok(Self::parse_identifier),
                 Token::Int(_) => Ok(Self::parse_integer_literal),
-                token if token == &Token::Minus || token == &Token::Bang => {
-                    Ok(Self::parse_prefix_expression)
-                }
+                Token::Float(_) => Ok(Self::parse_float_literal),
+                Token::Minus | Token::Bang | Token::Plus => Ok(Self::parse_prefix_expression),
                 token if token == &Token::True || token == &Token::False => Ok(Self::parse_boolean),
                 Token::Lparen => Ok(Self::parse_grouped_expression),
                 Token::If => Ok(Self::parse_if_expression),
                 Token::Function => Ok(Self::parse_function_literal),
-                Token::String(_) => Ok(Self::parse_string),
+                Token::String(_) => Ok(Self::parse_string_literal),
                 Token::Lbracket => Ok(Self::parse_array_literal),
                 Token::Lbrace => Ok(Self::parse_hash_literal),
-                _ => todo!(),
+                Token::Illegal(err) => Err(err.clone()),
+                t => Err(format!(
+                    "unable to parse expression, no prefix parse function for \"{t}\""
+                )),
             },
             None => Err(String::from(
                 "unable to parse expression, unknown prefix expression type",
@@ -235,12 +347,17 @@ impl Parser {
                 Token::Minus => Ok(Self::parse_infix_expression),
                 Token::Asterisk => Ok(Self::parse_infix_expression),
                 Token::Slash => Ok(Self::parse_infix_expression),
+                Token::Percent => Ok(Self::parse_infix_expression),
                 Token::Lt => Ok(Self::parse_infix_expression),
                 Token::Gt => Ok(Self::parse_infix_expression),
                 Token::Eq => Ok(Self::parse_infix_expression),
                 Token::Ne => Ok(Self::parse_infix_expression),
+                Token::And => Ok(Self::parse_infix_expression),
+                Token::Or => Ok(Self::parse_infix_expression),
                 Token::Lparen => Ok(Self::parse_call_expression),
                 Token::Lbracket => Ok(Self::parse_index_expression),
+                Token::Assign => Ok(Self::parse_assign_expression),
+                Token::Question => Ok(Self::parse_conditional_expression),
                 _ => todo!(),
             },
             None => Err(String::from(
@@ -252,6 +369,7 @@ impl Parser {
     fn parse_identifier(parser: &mut Parser) -> MonkeyResult<Expression> {
         Ok(Expression::Identifier(Identifier {
             token: parser.cur_token.clone().unwrap(),
+            span: parser.cur_span,
         }))
     }
 
@@ -268,15 +386,44 @@ impl Parser {
             ));
         };
 
-        Ok(Expression::IntegerLiteral(IntegerLiteral { token, value }))
+        Ok(Expression::IntegerLiteral(IntegerLiteral {
+            token,
+            span: parser.cur_span,
+            value,
+        }))
+    }
+
+    fn parse_float_literal(parser: &mut Parser) -> MonkeyResult<Expression> {
+        let token = parser.cur_token.clone().unwrap();
+
+        let value = if let Token::Float(ref number_str) = token {
+            number_str
+                .parse::<f64>()
+                .map_err(|_| String::from("unable to parse float literal, f64 cast error"))?
+        } else {
+            return Err(String::from(
+                "unable to parse float literal, wrong token found",
+            ));
+        };
+
+        Ok(Expression::FloatLiteral(FloatLiteral {
+            token,
+            span: parser.cur_span,
+            value,
+        }))
     }
 
     fn parse_prefix_expression(parser: &mut Parser) -> MonkeyResult<Expression> {
         let token = parser.cur_token.clone().unwrap();
+        let start = parser.cur_span.start;
         parser.next_token();
         let right = Rc::new(parser.parse_expression(ExpressionType::Prefix as usize)?);
 
-        Ok(Expression::Prefix(PrefixExpression { token, right }))
+        Ok(Expression::Prefix(PrefixExpression {
+            token,
+            span: parser.span_from(start),
+            right,
+        }))
     }
 
     fn parse_infix_expression(parser: &mut Parser, left: Expression) -> MonkeyResult<Expression> {
@@ -288,11 +435,76 @@ impl Parser {
 
         Ok(Expression::Infix(InfixExpression {
             token: cur_token.unwrap(),
+            span: parser.span_from(left.span().start),
             left: Rc::new(left),
             right,
         }))
     }
 
+    fn parse_assign_expression(parser: &mut Parser, left: Expression) -> MonkeyResult<Expression> {
+        let token = parser.cur_token.clone().unwrap();
+
+        let name = match left {
+            Expression::Identifier(ident) => ident,
+            actual => {
+                return Err(format!(
+                    "unable to parse assign expression, identifier expected, but got \"{actual}\""
+                ))
+            }
+        };
+
+        parser.next_token();
+        let value = Rc::new(parser.parse_expression(ExpressionType::Lowest as usize)?);
+
+        Ok(Expression::Assign(AssignExpression {
+            token,
+            span: parser.span_from(name.span.start),
+            name,
+            value,
+        }))
+    }
+
+    fn parse_conditional_expression(
+        parser: &mut Parser,
+        condition: Expression,
+    ) -> MonkeyResult<Expression> {
+        parser.next_token();
+        let consequence = parser.parse_branch_block(ExpressionType::Lowest as usize)?;
+
+        if !parser.expect_peek(Token::Colon) {
+            return Err(String::from(
+                "unable to parse conditional expression, couldn't find colon",
+            ));
+        }
+
+        parser.next_token();
+        let alternative = parser.parse_branch_block(ExpressionType::Assign as usize)?;
+
+        Ok(Expression::If(IfExpression {
+            token: Token::If,
+            span: parser.span_from(condition.span().start),
+            condition: Rc::new(condition),
+            consequence: Rc::new(consequence),
+            alternative: Some(Rc::new(alternative)),
+        }))
+    }
+
+    fn parse_branch_block(&mut self, precedence: usize) -> MonkeyResult<Statement> {
+        let token = self.cur_token.clone().unwrap();
+        let expression = Rc::new(self.parse_expression(precedence)?);
+        let span = expression.span();
+
+        Ok(Statement::Block(BlockStatement {
+            token: Token::Lbrace,
+            span,
+            statements: vec![Rc::new(Statement::Expression(ExpressionStatement {
+                token,
+                span,
+                expression,
+            }))],
+        }))
+    }
+
     fn parse_boolean(parser: &mut Parser) -> MonkeyResult<Expression> {
         let cur_token = parser.cur_token.clone().unwrap();
         let is_true = cur_token == Token::True;
@@ -300,6 +512,7 @@ impl Parser {
         Ok(Expression::Boolean(Boolean {
             value: is_true,
             token: cur_token,
+            span: parser.cur_span,
         }))
     }
 
@@ -318,6 +531,7 @@ impl Parser {
 
     fn parse_if_expression(parser: &mut Parser) -> MonkeyResult<Expression> {
         let token = parser.cur_token.clone().unwrap();
+        let start = parser.cur_span.start;
 
         if !parser.expect_peek(Token::Lparen) {
             return Err(String::from(
@@ -362,6 +576,7 @@ impl Parser {
 
         Ok(Expression::If(IfExpression {
             token,
+            span: parser.span_from(start),
             condition,
             consequence,
             alternative,
@@ -370,6 +585,7 @@ impl Parser {
 
     fn parse_function_literal(parser: &mut Parser) -> MonkeyResult<Expression> {
         let token = parser.cur_token.clone().unwrap();
+        let start = parser.cur_span.start;
 
         if !parser.expect_peek(Token::Lparen) {
             return Err(String::from(
@@ -389,6 +605,7 @@ impl Parser {
 
         Ok(Expression::FunctionLiteral(FunctionLiteral {
             token,
+            span: parser.span_from(start),
             parameters,
             body,
             name: String::new(),
@@ -408,30 +625,13 @@ impl Parser {
         }
 
         self.next_token();
-
-        if self.cur_token.is_none() {
-            return Err(String::from(
-                "unable to parse function parameters, couldn't find identifier",
-            ));
-        }
-
-        identifiers.push(Identifier {
-            token: self.cur_token.clone().unwrap(),
-        });
+        identifiers.push(self.parse_function_parameter()?);
 
         while self.peek_token.as_ref().is_some_and(|t| t == &Token::Comma) {
             self.next_token();
             self.next_token();
 
-            if self.cur_token.is_none() {
-                return Err(String::from(
-                    "unable to parse function parameters, couldn't find identifier",
-                ));
-            }
-
-            identifiers.push(Identifier {
-                token: self.cur_token.clone().unwrap(),
-            });
+            identifiers.push(self.parse_function_parameter()?);
         }
 
         if !self.expect_peek(Token::Rparen) {
@@ -443,6 +643,21 @@ impl Parser {
         Ok(identifiers)
     }
 
+    fn parse_function_parameter(&self) -> MonkeyResult<Identifier> {
+        match &self.cur_token {
+            Some(token @ Token::Ident(_)) => Ok(Identifier {
+                token: token.clone(),
+                span: self.cur_span,
+            }),
+            Some(actual) => Err(format!(
+                "unable to parse function parameters, identifier expected, but got \"{actual}\""
+            )),
+            None => Err(String::from(
+                "unable to parse function parameters, couldn't find identifier",
+            )),
+        }
+    }
+
     fn parse_call_expression(
         parser: &mut Parser,
         function: Expression,
@@ -452,6 +667,7 @@ impl Parser {
 
         Ok(Expression::Call(CallExpression {
             token,
+            span: parser.span_from(function.span().start),
             arguments,
             function: Rc::new(function),
         }))
@@ -472,6 +688,11 @@ impl Parser {
 
         while self.peek_token.as_ref().is_some_and(|t| t == &Token::Comma) {
             self.next_token();
+
+            if self.peek_token.as_ref().is_some_and(|t| t == &end) {
+                break;
+            }
+
             self.next_token();
 
             arguments.push(Rc::new(
@@ -479,25 +700,31 @@ impl Parser {
             ));
         }
 
-        if !self.expect_peek(end) {
-            return Err(String::from(
-                "unable to parse call arguments, couldn't find closing parentheses",
+        if !self.expect_peek(end.clone()) {
+            return Err(format!(
+                "unable to parse expression list, couldn't find closing \"{end}\""
             ));
         }
 
         Ok(arguments)
     }
 
-    fn parse_string(parser: &mut Parser) -> MonkeyResult<Expression> {
+    fn parse_string_literal(parser: &mut Parser) -> MonkeyResult<Expression> {
         Ok(Expression::StringLiteral(StringLiteral {
             token: parser.cur_token.clone().unwrap(),
+            span: parser.cur_span,
         }))
     }
 
     fn parse_array_literal(parser: &mut Parser) -> MonkeyResult<Expression> {
+        let token = parser.cur_token.clone().unwrap();
+        let start = parser.cur_span.start;
+        let elements = parser.parse_expression_list(Token::Rbracket)?;
+
         Ok(Expression::ArrayLiteral(ArrayLiteral {
-            token: parser.cur_token.clone().unwrap(),
-            elements: parser.parse_expression_list(Token::Rbracket)?,
+            token,
+            span: parser.span_from(start),
+            elements,
         }))
     }
 
@@ -515,6 +742,7 @@ impl Parser {
 
         Ok(Expression::IndexExpression(IndexExpression {
             token,
+            span: parser.span_from(left.span().start),
             left: Rc::new(left),
             index,
         }))
@@ -522,7 +750,8 @@ impl Parser {
 
     fn parse_hash_literal(parser: &mut Parser) -> MonkeyResult<Expression> {
         let token = parser.cur_token.clone().unwrap();
-        let mut pairs = HashMap::new();
+        let start = parser.cur_span.start;
+        let mut pairs = vec![];
 
         while parser
             .peek_token
@@ -541,7 +770,7 @@ impl Parser {
             parser.next_token();
             let value = Rc::new(parser.parse_expression(ExpressionType::Lowest as usize)?);
 
-            pairs.insert(key, value);
+            pairs.push((key, value));
 
             if (parser.peek_token.is_none()
                 || parser
@@ -562,23 +791,32 @@ impl Parser {
             ));
         }
 
-        Ok(Expression::HashLiteral(HashLiteral { token, pairs }))
+        Ok(Expression::HashLiteral(HashLiteral {
+            token,
+            span: parser.span_from(start),
+            pairs,
+        }))
     }
 }
 
-fn get_precedence(token: &Option<Token>) -> usize {
+pub(crate) fn get_precedence(token: &Option<Token>) -> usize {
     let expr_type = match token {
         Some(t) => match t {
             Token::Plus => ExpressionType::Sum,
             Token::Minus => ExpressionType::Sum,
             Token::Asterisk => ExpressionType::Product,
             Token::Slash => ExpressionType::Product,
+            Token::Percent => ExpressionType::Product,
             Token::Lt => ExpressionType::LessGreater,
             Token::Gt => ExpressionType::LessGreater,
             Token::Eq => ExpressionType::Equals,
             Token::Ne => ExpressionType::Equals,
+            Token::And => ExpressionType::And,
+            Token::Or => ExpressionType::Or,
             Token::Lparen => ExpressionType::Call,
             Token::Lbracket => ExpressionType::Index,
+            Token::Assign => ExpressionType::Assign,
+            Token::Question => ExpressionType::Conditional,
             _ => ExpressionType::Lowest,
         },
         None => ExpressionType::Lowest,
@@ -593,11 +831,15 @@ mod tests {
 
     use super::Parser;
     use crate::{
-        lexer::{lexer::Lexer, token::Token},
+        lexer::{
+            lexer::Lexer,
+            token::{Position, Span, Token},
+        },
         parser::ast::{
             Boolean, Expression, Identifier, InfixExpression, IntegerLiteral, LetStatement,
             Program, Statement,
         },
+        result::MonkeyError,
     };
 
     fn parse_input(input: &str) -> Program {
@@ -621,6 +863,7 @@ mod tests {
                 "let x = 5;",
                 Token::Ident(String::from("x")),
                 Expression::IntegerLiteral(IntegerLiteral {
+                    span: Span::default(),
                     token: Token::Int(String::from("5")),
                     value: 5,
                 }),
@@ -629,6 +872,7 @@ mod tests {
                 "let y = true;",
                 Token::Ident(String::from("y")),
                 Expression::Boolean(Boolean {
+                    span: Span::default(),
                     token: Token::True,
                     value: true,
                 }),
@@ -637,6 +881,7 @@ mod tests {
                 "let foobar = y;",
                 Token::Ident(String::from("foobar")),
                 Expression::Identifier(Identifier {
+                    span: Span::default(),
                     token: Token::Ident(String::from("y")),
                 }),
             ),
@@ -674,12 +919,83 @@ mod tests {
         }
     }
 
+    #[test]
+    fn let_statement_value_test() {
+        let program = parse_input("let x = 5 + 3;");
+
+        let statements = match program {
+            Program::Statements(statements) => statements,
+            actual => panic!("statements expected, but got {actual}"),
+        };
+
+        assert_eq!(statements.len(), 1);
+
+        let let_statement = match statements.first().unwrap().as_ref() {
+            Statement::Let(let_statement) => let_statement,
+            actual => panic!("let statement expected, but got {actual}"),
+        };
+
+        assert_eq!(let_statement.name.token, Token::Ident(String::from("x")));
+
+        let infix = match let_statement.value.as_ref() {
+            Expression::Infix(infix) => infix,
+            actual => panic!("infix expression expected, but got {actual}"),
+        };
+
+        assert_eq!(infix.token, Token::Plus);
+
+        match (infix.left.as_ref(), infix.right.as_ref()) {
+            (Expression::IntegerLiteral(left), Expression::IntegerLiteral(right)) => {
+                assert_eq!(left.value, 5);
+                assert_eq!(right.value, 3);
+            }
+            (actual_left, actual_right) => {
+                panic!("integer literals expected, but got {actual_left} {actual_right}")
+            }
+        };
+
+        assert_eq!(let_statement.to_string(), "let x = (5 + 3);");
+    }
+
+    #[test]
+    fn multiple_parse_errors_test() {
+        let input = "let = 5; let y 10; let z = 1; z;";
+
+        let lexer = Lexer::new(String::from(input));
+        let mut parser = Parser::new(lexer);
+
+        let err = parser.parse_program().unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "1:1: unable to parse let statement, identifier expected\n1:14: unable to parse let statement, assign token expected"
+        );
+        assert_eq!(err.position(), Some(Position { line: 1, column: 1 }));
+        assert_eq!(
+            parser.errors(),
+            vec![
+                MonkeyError::Parse {
+                    message: String::from("unable to parse let statement, identifier expected"),
+                    position: Some(Position { line: 1, column: 1 }),
+                },
+                MonkeyError::Parse {
+                    message: String::from("unable to parse let statement, assign token expected"),
+                    position: Some(Position {
+                        line: 1,
+                        column: 14
+                    }),
+                },
+            ]
+        );
+    }
+
     #[test]
     fn return_statements_test() {
         let expected: Vec<(&str, Expression)> = vec![
             (
                 "return 5;",
                 Expression::IntegerLiteral(IntegerLiteral {
+                    span: Span::default(),
                     token: Token::Int(String::from("5")),
                     value: 5,
                 }),
@@ -687,6 +1003,7 @@ mod tests {
             (
                 "return true;",
                 Expression::Boolean(Boolean {
+                    span: Span::default(),
                     token: Token::True,
                     value: true,
                 }),
@@ -694,9 +1011,33 @@ mod tests {
             (
                 "return y;",
                 Expression::Identifier(Identifier {
+                    span: Span::default(),
                     token: Token::Ident(String::from("y")),
                 }),
             ),
+            (
+                "return 10;",
+                Expression::IntegerLiteral(IntegerLiteral {
+                    span: Span::default(),
+                    token: Token::Int(String::from("10")),
+                    value: 10,
+                }),
+            ),
+            (
+                "return x + y;",
+                Expression::Infix(InfixExpression {
+                    span: Span::default(),
+                    token: Token::Plus,
+                    left: Rc::new(Expression::Identifier(Identifier {
+                        span: Span::default(),
+                        token: Token::Ident(String::from("x")),
+                    })),
+                    right: Rc::new(Expression::Identifier(Identifier {
+                        span: Span::default(),
+                        token: Token::Ident(String::from("y")),
+                    })),
+                }),
+            ),
         ];
 
         for (input, expression) in expected {
@@ -724,6 +1065,11 @@ mod tests {
                 (Expression::Identifier(ident), Expression::Identifier(exp)) => {
                     assert_eq!(ident.token, exp.token)
                 }
+                (Expression::Infix(infix), Expression::Infix(exp)) => {
+                    assert_eq!(infix.token, exp.token);
+                    assert_eq!(infix.left.to_string(), exp.left.to_string());
+                    assert_eq!(infix.right.to_string(), exp.right.to_string());
+                }
                 (actual, _) => panic!("integer literal expected, but got {actual}"),
             };
         }
@@ -732,11 +1078,14 @@ mod tests {
     #[test]
     fn pretty_print_test() {
         let program = Program::Statements(vec![Rc::new(Statement::Let(LetStatement {
+            span: Span::default(),
             token: Token::Let,
             name: Identifier {
+                span: Span::default(),
                 token: Token::Ident(String::from("myVar")),
             },
             value: Rc::new(Expression::Identifier(Identifier {
+                span: Span::default(),
                 token: Token::Ident(String::from("anotherVar")),
             })),
         }))]);
@@ -795,6 +1144,32 @@ mod tests {
         assert_eq!(integer_literal.value, 5);
     }
 
+    #[test]
+    fn float_literal_expression_test() {
+        let input = "2.75;";
+        let program = parse_input(input);
+
+        let statements = match program {
+            Program::Statements(statements) => statements,
+            actual => panic!("statements expected, but got {actual}"),
+        };
+
+        assert_eq!(statements.len(), 1);
+
+        let expression_statement = match statements.first().unwrap().as_ref() {
+            Statement::Expression(expr) => expr,
+            actual => panic!("expression statement expected, but got {actual}"),
+        };
+
+        let float_literal = match &expression_statement.expression.as_ref() {
+            Expression::FloatLiteral(float) => float,
+            actual => panic!("float literal expression expected, but got {actual}"),
+        };
+
+        assert_eq!(float_literal.token, Token::Float(String::from("2.75")));
+        assert_eq!(float_literal.value, 2.75);
+    }
+
     #[test]
     fn prefix_expression_test_num() {
         let expected_expressions = vec![
@@ -802,6 +1177,7 @@ mod tests {
                 "!5;",
                 Token::Bang,
                 Expression::IntegerLiteral(IntegerLiteral {
+                    span: Span::default(),
                     token: Token::Int(String::from("5")),
                     value: 5,
                 }),
@@ -810,6 +1186,16 @@ mod tests {
                 "-15;",
                 Token::Minus,
                 Expression::IntegerLiteral(IntegerLiteral {
+                    span: Span::default(),
+                    token: Token::Int(String::from("15")),
+                    value: 15,
+                }),
+            ),
+            (
+                "+15;",
+                Token::Plus,
+                Expression::IntegerLiteral(IntegerLiteral {
+                    span: Span::default(),
                     token: Token::Int(String::from("15")),
                     value: 15,
                 }),
@@ -818,6 +1204,7 @@ mod tests {
                 "!true;",
                 Token::Bang,
                 Expression::Boolean(Boolean {
+                    span: Span::default(),
                     token: Token::True,
                     value: true,
                 }),
@@ -826,6 +1213,7 @@ mod tests {
                 "!false;",
                 Token::Bang,
                 Expression::Boolean(Boolean {
+                    span: Span::default(),
                     token: Token::False,
                     value: false,
                 }),
@@ -874,12 +1262,15 @@ mod tests {
             (
                 "5 + 5;",
                 Expression::Infix(InfixExpression {
+                    span: Span::default(),
                     token: Token::Plus,
                     left: Rc::new(Expression::IntegerLiteral(IntegerLiteral {
+                        span: Span::default(),
                         token: Token::Int(String::from("5")),
                         value: 5,
                     })),
                     right: Rc::new(Expression::IntegerLiteral(IntegerLiteral {
+                        span: Span::default(),
                         token: Token::Int(String::from("5")),
                         value: 5,
                     })),
@@ -888,12 +1279,15 @@ mod tests {
             (
                 "5 - 5;",
                 Expression::Infix(InfixExpression {
+                    span: Span::default(),
                     token: Token::Minus,
                     left: Rc::new(Expression::IntegerLiteral(IntegerLiteral {
+                        span: Span::default(),
                         token: Token::Int(String::from("5")),
                         value: 5,
                     })),
                     right: Rc::new(Expression::IntegerLiteral(IntegerLiteral {
+                        span: Span::default(),
                         token: Token::Int(String::from("5")),
                         value: 5,
                     })),
@@ -902,12 +1296,15 @@ mod tests {
             (
                 "5 * 5;",
                 Expression::Infix(InfixExpression {
+                    span: Span::default(),
                     token: Token::Asterisk,
                     left: Rc::new(Expression::IntegerLiteral(IntegerLiteral {
+                        span: Span::default(),
                         token: Token::Int(String::from("5")),
                         value: 5,
                     })),
                     right: Rc::new(Expression::IntegerLiteral(IntegerLiteral {
+                        span: Span::default(),
                         token: Token::Int(String::from("5")),
                         value: 5,
                     })),
@@ -916,12 +1313,15 @@ mod tests {
             (
                 "5 / 5;",
                 Expression::Infix(InfixExpression {
+                    span: Span::default(),
                     token: Token::Slash,
                     left: Rc::new(Expression::IntegerLiteral(IntegerLiteral {
+                        span: Span::default(),
                         token: Token::Int(String::from("5")),
                         value: 5,
                     })),
                     right: Rc::new(Expression::IntegerLiteral(IntegerLiteral {
+                        span: Span::default(),
                         token: Token::Int(String::from("5")),
                         value: 5,
                     })),
@@ -930,12 +1330,15 @@ mod tests {
             (
                 "5 > 5;",
                 Expression::Infix(InfixExpression {
+                    span: Span::default(),
                     token: Token::Gt,
                     left: Rc::new(Expression::IntegerLiteral(IntegerLiteral {
+                        span: Span::default(),
                         token: Token::Int(String::from("5")),
                         value: 5,
                     })),
                     right: Rc::new(Expression::IntegerLiteral(IntegerLiteral {
+                        span: Span::default(),
                         token: Token::Int(String::from("5")),
                         value: 5,
                     })),
@@ -944,12 +1347,15 @@ mod tests {
             (
                 "5 < 5;",
                 Expression::Infix(InfixExpression {
+                    span: Span::default(),
                     token: Token::Lt,
                     left: Rc::new(Expression::IntegerLiteral(IntegerLiteral {
+                        span: Span::default(),
                         token: Token::Int(String::from("5")),
                         value: 5,
                     })),
                     right: Rc::new(Expression::IntegerLiteral(IntegerLiteral {
+                        span: Span::default(),
                         token: Token::Int(String::from("5")),
                         value: 5,
                     })),
@@ -958,12 +1364,15 @@ mod tests {
             (
                 "5 == 5;",
                 Expression::Infix(InfixExpression {
+                    span: Span::default(),
                     token: Token::Eq,
                     left: Rc::new(Expression::IntegerLiteral(IntegerLiteral {
+                        span: Span::default(),
                         token: Token::Int(String::from("5")),
                         value: 5,
                     })),
                     right: Rc::new(Expression::IntegerLiteral(IntegerLiteral {
+                        span: Span::default(),
                         token: Token::Int(String::from("5")),
                         value: 5,
                     })),
@@ -972,12 +1381,15 @@ mod tests {
             (
                 "5 != 5;",
                 Expression::Infix(InfixExpression {
+                    span: Span::default(),
                     token: Token::Ne,
                     left: Rc::new(Expression::IntegerLiteral(IntegerLiteral {
+                        span: Span::default(),
                         token: Token::Int(String::from("5")),
                         value: 5,
                     })),
                     right: Rc::new(Expression::IntegerLiteral(IntegerLiteral {
+                        span: Span::default(),
                         token: Token::Int(String::from("5")),
                         value: 5,
                     })),
@@ -986,12 +1398,15 @@ mod tests {
             (
                 "true == true",
                 Expression::Infix(InfixExpression {
+                    span: Span::default(),
                     token: Token::Eq,
                     left: Rc::new(Expression::Boolean(Boolean {
+                        span: Span::default(),
                         token: Token::True,
                         value: true,
                     })),
                     right: Rc::new(Expression::Boolean(Boolean {
+                        span: Span::default(),
                         token: Token::True,
                         value: true,
                     })),
@@ -1000,12 +1415,15 @@ mod tests {
             (
                 "true != false",
                 Expression::Infix(InfixExpression {
+                    span: Span::default(),
                     token: Token::Ne,
                     left: Rc::new(Expression::Boolean(Boolean {
+                        span: Span::default(),
                         token: Token::True,
                         value: true,
                     })),
                     right: Rc::new(Expression::Boolean(Boolean {
+                        span: Span::default(),
                         token: Token::False,
                         value: false,
                     })),
@@ -1014,12 +1432,15 @@ mod tests {
             (
                 "false == false",
                 Expression::Infix(InfixExpression {
+                    span: Span::default(),
                     token: Token::Eq,
                     left: Rc::new(Expression::Boolean(Boolean {
+                        span: Span::default(),
                         token: Token::False,
                         value: false,
                     })),
                     right: Rc::new(Expression::Boolean(Boolean {
+                        span: Span::default(),
                         token: Token::False,
                         value: false,
                     })),
@@ -1075,6 +1496,81 @@ mod tests {
         }
     }
 
+    #[test]
+    fn boolean_expression_test() {
+        let expected = vec![("true;", true), ("false;", false)];
+
+        for (input, expected_value) in expected {
+            let program = parse_input(input);
+
+            let statements = match program {
+                Program::Statements(statements) => statements,
+                actual => panic!("statements expected, but got {actual}"),
+            };
+
+            assert_eq!(statements.len(), 1);
+
+            match statements.first().unwrap().as_ref() {
+                Statement::Expression(expr) => match expr.expression.as_ref() {
+                    Expression::Boolean(bool) => assert_eq!(bool.value, expected_value),
+                    actual => panic!("boolean expected, but got {actual}"),
+                },
+                actual => panic!("expression statement expected, but got {actual}"),
+            };
+        }
+
+        let program = parse_input("3 > 5 == false");
+        assert_eq!(program.to_string(), "((3 > 5) == false)");
+
+        let lexer = Lexer::new(String::from("let x = ;"));
+        let mut parser = Parser::new(lexer);
+
+        assert_eq!(
+            parser.parse_program().unwrap_err().to_string(),
+            "1:9: unable to parse expression, no prefix parse function for \";\""
+        );
+    }
+
+    #[test]
+    fn unterminated_block_comment_test() {
+        let lexer = Lexer::new(String::from("let x = 5;\n/* x"));
+        let mut parser = Parser::new(lexer);
+
+        assert_eq!(
+            parser.parse_program().unwrap_err().to_string(),
+            "2:5: unterminated block comment"
+        );
+    }
+
+    #[test]
+    fn assign_expression_test() {
+        let program = parse_input("x = 10;");
+
+        let statements = match program {
+            Program::Statements(statements) => statements,
+            actual => panic!("statements expected, but got {actual}"),
+        };
+
+        match statements.first().unwrap().as_ref() {
+            Statement::Expression(statement) => match statement.expression.as_ref() {
+                Expression::Assign(assign) => {
+                    assert_eq!(assign.name.token, Token::Ident(String::from("x")));
+                    assert_eq!(assign.value.to_string(), "10");
+                }
+                actual => panic!("assign expression expected, but got {actual}"),
+            },
+            actual => panic!("expression statement expected, but got {actual}"),
+        };
+
+        let lexer = Lexer::new(String::from("1 = 2;"));
+        let mut parser = Parser::new(lexer);
+
+        assert_eq!(
+            parser.parse_program().unwrap_err().to_string(),
+            "1:3: unable to parse assign expression, identifier expected, but got \"1\""
+        );
+    }
+
     #[test]
     fn operator_precedence_test() {
         let expected_expressions = vec![
@@ -1084,9 +1580,18 @@ mod tests {
             ("a + b - c", "((a + b) - c)"),
             ("a * b * c", "((a * b) * c)"),
             ("a * b / c", "((a * b) / c)"),
+            ("a * b % c", "((a * b) % c)"),
+            ("a + b % c", "(a + (b % c))"),
+            ("1.5 + 2 * 3.0", "(1.5 + (2 * 3.0))"),
             ("a + b / c", "(a + (b / c))"),
             ("a + b * c + d / e - f", "(((a + (b * c)) + (d / e)) - f)"),
             ("3 + 4; -5 * 5", "(3 + 4)((-5) * 5)"),
+            ("x = 1 + 2 * 3", "(x = (1 + (2 * 3)))"),
+            ("a == b && c || d", "(((a == b) && c) || d)"),
+            ("a || b && c", "(a || (b && c))"),
+            ("!a && b < c", "((!a) && (b < c))"),
+            ("x = a || b", "(x = (a || b))"),
+            ("x = y = z == 1", "(x = (y = (z == 1)))"),
             ("5 > 4 == 3 < 4", "((5 > 4) == (3 < 4))"),
             ("5 < 4 != 3 > 4", "((5 < 4) != (3 > 4))"),
             (
@@ -1131,15 +1636,102 @@ mod tests {
         }
     }
 
+    #[test]
+    fn conditional_expression_test() {
+        let expected = vec![
+            ("x > 0 ? 1 : 2", "if (x > 0) { 1 } else { 2 }"),
+            ("a ? b + 1 : c * 2;", "if (a) { b + 1 } else { c * 2 }"),
+            ("a || b ? c : d", "if (a || b) { c } else { d }"),
+            ("x = a ? b : c", "x = if (a) { b } else { c }"),
+            ("f(a ? b : c)", "f(if (a) { b } else { c })"),
+            (
+                "a ? fn(x) { x } : [1]",
+                "if (a) { fn(x) { x } } else { [1] }",
+            ),
+        ];
+
+        let parse_expression = |input: &str| match parse_input(input) {
+            Program::Statements(statements) => match statements.first().unwrap().as_ref() {
+                Statement::Expression(expr) => Rc::clone(&expr.expression),
+                actual => panic!("expression statement expected, but got {actual}"),
+            },
+            actual => panic!("statements expected, but got {actual}"),
+        };
+
+        for (input, desugared) in expected {
+            // spans differ between the sugared and desugared source
+            assert_eq!(
+                parse_expression(input).to_string(),
+                parse_expression(desugared).to_string(),
+                "{input}"
+            );
+        }
+
+        assert!(matches!(
+            parse_expression("a ? b : c").as_ref(),
+            Expression::If(_)
+        ));
+
+        let expected = vec![
+            ("a ? b : c ? d : e", "if a b else if c d else e"),
+            ("a ? b ? c : d : e", "if a if b c else d else e"),
+        ];
+
+        for (input, expected_result) in expected {
+            assert_eq!(parse_input(input).to_string(), expected_result);
+        }
+
+        let expected_errors = vec![
+            (
+                "a ? b",
+                "1:5: unable to parse conditional expression, couldn't find colon",
+            ),
+            (
+                "a ? b c",
+                "1:5: unable to parse conditional expression, couldn't find colon",
+            ),
+        ];
+
+        for (input, error) in expected_errors {
+            let mut parser = Parser::new(Lexer::new(String::from(input)));
+            assert_eq!(parser.parse_program().unwrap_err().to_string(), error);
+        }
+    }
+
+    #[test]
+    fn grouped_expression_test() {
+        let expected_expressions = vec![
+            ("((1 + 2))", "(1 + 2)"),
+            ("(((a)))", "a"),
+            ("(1 + 2) * 3", "((1 + 2) * 3)"),
+            ("1 + (2 * 3)", "(1 + (2 * 3))"),
+            ("(1 + (2 + 3)) * 4", "((1 + (2 + 3)) * 4)"),
+        ];
+
+        for (input, expected) in expected_expressions {
+            let program = parse_input(input);
+            assert_eq!(program.to_string(), expected);
+        }
+
+        let lexer = Lexer::new(String::from("(1 + 2"));
+        let mut parser = Parser::new(lexer);
+
+        assert_eq!(
+            parser.parse_program().unwrap_err().to_string(),
+            "1:6: unable to parse grouped expression, couldn't find closing parentheses"
+        );
+    }
+
     #[test]
     fn if_expression_test() {
         let expected = vec![
-            ("if (x < y) { x }", false),
-            ("if (x < y) { x } else { y }", true),
+            ("if (x < y) { x }", false, "if (x < y) x"),
+            ("if (x < y) { x } else { y }", true, "if (x < y) x else y"),
         ];
 
-        for (input, has_alternative_statement) in expected {
+        for (input, has_alternative_statement, expected_pretty_print) in expected {
             let program = parse_input(input);
+            assert_eq!(program.to_string(), expected_pretty_print);
 
             let statements = match program {
                 Program::Statements(statements) => statements,
@@ -1210,10 +1802,87 @@ mod tests {
                     },
                     actual => panic!("expression statement expected, bug got {actual}"),
                 };
+            } else {
+                assert!(if_expression.alternative.is_none());
             }
         }
     }
 
+    #[test]
+    fn while_statement_test() {
+        let program = parse_input("while (x < 3) { let x = x + 1; }");
+        assert_eq!(program.to_string(), "while (x < 3) let x = (x + 1);");
+
+        let statements = match program {
+            Program::Statements(statements) => statements,
+            actual => panic!("statements expected, but got {actual}"),
+        };
+
+        assert_eq!(statements.len(), 1);
+
+        let while_statement = match statements.first().unwrap().as_ref() {
+            Statement::While(while_statement) => while_statement,
+            actual => panic!("while statement expected, but got {actual}"),
+        };
+
+        match while_statement.condition.as_ref() {
+            Expression::Infix(infix) => assert_eq!(infix.token, Token::Lt),
+            actual => panic!("infix expression expected, but got {actual}"),
+        };
+
+        match while_statement.body.as_ref() {
+            Statement::Block(block) => assert_eq!(block.statements.len(), 1),
+            actual => panic!("block statement expected, but got {actual}"),
+        };
+
+        let lexer = Lexer::new(String::from("while (true) 1"));
+        let mut parser = Parser::new(lexer);
+
+        assert_eq!(
+            parser.parse_program().unwrap_err().to_string(),
+            "1:12: unable to parse while statement, couldn't find opening brace"
+        );
+    }
+
+    #[test]
+    fn block_statement_test() {
+        let lexer = Lexer::new(String::from("{ let x = 5; x }"));
+        let mut parser = Parser::new(lexer);
+
+        let block = match parser.parse_block_statement() {
+            Ok(Statement::Block(block)) => block,
+            Ok(actual) => panic!("block statement expected, but got {actual}"),
+            Err(err) => panic!("{err}"),
+        };
+
+        assert_eq!(block.statements.len(), 2);
+
+        match block.statements[0].as_ref() {
+            Statement::Let(let_statement) => {
+                assert_eq!(let_statement.name.token, Token::Ident(String::from("x")))
+            }
+            actual => panic!("let statement expected, but got {actual}"),
+        };
+
+        match block.statements[1].as_ref() {
+            Statement::Expression(statement) => match statement.expression.as_ref() {
+                Expression::Identifier(ident) => {
+                    assert_eq!(ident.token, Token::Ident(String::from("x")))
+                }
+                actual => panic!("identifier expected, but got {actual}"),
+            },
+            actual => panic!("expression statement expected, but got {actual}"),
+        };
+
+        let lexer = Lexer::new(String::from("if (true) { let x = 5; x"));
+        let mut parser = Parser::new(lexer);
+
+        assert_eq!(
+            parser.parse_program().unwrap_err().to_string(),
+            "1:25: unable to parse block, closing brace expected"
+        );
+    }
+
     #[test]
     fn function_literal_test() {
         let input = "fn(x, y) { x + y; }";
@@ -1318,6 +1987,42 @@ mod tests {
         }
     }
 
+    #[test]
+    fn function_literal_errors_test() {
+        let expected = vec![
+            (
+                "fn(1) {}",
+                "1:4: unable to parse function parameters, identifier expected, but got \"1\"",
+            ),
+            (
+                "fn(x, ) {}",
+                "1:7: unable to parse function parameters, identifier expected, but got \")\"",
+            ),
+            (
+                "fn(x y) {}",
+                "1:4: unable to parse function parameters, couldn't find closing parentheses",
+            ),
+            (
+                "fn(x,",
+                "1:6: unable to parse function parameters, couldn't find identifier",
+            ),
+            (
+                "fn(x) x",
+                "1:5: unable to parse function literal, couldn't find opening brace",
+            ),
+        ];
+
+        for (input, expected_err) in expected {
+            let lexer = Lexer::new(String::from(input));
+            let mut parser = Parser::new(lexer);
+
+            assert_eq!(
+                parser.parse_program().unwrap_err().to_string(),
+                expected_err
+            );
+        }
+    }
+
     #[test]
     fn call_expression_test() {
         let input = "add(1, 2 * 3, 4 + 5);";
@@ -1386,24 +2091,70 @@ mod tests {
     }
 
     #[test]
-    fn string_literal_test() {
-        let input = "\"hello world!\"";
+    fn call_expression_corner_cases_test() {
+        let expected = vec![
+            ("foo()", "foo", 0, "foo()"),
+            ("foo(1,)", "foo", 1, "foo(1)"),
+            ("fn(x) { x }(5)", "fn(x)x", 1, "fn(x)x(5)"),
+        ];
 
-        let program = parse_input(input);
-        let statements = match program {
-            Program::Statements(statements) => statements,
-            actual => panic!("statements expected, but got {actual}"),
-        };
-        assert_eq!(statements.len(), 1);
+        for (input, expected_function, expected_args_len, expected_pretty_print) in expected {
+            let program = parse_input(input);
+            assert_eq!(program.to_string(), expected_pretty_print);
 
-        match statements.first().unwrap().as_ref() {
-            Statement::Expression(expr) => match &expr.expression.as_ref() {
-                Expression::StringLiteral(string) => {
-                    assert_eq!(string.token.to_string(), "hello world!")
+            let statements = match program {
+                Program::Statements(statements) => statements,
+                actual => panic!("statements expected, but got {actual}"),
+            };
+
+            let expression = match statements.first().unwrap().as_ref() {
+                Statement::Expression(expr) => expr.expression.as_ref(),
+                actual => panic!("expression statement expected, but got {actual}"),
+            };
+
+            match expression {
+                Expression::Call(call) => {
+                    assert_eq!(call.function.to_string(), expected_function);
+                    assert_eq!(call.arguments.len(), expected_args_len);
                 }
-                actual => panic!("string literal expected, got {actual}"),
-            },
-            actual => panic!("expression statement expected, got {actual}"),
+                actual => panic!("call expression expected, but got {actual}"),
+            }
+        }
+
+        let lexer = Lexer::new(String::from("add(1, 2"));
+        let mut parser = Parser::new(lexer);
+
+        assert_eq!(
+            parser.parse_program().unwrap_err().to_string(),
+            "1:8: unable to parse expression list, couldn't find closing \")\""
+        );
+    }
+
+    #[test]
+    fn string_literal_test() {
+        let expected = vec![
+            ("\"hello world!\"", "hello world!"),
+            ("\"hello world\";", "hello world"),
+            ("\"\"", ""),
+        ];
+
+        for (input, expected_value) in expected {
+            let program = parse_input(input);
+            let statements = match program {
+                Program::Statements(statements) => statements,
+                actual => panic!("statements expected, but got {actual}"),
+            };
+            assert_eq!(statements.len(), 1);
+
+            match statements.first().unwrap().as_ref() {
+                Statement::Expression(expr) => match &expr.expression.as_ref() {
+                    Expression::StringLiteral(string) => {
+                        assert_eq!(string.token, Token::String(String::from(expected_value)))
+                    }
+                    actual => panic!("string literal expected, got {actual}"),
+                },
+                actual => panic!("expression statement expected, got {actual}"),
+            }
         }
     }
 
@@ -1470,6 +2221,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn array_literal_corner_cases_test() {
+        let expected = vec![
+            ("[]", "[]"),
+            ("[1, 2,]", "[1, 2]"),
+            ("[1 + 1, [2],]", "[(1 + 1), [2]]"),
+        ];
+
+        for (input, expected) in expected {
+            let program = parse_input(input);
+            assert_eq!(program.to_string(), expected);
+        }
+
+        let lexer = Lexer::new(String::from("[1, 2"));
+        let mut parser = Parser::new(lexer);
+
+        assert_eq!(
+            parser.parse_program().unwrap_err().to_string(),
+            "1:5: unable to parse expression list, couldn't find closing \"]\""
+        );
+    }
+
     #[test]
     fn index_expression_test() {
         let input = "myArray[1 + 1]";
@@ -1502,6 +2275,30 @@ mod tests {
             },
             actual => panic!("expression statement expected, got {actual}"),
         }
+
+        let lexer = Lexer::new(String::from("myArray[1"));
+        let mut parser = Parser::new(lexer);
+
+        assert_eq!(
+            parser.parse_program().unwrap_err().to_string(),
+            "1:9: unable to parse index expression, couldn't find closing bracket"
+        );
+    }
+
+    #[test]
+    fn index_expression_precedence_test() {
+        let expected_expressions = vec![
+            ("a * b[2]", "(a * (b[2]))"),
+            ("b[2] * a", "((b[2]) * a)"),
+            ("-a[0]", "(-(a[0]))"),
+            ("a[0][1]", "((a[0])[1])"),
+            ("f(x)[0]", "(f(x)[0])"),
+        ];
+
+        for (input, expected) in expected_expressions {
+            let program = parse_input(input);
+            assert_eq!(program.to_string(), expected);
+        }
     }
 
     #[test]
@@ -1569,6 +2366,47 @@ mod tests {
         }
     }
 
+    #[test]
+    fn hash_literal_source_order_test() {
+        let expected = vec![
+            (r#"{"two": 2, "one": 1}"#, "{ two: 2, one: 1 }"),
+            (r#"{3: "c", 1: "a", 2: "b",}"#, "{ 3: c, 1: a, 2: b }"),
+        ];
+
+        for (input, expected) in expected {
+            let program = parse_input(input);
+            assert_eq!(program.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn hash_literal_errors_test() {
+        let expected = vec![
+            (
+                r#"{"one" 1}"#,
+                "1:2: unable to parse hash literal, couldn't find colon",
+            ),
+            (
+                r#"{"one": 1 "two": 2}"#,
+                "1:9: unable to parse hash literal, couldn't find closing brace or comma",
+            ),
+            (
+                r#"{"one": 1"#,
+                "1:9: unable to parse hash literal, couldn't find closing brace or comma",
+            ),
+        ];
+
+        for (input, expected_err) in expected {
+            let lexer = Lexer::new(String::from(input));
+            let mut parser = Parser::new(lexer);
+
+            assert_eq!(
+                parser.parse_program().unwrap_err().to_string(),
+                expected_err
+            );
+        }
+    }
+
     #[test]
     fn complex_hash_literal_test() {
         let input = r#"{"one": 0 + 1, "two": 10 - 8, "three": 15 / 5}"#;
@@ -1638,4 +2476,82 @@ mod tests {
             actual => panic!("expression statement expected, but got {actual}"),
         }
     }
+
+    #[test]
+    fn span_test() {
+        let span = |start: (usize, usize), end: (usize, usize)| {
+            format!(
+                "{}-{}",
+                Position {
+                    line: start.0,
+                    column: start.1
+                },
+                Position {
+                    line: end.0,
+                    column: end.1
+                }
+            )
+        };
+
+        let statements = match parse_input("a + b") {
+            Program::Statements(statements) => statements,
+            actual => panic!("statements expected, but got {actual}"),
+        };
+
+        match statements[0].as_ref() {
+            Statement::Expression(statement) => match statement.expression.as_ref() {
+                Expression::Infix(infix) => {
+                    assert_eq!(infix.span.start, Position { line: 1, column: 1 });
+                    assert_eq!(infix.span.end, Position { line: 1, column: 6 });
+                    assert_eq!(infix.left.span().to_string(), span((1, 1), (1, 2)));
+                    assert_eq!(infix.right.span().to_string(), span((1, 5), (1, 6)));
+                    assert_ne!(infix.left.span(), infix.right.span());
+                }
+                actual => panic!("infix expression expected, but got {actual}"),
+            },
+            actual => panic!("expression statement expected, but got {actual}"),
+        }
+
+        let input = "let x = foo(1, [2]);
+if (x > 1) {
+    x[0]
+} else { -x }";
+
+        let statements = match parse_input(input) {
+            Program::Statements(statements) => statements,
+            actual => panic!("statements expected, but got {actual}"),
+        };
+
+        assert_eq!(statements[0].span().to_string(), span((1, 1), (1, 21)));
+        assert_eq!(statements[1].span().to_string(), span((2, 1), (4, 14)));
+
+        match statements[0].as_ref() {
+            Statement::Let(let_statement) => {
+                assert_eq!(let_statement.name.span.to_string(), span((1, 5), (1, 6)));
+                assert_eq!(
+                    let_statement.value.span().to_string(),
+                    span((1, 9), (1, 20))
+                );
+            }
+            actual => panic!("let statement expected, but got {actual}"),
+        }
+
+        match statements[1].as_ref() {
+            Statement::Expression(statement) => match statement.expression.as_ref() {
+                Expression::If(if_expr) => {
+                    assert_eq!(if_expr.condition.span().to_string(), span((2, 5), (2, 10)));
+                    assert_eq!(
+                        if_expr.consequence.span().to_string(),
+                        span((2, 12), (4, 2))
+                    );
+                    assert_eq!(
+                        if_expr.alternative.as_ref().unwrap().span().to_string(),
+                        span((4, 8), (4, 14))
+                    );
+                }
+                actual => panic!("if expression expected, but got {actual}"),
+            },
+            actual => panic!("expression statement expected, but got {actual}"),
+        }
+    }
 }
@@ -0,0 +1,249 @@
+use std::{
+    cell::RefCell,
+    fmt::Display,
+    io::{BufRead, Result, Write},
+    rc::Rc,
+};
+
+use crate::{
+    compiler::{
+        compiler::Compiler,
+        symbol_table::{SymbolTable, SymbolTableRef},
+    },
+    evaluator::{
+        environment::{Environment, EnvironmentRef},
+        evaluator::eval,
+    },
+    lexer::lexer::Lexer,
+    parser::parser::Parser,
+    result::MonkeyError,
+    types::Object,
+    vm::vm::{empty_globals, Vm},
+};
+
+const PROMPT: &str = ">> ";
+
+pub const MONKEY_FACE: &str = r#"
+             __,__
+     .--.  .-"   "-.  .--.
+    / .. \/ .-. .-. \/ .. \
+    | | '|  /  Y  \  |' | |
+    | \  \ \ 0 | 0 / /  / |
+     \'- ,\ .-"""-. /, -'/
+      ''-' /_ ^ ^ _\ '-''
+          | \._ _./ |
+          \  \'~'/  /
+           '._'-'_.'
+             '---'
+"#;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Engine {
+    Vm,
+    Eval,
+}
+
+impl Display for Engine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Engine::Vm => write!(f, "vm"),
+            Engine::Eval => write!(f, "eval"),
+        }
+    }
+}
+
+struct Session {
+    engine: Engine,
+    env: EnvironmentRef,
+    constants: Vec<Rc<Object>>,
+    globals: Vec<Rc<Object>>,
+    symbol_table: SymbolTableRef,
+}
+
+impl Session {
+    fn new() -> Self {
+        let symbol_table = SymbolTable::new();
+        symbol_table
+            .borrow_mut()
+            .populate_symbol_table_with_builtins();
+
+        Session {
+            engine: Engine::Vm,
+            env: Rc::new(RefCell::new(Environment::new())),
+            constants: vec![],
+            globals: empty_globals(),
+            symbol_table,
+        }
+    }
+
+    fn execute_command(&mut self, command: &str) -> std::result::Result<String, String> {
+        let (name, args) = command
+            .split_once(char::is_whitespace)
+            .map(|(name, args)| (name, args.trim()))
+            .unwrap_or((command, ""));
+
+        match (name, args) {
+            ("engine", "") => Ok(format!("engine: {}", self.engine)),
+            ("engine", "vm") => {
+                self.engine = Engine::Vm;
+                Ok(format!("engine: {}", self.engine))
+            }
+            ("engine", "eval") => {
+                self.engine = Engine::Eval;
+                Ok(format!("engine: {}", self.engine))
+            }
+            ("engine", engine) => Err(format!("unknown engine {engine}, vm or eval expected")),
+            ("bytecode", input) => self.byte_code(input).map_err(|err| err.to_string()),
+            _ => Err(format!("unknown command :{command}")),
+        }
+    }
+
+    fn byte_code(&self, line: &str) -> std::result::Result<String, MonkeyError> {
+        let program = Parser::new(Lexer::new(String::from(line))).parse_program()?;
+
+        let symbol_table = Rc::new(RefCell::new(self.symbol_table.borrow().clone()));
+        let mut compiler = Compiler::new_with_state(symbol_table, self.constants.clone());
+        compiler.compile(program)?;
+
+        let to_compile_error = |message| MonkeyError::Compile {
+            message,
+            position: None,
+        };
+        let byte_code = compiler.byte_code().map_err(to_compile_error)?;
+        let mut output = byte_code
+            .instructions
+            .disassemble()
+            .map_err(to_compile_error)?;
+
+        output.push_str("constants:");
+        for (idx, constant) in byte_code.constants.iter().enumerate() {
+            output.push_str(&format!("\n{idx:04} {constant}"));
+        }
+
+        Ok(output)
+    }
+
+    fn run(&mut self, line: &str) -> std::result::Result<Object, MonkeyError> {
+        let program = Parser::new(Lexer::new(String::from(line))).parse_program()?;
+
+        if self.engine == Engine::Eval {
+            return eval(program, &self.env);
+        }
+
+        let mut compiler =
+            Compiler::new_with_state(self.symbol_table.clone(), self.constants.clone());
+        compiler.compile(program)?;
+
+        self.symbol_table = compiler.symbol_table.clone();
+
+        let byte_code = compiler
+            .byte_code()
+            .map_err(|message| MonkeyError::Compile {
+                message,
+                position: None,
+            })?;
+        self.constants = byte_code.constants.clone();
+
+        let mut vm = Vm::new_with_global_store(byte_code, self.globals.clone());
+        let result = vm.run();
+        self.globals = vm.globals.clone();
+
+        result?;
+
+        Ok(vm.last_popped_stack_elem()?)
+    }
+}
+
+pub fn start(mut input: impl BufRead, mut output: impl Write) -> Result<()> {
+    let mut session = Session::new();
+    let mut line = String::new();
+
+    write!(output, "{PROMPT}")?;
+    output.flush()?;
+
+    while input.read_line(&mut line)? > 0 {
+        let result = match line.trim().strip_prefix(':') {
+            Some(command) => session.execute_command(command),
+            None => session
+                .run(&line)
+                .map(|obj| obj.to_string())
+                .map_err(|err| err.render(&line)),
+        };
+
+        match result {
+            Ok(result) => writeln!(output, "{result}")?,
+            Err(err) => writeln!(output, "{MONKEY_FACE}\n{err}")?,
+        }
+
+        line.clear();
+        write!(output, "{PROMPT}")?;
+        output.flush()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::start;
+
+    fn run_lines(lines: &[&str]) -> String {
+        let input = lines.join("\n");
+        let mut output = vec![];
+
+        start(input.as_bytes(), &mut output).unwrap();
+
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn repl_test() {
+        let output = run_lines(&["let x = 5;", "x + 1;"]);
+
+        assert!(output.contains("6\n"));
+    }
+
+    #[test]
+    fn repl_error_test() {
+        let output = run_lines(&["let x = ;", "1 / 0", "let y = 2;", "y * 21"]);
+
+        assert!(output.contains("1:9: unable to parse expression"));
+        assert!(output.contains("1 | let x = ;\n  |         ^"));
+        assert!(output.contains("division by zero"));
+        assert!(output.contains("42\n"));
+    }
+
+    #[test]
+    fn repl_engine_test() {
+        let output = run_lines(&[
+            ":engine",
+            "let x = 20;",
+            ":engine eval",
+            "x",
+            "let y = 21; y * 2",
+            ":engine vm",
+            "x + 1",
+            ":engine wasm",
+            ":nope",
+        ]);
+
+        assert!(output.contains("engine: vm\n"));
+        assert!(output.contains("engine: eval\n"));
+        assert!(output.contains("identifier not found: x"));
+        assert!(output.contains("42\n"));
+        assert!(output.contains("21\n"));
+        assert!(output.contains("unknown engine wasm, vm or eval expected"));
+        assert!(output.contains("unknown command :nope"));
+    }
+
+    #[test]
+    fn repl_bytecode_test() {
+        let output = run_lines(&[":bytecode 1 + 2", "let x = 3;", ":bytecode x", "x"]);
+
+        assert!(output.contains("OpConstant"));
+        assert!(output.contains("OpAdd"));
+        assert!(output.contains("OpGetGlobal 0"));
+        assert!(output.contains("0000 1\n0001 2\n"));
+        assert!(output.contains(">> 3\n"));
+    }
+}
@@ -1,3 +1,207 @@
-use std::result;
+use std::{error::Error, fmt::Display, result};
+
+use crate::lexer::token::Position;
 
 pub type MonkeyResult<T> = result::Result<T, String>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MonkeyError {
+    Lex {
+        message: String,
+        position: Option<Position>,
+    },
+    Parse {
+        message: String,
+        position: Option<Position>,
+    },
+    Compile {
+        message: String,
+        position: Option<Position>,
+    },
+    Runtime {
+        message: String,
+        position: Option<Position>,
+    },
+}
+
+impl MonkeyError {
+    pub fn message(&self) -> &str {
+        match self {
+            MonkeyError::Lex { message, .. }
+            | MonkeyError::Parse { message, .. }
+            | MonkeyError::Compile { message, .. }
+            | MonkeyError::Runtime { message, .. } => message,
+        }
+    }
+
+    pub fn position(&self) -> Option<Position> {
+        match self {
+            MonkeyError::Lex { position, .. }
+            | MonkeyError::Parse { position, .. }
+            | MonkeyError::Compile { position, .. }
+            | MonkeyError::Runtime { position, .. } => *position,
+        }
+    }
+
+    pub fn render(&self, source: &str) -> String {
+        let Some(position) = self.position() else {
+            return self.to_string();
+        };
+
+        let Some(line) = source.lines().nth(position.line.saturating_sub(1)) else {
+            return self.to_string();
+        };
+
+        let gutter = " ".repeat(position.line.to_string().len());
+        let padding: String = line
+            .chars()
+            .take(position.column.saturating_sub(1))
+            .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+            .collect();
+
+        format!(
+            "{self}\n{gutter} |\n{} | {line}\n{gutter} | {padding}^",
+            position.line
+        )
+    }
+}
+
+impl Display for MonkeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.position() {
+            Some(position) => write!(f, "{position}: {}", self.message()),
+            None => write!(f, "{}", self.message()),
+        }
+    }
+}
+
+impl Error for MonkeyError {}
+
+impl From<String> for MonkeyError {
+    fn from(message: String) -> Self {
+        MonkeyError::Runtime {
+            message,
+            position: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::MonkeyError;
+    use crate::{
+        compiler::compiler::Compiler,
+        evaluator::{environment::Environment, evaluator::eval},
+        lexer::{lexer::Lexer, token::Position},
+        parser::parser::Parser,
+        vm::vm::Vm,
+    };
+
+    #[test]
+    fn error_kinds_test() {
+        let parse_err = Parser::new(Lexer::new(String::from("let x = ;")))
+            .parse_program()
+            .unwrap_err();
+
+        assert_eq!(
+            parse_err,
+            MonkeyError::Parse {
+                message: String::from(
+                    "unable to parse expression, no prefix parse function for \";\""
+                ),
+                position: Some(Position { line: 1, column: 9 }),
+            }
+        );
+        assert_eq!(
+            parse_err.to_string(),
+            "1:9: unable to parse expression, no prefix parse function for \";\""
+        );
+
+        let lex_err = Parser::new(Lexer::new(String::from("1 + /* 2")))
+            .parse_program()
+            .unwrap_err();
+
+        assert!(matches!(lex_err, MonkeyError::Lex { .. }));
+        assert_eq!(lex_err.message(), "unterminated block comment");
+
+        let program = Parser::new(Lexer::new(String::from("1 / 0")))
+            .parse_program()
+            .unwrap();
+        let env = Rc::new(RefCell::new(Environment::new()));
+
+        match eval(program.clone(), &env) {
+            Err(MonkeyError::Runtime { message, .. }) => assert_eq!(message, "division by zero"),
+            actual => panic!("runtime error expected, but got {actual:?}"),
+        }
+
+        let mut compiler = Compiler::new();
+        compiler.compile(program).unwrap();
+        let mut vm = Vm::new(compiler.byte_code().unwrap());
+
+        match vm.run() {
+            Err(MonkeyError::Runtime { message, .. }) => assert_eq!(message, "division by zero"),
+            actual => panic!("runtime error expected, but got {actual:?}"),
+        }
+
+        let program = Parser::new(Lexer::new(String::from("y")))
+            .parse_program()
+            .unwrap();
+
+        assert!(matches!(
+            Compiler::new().compile(program),
+            Err(MonkeyError::Compile { .. })
+        ));
+    }
+
+    #[test]
+    fn render_test() {
+        let source = "let a = 1;\nlet b = a +;\nb";
+        let err = Parser::new(Lexer::new(String::from(source)))
+            .parse_program()
+            .unwrap_err();
+
+        assert_eq!(
+            err.render(source),
+            "2:12: unable to parse expression, no prefix parse function for \";\"
+  |
+2 | let b = a +;
+  |            ^"
+        );
+
+        let err = MonkeyError::Lex {
+            message: String::from("illegal character '@'"),
+            position: Some(Position {
+                line: 10,
+                column: 5,
+            }),
+        };
+        let source = "\n".repeat(9) + "\t x @";
+
+        assert_eq!(
+            err.render(&source),
+            "10:5: illegal character '@'\n   |\n10 | \t x @\n   | \t   ^"
+        );
+
+        let source = "let a = 1;\nlet b = 2 * (a / 0);";
+        let program = Parser::new(Lexer::new(String::from(source)))
+            .parse_program()
+            .unwrap();
+        let err = eval(program, &Rc::new(RefCell::new(Environment::new()))).unwrap_err();
+
+        assert_eq!(
+            err.render(source),
+            "2:14: division by zero\n  |\n2 | let b = 2 * (a / 0);\n  |              ^"
+        );
+
+        let err = MonkeyError::from(String::from("division by zero"));
+        assert_eq!(err.render("1 / 0"), "division by zero");
+
+        let err = MonkeyError::Parse {
+            message: String::from("oops"),
+            position: Some(Position { line: 3, column: 1 }),
+        };
+        assert_eq!(err.render("1"), "3:1: oops");
+    }
+}
@@ -1,31 +1,92 @@
-use crate::evaluator::environment::OuterEnvWrapper;
-use std::{collections::HashMap, fmt::Display, hash::Hash, rc::Rc};
+use crate::evaluator::environment::FunctionEnv;
+use std::{cmp::Ordering, collections::HashMap, fmt::Display, hash::Hash, rc::Rc};
 
 use crate::{
+    builtins::BuiltinContext,
     code::code::Instructions,
     parser::ast::{Identifier, Statement},
     result::MonkeyResult,
 };
 
+// Only plain values can be serialized, functions, builtins, closures and
+// return values fail with a "cannot be serialized" error
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
 pub enum Object {
     Integer(Integer),
+    Float(Float),
     Boolean(Boolean),
     Null(Null),
+    #[cfg_attr(feature = "serde", serde(skip))]
     Return(Return),
+    #[cfg_attr(feature = "serde", serde(skip))]
     Function(Function),
     String(Str),
+    #[cfg_attr(feature = "serde", serde(skip))]
     Builtin(BuiltinFunction),
     Array(Array),
     HashTable(HashTable),
+    #[cfg_attr(feature = "serde", serde(skip))]
     CompiledFunction(CompiledFunction),
+    #[cfg_attr(feature = "serde", serde(skip))]
     Closure(Closure),
 }
 
+impl Object {
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Object::Boolean(bool) => bool.value,
+            Object::Null(_) => false,
+            _ => true,
+        }
+    }
+
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            Object::Integer(int) => Some(int.value as f64),
+            Object::Float(float) => Some(float.value),
+            _ => None,
+        }
+    }
+
+    pub fn validate_hash_key(&self) -> MonkeyResult<()> {
+        match self {
+            Object::Integer(_) | Object::Boolean(_) | Object::String(_) => Ok(()),
+            actual => Err(format!("unusable as hash key: {}", actual.type_name())),
+        }
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Object::Integer(_) => "Integer",
+            Object::Float(_) => "Float",
+            Object::Boolean(_) => "Boolean",
+            Object::Null(_) => "Null",
+            Object::Return(_) => "Return",
+            Object::Function(_) | Object::CompiledFunction(_) | Object::Closure(_) => "Function",
+            Object::String(_) => "String",
+            Object::Builtin(_) => "Builtin",
+            Object::Array(_) => "Array",
+            Object::HashTable(_) => "HashTable",
+        }
+    }
+
+    pub(crate) fn compare(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Object::Integer(left), Object::Integer(right)) => Some(left.value.cmp(&right.value)),
+            (Object::Float(left), Object::Float(right)) => left.value.partial_cmp(&right.value),
+            (Object::String(left), Object::String(right)) => Some(left.value.cmp(&right.value)),
+            _ => None,
+        }
+    }
+}
+
 impl Display for Object {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Object::Integer(int) => write!(f, "{int}"),
+            Object::Float(float) => write!(f, "{float}"),
             Object::Boolean(bool) => write!(f, "{bool}"),
             Object::Null(null) => write!(f, "{null}"),
             Object::Return(return_statement) => write!(f, "{return_statement}"),
@@ -41,6 +102,7 @@ impl Display for Object {
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Integer {
     pub value: i64,
 }
@@ -51,7 +113,34 @@ impl Display for Integer {
     }
 }
 
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Float {
+    pub value: f64,
+}
+
+impl Hash for Float {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.value.to_bits().hash(state);
+    }
+}
+
+impl PartialEq for Float {
+    fn eq(&self, other: &Self) -> bool {
+        self.value.to_bits() == other.value.to_bits()
+    }
+}
+
+impl Eq for Float {}
+
+impl Display for Float {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.value)
+    }
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Boolean {
     pub value: bool,
 }
@@ -63,6 +152,7 @@ impl Display for Boolean {
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Null {}
 
 impl Display for Null {
@@ -86,7 +176,7 @@ impl Display for Return {
 pub struct Function {
     pub parameters: Vec<Identifier>,
     pub body: Rc<Statement>,
-    pub env: OuterEnvWrapper,
+    pub env: FunctionEnv,
 }
 
 impl Display for Function {
@@ -104,10 +194,34 @@ impl Display for Function {
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Str {
     pub value: String,
 }
 
+impl Str {
+    pub fn get(&self, index: i64) -> Option<Object> {
+        let index = resolve_index(index, self.value.chars().count())?;
+
+        self.value.chars().nth(index).map(|ch| {
+            Object::String(Str {
+                value: ch.to_string(),
+            })
+        })
+    }
+
+    pub fn chars(&self) -> Vec<Object> {
+        self.value
+            .chars()
+            .map(|ch| {
+                Object::String(Str {
+                    value: ch.to_string(),
+                })
+            })
+            .collect()
+    }
+}
+
 impl Display for Str {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.value)
@@ -115,7 +229,9 @@ impl Display for Str {
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
-pub struct BuiltinFunction(pub fn(args: Vec<Object>) -> MonkeyResult<Object>);
+pub struct BuiltinFunction(
+    pub fn(args: Vec<Object>, ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object>,
+);
 
 impl Display for BuiltinFunction {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -124,10 +240,25 @@ impl Display for BuiltinFunction {
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Array {
     pub elements: Vec<Object>,
 }
 
+impl Array {
+    pub fn get(&self, index: i64) -> Option<&Object> {
+        self.elements
+            .get(resolve_index(index, self.elements.len())?)
+    }
+}
+
+fn resolve_index(index: i64, len: usize) -> Option<usize> {
+    match index {
+        index if index < 0 => len.checked_sub(index.unsigned_abs() as usize),
+        index => Some(index as usize),
+    }
+}
+
 impl Display for Array {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let elements = self
@@ -142,7 +273,9 @@ impl Display for Array {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HashTable {
+    #[cfg_attr(feature = "serde", serde(with = "hash_pairs"))]
     pub pairs: HashMap<Object, Object>,
 }
 
@@ -171,6 +304,30 @@ impl Hash for HashTable {
     }
 }
 
+#[cfg(feature = "serde")]
+#[allow(clippy::mutable_key_type)]
+mod hash_pairs {
+    use std::collections::HashMap;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::Object;
+
+    pub fn serialize<S: Serializer>(
+        pairs: &HashMap<Object, Object>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(pairs.iter())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<Object, Object>, D::Error> {
+        let pairs: Vec<(Object, Object)> = Vec::deserialize(deserializer)?;
+        Ok(pairs.into_iter().collect())
+    }
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct CompiledFunction {
     pub instructions: Instructions,
@@ -195,3 +352,121 @@ impl Display for Closure {
         write!(f, "Closure[{}]", self.func)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{cmp::Ordering, collections::HashMap};
+
+    use super::{
+        Array, Boolean, Closure, CompiledFunction, Float, HashTable, Integer, Null, Object, Str,
+    };
+    use crate::{builtins::get_builtin_function, code::code::Instructions};
+
+    #[test]
+    fn truthiness_test() {
+        let compiled_fn = CompiledFunction {
+            instructions: Instructions(vec![]),
+            locals_num: 0,
+            parameters_num: 0,
+        };
+
+        let expected = vec![
+            (Object::Boolean(Boolean { value: true }), true),
+            (Object::Boolean(Boolean { value: false }), false),
+            (Object::Null(Null {}), false),
+            (Object::Integer(Integer { value: 0 }), true),
+            (Object::Integer(Integer { value: -1 }), true),
+            (Object::Float(Float { value: 0.0 }), true),
+            (
+                Object::String(Str {
+                    value: String::new(),
+                }),
+                true,
+            ),
+            (Object::Array(Array { elements: vec![] }), true),
+            (
+                Object::HashTable(HashTable {
+                    pairs: HashMap::new(),
+                }),
+                true,
+            ),
+            (Object::CompiledFunction(compiled_fn.clone()), true),
+            (
+                Object::Closure(Closure {
+                    func: compiled_fn,
+                    free: vec![],
+                }),
+                true,
+            ),
+            (get_builtin_function("len").unwrap(), true),
+        ];
+
+        for (obj, expected_result) in expected {
+            assert_eq!(obj.is_truthy(), expected_result, "truthiness of {obj:?}");
+        }
+    }
+
+    #[test]
+    fn compare_test() {
+        let int = |value| Object::Integer(Integer { value });
+        let float = |value| Object::Float(Float { value });
+        let string = |value: &str| {
+            Object::String(Str {
+                value: String::from(value),
+            })
+        };
+
+        let expected = vec![
+            (int(1), int(2), Some(Ordering::Less)),
+            (int(2), int(2), Some(Ordering::Equal)),
+            (float(2.5), float(-1.0), Some(Ordering::Greater)),
+            (string("a"), string("b"), Some(Ordering::Less)),
+            (string("b"), string("ab"), Some(Ordering::Greater)),
+            (int(1), float(1.0), None),
+            (int(1), string("1"), None),
+            (Object::Boolean(Boolean { value: true }), int(1), None),
+            (Object::Null(Null {}), Object::Null(Null {}), None),
+            (float(f64::NAN), float(f64::NAN), None),
+            (float(f64::NAN), float(1.0), None),
+            (float(-0.0), float(0.0), Some(Ordering::Equal)),
+        ];
+
+        for (left, right, expected_result) in expected {
+            assert_eq!(left.compare(&right), expected_result);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip_test() {
+        let hash = |key: &str, value: i64| {
+            Object::HashTable(HashTable {
+                pairs: HashMap::from([(
+                    Object::String(Str {
+                        value: String::from(key),
+                    }),
+                    Object::Integer(Integer { value }),
+                )]),
+            })
+        };
+
+        let value = Object::Array(Array {
+            elements: vec![
+                hash("a", 1),
+                hash("b", -2),
+                Object::Boolean(Boolean { value: true }),
+                Object::Null(Null {}),
+            ],
+        });
+
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"Array","value":{"elements":[{"type":"HashTable","value":{"pairs":[[{"type":"String","value":{"value":"a"}},{"type":"Integer","value":{"value":1}}]]}},{"type":"HashTable","value":{"pairs":[[{"type":"String","value":{"value":"b"}},{"type":"Integer","value":{"value":-2}}]]}},{"type":"Boolean","value":{"value":true}},{"type":"Null","value":{}}]}}"#
+        );
+        assert_eq!(serde_json::from_str::<Object>(&json).unwrap(), value);
+
+        let builtin = get_builtin_function("len").unwrap();
+        assert!(serde_json::to_string(&builtin).is_err());
+    }
+}
@@ -1,13 +1,28 @@
-use core::panic;
-use std::{collections::HashMap, usize};
+use std::{collections::HashMap, fmt::Debug, io::{self, Write}, rc::Rc, usize};
 
 use crate::{
-    builtins::{get_builtin_function, BUILTINS}, code::code::{read_u16, Instructions, OpCodeType}, compiler::compiler::ByteCode, result::MonkeyResult, types::{Array, Boolean, BuiltinFunction, Closure, CompiledFunction, HashTable, Integer, Null, Object, Str}
+    builtins::{get_builtin_function, BuiltinContext, MAX_BUILTIN_CALL_DEPTH}, code::code::{definition, read_operands, read_u16, read_u8, Instructions, OpCodeType}, compiler::compiler::ByteCode, result::{MonkeyError, MonkeyResult}, types::{Array, Boolean, BuiltinFunction, Closure, CompiledFunction, Float, HashTable, Integer, Null, Object, Str}
 };
 
-const STACK_SIZE: usize = 2048;
-const GLOBALS_SIZE: usize = 65536;
-const MAX_FRAMES: usize = 1024;
+pub const GLOBALS_SIZE: usize = 65536;
+pub const MAX_FRAMES: usize = 1024;
+// enough slots per frame for the call depth limit to trip before the stack runs out
+const FRAME_STACK_SLOTS: usize = 8;
+const STACK_SIZE: usize = MAX_FRAMES * FRAME_STACK_SLOTS;
+
+pub fn empty_globals() -> Vec<Rc<Object>> {
+    let null = Rc::new(Object::Null(Null {}));
+    vec![null; GLOBALS_SIZE]
+}
+
+fn resolve_builtins(names: Vec<String>) -> Vec<(String, Option<Rc<Object>>)> {
+    names.into_iter()
+        .map(|name| {
+            let builtin = get_builtin_function(&name).map(Rc::new);
+            (name, builtin)
+        })
+        .collect()
+}
 
 #[derive(Debug, Clone)]
 struct Frame {
@@ -26,14 +41,41 @@ impl Frame {
     }
 }
 
-#[derive(Debug)]
 pub struct Vm {
-    constants: Vec<Object>,
-    stack: Vec<Object>,
+    constants: Vec<Rc<Object>>,
+    stack: Vec<Rc<Object>>,
     sp: usize,
-    pub globals: Vec<Object>,
+    stack_limit: usize,
+    pub globals: Vec<Rc<Object>>,
     frames: Vec<Option<Frame>>,
-    frames_index: usize
+    frames_index: usize,
+    max_frames: usize,
+    builtin_call_depth: usize,
+    output: Box<dyn Write>,
+    trace: bool,
+    trace_output: Box<dyn Write>,
+    instruction_counts: Option<HashMap<OpCodeType, u64>>,
+    builtins: Vec<(String, Option<Rc<Object>>)>,
+    global_names: HashMap<String, usize>,
+    last_popped: Option<Rc<Object>>,
+}
+
+impl Debug for Vm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Vm")
+            .field("constants", &self.constants)
+            .field("stack", &self.stack)
+            .field("sp", &self.sp)
+            .field("stack_limit", &self.stack_limit)
+            .field("globals", &self.globals)
+            .field("frames", &self.frames)
+            .field("frames_index", &self.frames_index)
+            .field("max_frames", &self.max_frames)
+            .field("builtin_call_depth", &self.builtin_call_depth)
+            .field("trace", &self.trace)
+            .field("instruction_counts", &self.instruction_counts)
+            .finish_non_exhaustive()
+    }
 }
 
 impl Vm {
@@ -48,13 +90,23 @@ impl Vm {
             constants: byte_code.constants,
             frames,
             frames_index: 1,
-            stack: vec![Object::Null(Null {}); STACK_SIZE],
+            max_frames: MAX_FRAMES,
+            builtin_call_depth: 0,
+            stack: Vec::new(),
             sp: 0,
-            globals: vec![Object::Null(Null {}); GLOBALS_SIZE],
+            stack_limit: STACK_SIZE,
+            output: Box::new(io::stdout()),
+            trace: false,
+            trace_output: Box::new(io::stderr()),
+            instruction_counts: None,
+            builtins: resolve_builtins(byte_code.builtin_names),
+            global_names: byte_code.global_names,
+            last_popped: None,
+            globals: empty_globals(),
         }
     }
 
-    pub fn new_with_global_store(byte_code: ByteCode, globals: Vec<Object>) -> Self {
+    pub fn new_with_global_store(byte_code: ByteCode, globals: Vec<Rc<Object>>) -> Self {
         let main_fn = CompiledFunction { instructions: byte_code.instructions, locals_num: 0, parameters_num: 0 };
         let main_closure = Closure { func: main_fn, free: vec![] };
 
@@ -65,27 +117,114 @@ impl Vm {
             constants: byte_code.constants,
             frames,
             frames_index: 1,
-            stack: vec![Object::Null(Null {}); STACK_SIZE],
+            max_frames: MAX_FRAMES,
+            builtin_call_depth: 0,
+            stack: Vec::new(),
             sp: 0,
+            stack_limit: STACK_SIZE,
+            output: Box::new(io::stdout()),
+            trace: false,
+            trace_output: Box::new(io::stderr()),
+            instruction_counts: None,
+            builtins: resolve_builtins(byte_code.builtin_names),
+            global_names: byte_code.global_names,
+            last_popped: None,
             globals,
         }
     }
 
+    pub fn with_stack_limit(mut self, limit: usize) -> Self {
+        self.stack_limit = limit;
+        self
+    }
+
+    pub fn with_max_frames(mut self, limit: usize) -> Self {
+        self.max_frames = limit;
+        self
+    }
+
+    pub fn with_output(mut self, output: Box<dyn Write>) -> Self {
+        self.output = output;
+        self
+    }
+
+    pub fn with_trace(mut self, trace: bool) -> Self {
+        self.trace = trace;
+        self
+    }
+
+    pub fn with_trace_output(mut self, output: Box<dyn Write>) -> Self {
+        self.trace_output = output;
+        self
+    }
+
+    pub fn with_instruction_counts(mut self, enabled: bool) -> Self {
+        self.instruction_counts = enabled.then(HashMap::new);
+        self
+    }
+
+    pub fn register_builtin(&mut self, name: &str, builtin: BuiltinFunction) {
+        let builtin = Rc::new(Object::Builtin(builtin));
+
+        // builtins are resolved by the names the compiler has assigned indices to, so a builtin
+        // the compiler doesn't know about is never referenced by the byte code
+        if let Some((_, existing)) = self.builtins.iter_mut().find(|(existing, _)| existing == name) {
+            *existing = Some(builtin);
+        }
+    }
+
+    pub fn call_function(&mut self, name: &str, args: Vec<Object>) -> Result<Object, MonkeyError> {
+        let index = *self.global_names.get(name).ok_or_else(|| format!("couldn't find function {name}"))?;
+        let func = self.globals.get(index).ok_or_else(|| format!("couldn't get global {name}"))?;
+
+        let func = match func.as_ref() {
+            Object::Closure(closure) => Object::Closure(closure.clone()),
+            actual => Err(format!("{name} is not a function, but \"{actual}\""))?,
+        };
+
+        let (sp, frames_index) = (self.sp, self.frames_index);
+        let result = BuiltinContext::call_function(self, func, args);
+
+        if result.is_err() {
+            self.sp = sp;
+            self.frames_index = frames_index;
+        }
+
+        Ok(result?)
+    }
+
+    pub fn instruction_counts(&self) -> Option<&HashMap<OpCodeType, u64>> {
+        self.instruction_counts.as_ref()
+    }
+
     pub fn stack_top(&self) -> Option<&Object> {
-        self.stack.get(self.sp - 1)
+        self.sp.checked_sub(1).and_then(|idx| self.stack.get(idx)).map(|obj| obj.as_ref())
     }
 
-    pub fn run(&mut self) -> MonkeyResult<()> {
+    pub fn run(&mut self) -> Result<(), MonkeyError> {
+        Ok(self.execute(0)?)
+    }
+
+    fn execute(&mut self, min_frames: usize) -> MonkeyResult<()> {
         let mut ip;
 
-        while self.current_frame().is_ok_and(|f| f.instructions().len() > 0 && f.ip < (f.instructions().len() - 1) as isize) {
+        while self.frames_index > min_frames && self.current_frame().is_ok_and(|f| f.instructions().len() > 0 && f.ip < (f.instructions().len() - 1) as isize) {
             self.current_frame()?.ip += 1;
             ip = self.current_frame()?.ip as usize;
+
+            if self.trace {
+                self.trace_instruction(ip)?;
+            }
+
+            if self.instruction_counts.is_some() {
+                self.count_instruction(ip)?;
+            }
+
             let ins = self.current_frame()?.instructions();
 
             let op: OpCodeType = (ins
                 .get(ip)
-                .ok_or(format!("couldn't parse byte code"))?)
+                .ok_or_else(|| String::from("couldn't parse byte code"))?)
                 .clone()
                 .try_into()?;
 
@@ -93,26 +232,22 @@ impl Vm {
                 OpCodeType::Constant => {
                     let const_idx = read_u16(ins
                         .get(ip + 1..)
-                        .ok_or(format!("couldn't parse byte code"))?,
-                    );
+                        .ok_or_else(|| String::from("couldn't parse byte code"))?,
+                    )?;
                     self.current_frame()?.ip += 2;
 
-                    self.push(
+                    self.push_shared(
                         self.constants
                             .get(const_idx as usize)
-                            .ok_or(format!("couldn't parse byte code"))?
+                            .ok_or_else(|| String::from("couldn't parse byte code"))?
                             .clone(),
                     )?;
                 }
-                op if op == OpCodeType::Add
-                    || op == OpCodeType::Sub
-                    || op == OpCodeType::Mul
-                    || op == OpCodeType::Div =>
-                {
+                OpCodeType::Add | OpCodeType::Sub | OpCodeType::Mul | OpCodeType::Div | OpCodeType::Mod => {
                     self.execute_binary_operation(op)?;
                 }
                 OpCodeType::Pop => {
-                    self.pop()?;
+                    self.pop_result()?;
                 }
                 OpCodeType::True => {
                     self.push(Object::Boolean(Boolean { value: true }))?;
@@ -120,43 +255,47 @@ impl Vm {
                 OpCodeType::False => {
                     self.push(Object::Boolean(Boolean { value: false }))?;
                 }
-                op if op == OpCodeType::GreaterThan
-                    || op == OpCodeType::Equal
-                    || op == OpCodeType::NotEqual =>
-                {
+                OpCodeType::GreaterThan | OpCodeType::LessThan | OpCodeType::Equal | OpCodeType::NotEqual => {
                     self.execute_comparison(op)?;
                 }
-                OpCodeType::Bang => match self.pop()? {
-                    Object::Boolean(bool) => {
-                        self.push(Object::Boolean(Boolean { value: !bool.value }))?
-                    }
-                    Object::Null(_) => self.push(Object::Boolean(Boolean { value: true }))?,
-                    _ => self.push(Object::Boolean(Boolean { value: false }))?,
-                },
+                OpCodeType::Bang => {
+                    let operand = self.pop()?;
+                    self.push(Object::Boolean(Boolean { value: !operand.is_truthy() }))?;
+                }
                 OpCodeType::Minus => match self.pop()? {
-                    Object::Integer(int) => {
-                        self.push(Object::Integer(Integer { value: -int.value }))?
-                    }
+                    Object::Integer(int) => match int.value.checked_neg() {
+                        Some(value) => self.push(Object::Integer(Integer { value }))?,
+                        None => Err(String::from("integer overflow in negation"))?,
+                    },
+                    Object::Float(float) => self.push(Object::Float(Float { value: -float.value }))?,
                     actual => Err(format!("unsupported type for negation, got {actual}"))?,
                 },
+                OpCodeType::Plus => {
+                    let operand = self.pop_shared()?;
+
+                    match operand.as_ref() {
+                        Object::Integer(_) | Object::Float(_) => self.push_shared(operand)?,
+                        actual => Err(format!("unsupported type for unary plus, got {actual}"))?,
+                    }
+                }
                 OpCodeType::Jump => {
                     let pos = read_u16( ins
                         .get(ip + 1..)
-                        .ok_or(format!("couldn't parse byte code"))?,
-                    );
+                        .ok_or_else(|| String::from("couldn't parse byte code"))?,
+                    )?;
 
                     self.current_frame()?.ip = (pos - 1) as isize;
                 }
                 OpCodeType::JumpNotTruthy => {
                     let pos = read_u16(ins
                         .get(ip + 1..)
-                        .ok_or(format!("couldn't parse byte code"))?,
-                    );
+                        .ok_or_else(|| String::from("couldn't parse byte code"))?,
+                    )?;
 
                     self.current_frame()?.ip += 2;
                     let condition = self.pop()?;
 
-                    if !Self::is_truthy(condition) {
+                    if !condition.is_truthy() {
                         self.current_frame()?.ip = (pos - 1) as isize;
                     }
                 }
@@ -164,62 +303,69 @@ impl Vm {
                 OpCodeType::SetGlobal => {
                     let pos = read_u16(ins
                         .get(ip + 1..)
-                        .ok_or(format!("couldn't parse byte code"))?,
-                    );
+                        .ok_or_else(|| String::from("couldn't parse byte code"))?,
+                    )?;
                     self.current_frame()?.ip += 2;
 
-                    self.globals[pos as usize] = self.pop()?;
+                    let value = self.pop_shared()?;
+
+                    if self.frames_index == 1 {
+                        self.last_popped = Some(value.clone());
+                    }
+
+                    self.globals[pos as usize] = value;
                 }
                 OpCodeType::GetGlobal => {
                     let pos = read_u16(ins
                         .get(ip + 1..)
-                        .ok_or(format!("couldn't parse byte code"))?,
-                    );
+                        .ok_or_else(|| String::from("couldn't parse byte code"))?,
+                    )?;
                     self.current_frame()?.ip += 2;
 
-                    self.push(
+                    self.push_shared(
                         self.globals
                             .get(pos as usize)
-                            .ok_or(String::from("couldn't parse byte code"))?
+                            .ok_or_else(|| String::from("couldn't parse byte code"))?
                             .clone(),
                     )?;
                 }
                 OpCodeType::Array => {
                     let array_len = read_u16(ins
                         .get(ip + 1..)
-                        .ok_or(format!("couldn't parse byte code"))?,
-                    );
+                        .ok_or_else(|| String::from("couldn't parse byte code"))?,
+                    )?;
                     self.current_frame()?.ip += 2;
 
-                    let array = self.build_array(self.sp - array_len as usize, self.sp)?;
-                    self.sp = self.sp - array_len as usize;
-
+                    let array = self.build_array(array_len as usize)?;
                     self.push(array)?;
                 }
                 OpCodeType::Hash => {
                     let hash_len = read_u16(ins
                         .get(ip + 1..)
-                        .ok_or(format!("couldn't parse byte code"))?,
-                    );
+                        .ok_or_else(|| String::from("couldn't parse byte code"))?,
+                    )?;
                     self.current_frame()?.ip += 2;
 
                     let hash = self.build_hash(hash_len as usize)?;
-                    self.sp = self.sp - hash_len as usize;
-
                     self.push(hash)?;
                 }
                 OpCodeType::Index => {
-                    let index = self.pop()?;
-                    let left = self.pop()?;
+                    let index = self.pop_shared()?;
+                    let left = self.pop_shared()?;
 
-                    self.execute_index_expression(left, index)?;
+                    self.execute_index_expression(&left, &index)?;
                 }
                 OpCodeType::ReturnValue => {
-                    let return_value = self.pop()?;
+                    if self.frames_index == 1 {
+                        return self.pop_result();
+                    }
+
+                    let return_value = self.pop_shared()?;
+
                     let frame = self.pop_frame()?;
 
                     self.sp = frame.base_pointer - 1;
-                    self.push(return_value)?;
+                    self.push_shared(return_value)?;
                 }
                 OpCodeType::Return => {
                     let frame = self.pop_frame()?;
@@ -228,53 +374,55 @@ impl Vm {
                     self.push(Object::Null(Null { }))?;
                 }
                 OpCodeType::SetLocal => {
-                    let local_index = *ins.get(ip + 1).ok_or(format!("couldn't get local index"))?;
+                    let local_index = read_u8(ins.get(ip + 1..).ok_or_else(|| String::from("couldn't get local index"))?)?;
                     self.current_frame()?.ip += 1;
 
                     let base_pointer = self.current_frame()?.base_pointer;
-                    self.stack[base_pointer + local_index as usize] = self.pop()?;
+                    self.stack[base_pointer + local_index as usize] = self.pop_shared()?;
                 }
                 OpCodeType::GetLocal => {
-                    let local_index = *ins.get(ip + 1).ok_or(format!("couldn't get local index"))?;
+                    let local_index = read_u8(ins.get(ip + 1..).ok_or_else(|| String::from("couldn't get local index"))?)?;
                     self.current_frame()?.ip += 1;
 
                     let base_pointer = self.current_frame()?.base_pointer;
-                    let local = self.stack.get(base_pointer + local_index as usize).ok_or(format!("couldn't get local variable"))?.clone();
-                    self.push(local)?;
+                    let local = self.stack.get(base_pointer + local_index as usize).ok_or_else(|| String::from("couldn't get local variable"))?.clone();
+                    self.push_shared(local)?;
                 }
                 OpCodeType::GetBuiltin => {
-                    let builtin_index = *ins.get(ip + 1).ok_or(format!("couldn't get builtin index"))?;
+                    let builtin_index = read_u8(ins.get(ip + 1..).ok_or_else(|| String::from("couldn't get builtin index"))?)?;
                     self.current_frame()?.ip += 1;
 
-                    let builtin_name = BUILTINS.get(builtin_index as usize).ok_or(format!("couldn't get builtin function name"))?;
-                    let builtin = get_builtin_function(builtin_name).ok_or(format!("couldn't get builtin function"))?;
-                    self.push(builtin)?;
+                    let builtin = match self.builtins.get(builtin_index as usize) {
+                        Some((_, Some(builtin))) => builtin.clone(),
+                        Some((name, None)) => Err(format!("couldn't find builtin function {name}"))?,
+                        None => Err(String::from("couldn't get builtin function"))?,
+                    };
+                    self.push_shared(builtin)?;
                 }
                 OpCodeType::Call => {
-                    let args_num = *ins.get(ip + 1).ok_or(format!("couldn't get args number"))?;
+                    let args_num = read_u8(ins.get(ip + 1..).ok_or_else(|| String::from("couldn't get args number"))?)?;
                     self.current_frame()?.ip += 1;
 
                     self.execute_call(args_num as usize)?;
                 }
                 OpCodeType::Closure => {
-                    let const_index = read_u16(ins.get(ip + 1..).ok_or(format!("couldn't get constant index"))?);
-                    let free_num = *ins.get(ip + 3).ok_or(format!("couldn't get free vars number"))?;
+                    let const_index = read_u16(ins.get(ip + 1..).ok_or_else(|| String::from("couldn't get constant index"))?)?;
+                    let free_num = read_u8(ins.get(ip + 3..).ok_or_else(|| String::from("couldn't get free vars number"))?)?;
 
                     self.current_frame()?.ip += 3;
                     self.push_closure(const_index as usize, free_num as usize)?;
                 }
                 OpCodeType::GetFree => {
-                    let free_idx = *ins.get(ip + 1).ok_or(format!("couldn't get free index"))?;
+                    let free_idx = read_u8(ins.get(ip + 1..).ok_or_else(|| String::from("couldn't get free index"))?)?;
                     self.current_frame()?.ip += 1;
 
                     let current_closure = self.current_frame()?.cl.clone();
-                    self.push(current_closure.free.get(free_idx as usize).ok_or(format!("couldn't free variable"))?.clone())?;
+                    self.push(current_closure.free.get(free_idx as usize).ok_or_else(|| String::from("couldn't get free variable"))?.clone())?;
                 }
                 OpCodeType::CurrentClosure => {
                     let current_closure = self.current_frame()?.cl.clone();
                     self.push(Object::Closure(current_closure))?;
                 }
-                _ => todo!(),
             }
         }
 
@@ -283,65 +431,131 @@ impl Vm {
 
     pub fn last_popped_stack_elem(&self) -> MonkeyResult<Object> {
         Ok(self
-            .stack
-            .get(self.sp)
-            .ok_or(String::from(
-                "couldn't pop from the stack, index is out of bounds",
-            ))?
-            .clone())
+            .last_popped
+            .as_ref()
+            .map(|obj| obj.as_ref().clone())
+            .unwrap_or(Object::Null(Null {})))
     }
 
     fn push(&mut self, object: Object) -> MonkeyResult<()> {
-        if self.sp >= STACK_SIZE {
+        self.push_shared(Rc::new(object))
+    }
+
+    fn push_shared(&mut self, object: Rc<Object>) -> MonkeyResult<()> {
+        if self.sp >= self.stack_limit {
             return Err(String::from("stack overflow"));
         }
 
-        self.stack[self.sp] = object;
+        self.stack.truncate(self.sp);
+        self.stack.push(object);
         self.sp += 1;
 
         Ok(())
     }
 
     fn pop(&mut self) -> MonkeyResult<Object> {
+        Ok(Rc::unwrap_or_clone(self.pop_shared()?))
+    }
+
+    fn pop_shared(&mut self) -> MonkeyResult<Rc<Object>> {
         self.sp -= 1;
+        self.stack.truncate(self.sp + 1);
 
-        Ok(self
-            .stack
-            .get(self.sp)
-            .ok_or(format!(
-                "couldn't pop from the stack, index is out of bounds",
-            ))?
-            .clone())
+        match self.stack.len() == self.sp + 1 {
+            true => self.stack.pop().ok_or_else(|| String::from("couldn't pop from the stack, index is out of bounds")),
+            false => Err(String::from("couldn't pop from the stack, index is out of bounds")),
+        }
+    }
+
+    fn discard(&mut self) -> MonkeyResult<()> {
+        self.sp = self.sp
+            .checked_sub(1)
+            .filter(|sp| *sp < self.stack.len())
+            .ok_or_else(|| String::from("couldn't pop from the stack, index is out of bounds"))?;
+        self.stack.truncate(self.sp + 1);
+
+        Ok(())
+    }
+
+    fn pop_result(&mut self) -> MonkeyResult<()> {
+        self.discard()?;
+
+        // values popped inside function bodies are never the result of the program
+        if self.frames_index == 1 {
+            self.last_popped = self.stack.pop();
+        }
+
+        Ok(())
+    }
+
+    fn take_objects(&mut self, num: usize) -> MonkeyResult<Vec<Object>> {
+        let start_idx = self.sp
+            .checked_sub(num)
+            .filter(|_| self.sp <= self.stack.len())
+            .ok_or_else(|| String::from("couldn't take objects from the stack, index is out of bounds"))?;
+
+        self.stack.truncate(self.sp);
+        self.sp = start_idx;
+
+        Ok(self.stack.drain(start_idx..).map(Rc::unwrap_or_clone).collect())
     }
 
     fn execute_binary_operation(&mut self, op: OpCodeType) -> MonkeyResult<()> {
-        let right = self.pop()?;
-        let left = self.pop()?;
+        let right = self.pop_shared()?;
+        let left = self.pop_shared()?;
 
-        match (left, right) {
+        match (left.as_ref(), right.as_ref()) {
             (Object::Integer(left_int), Object::Integer(right_int)) => match op {
-                OpCodeType::Add => self.push(Object::Integer(Integer {
-                    value: left_int.value + right_int.value,
-                })),
-                OpCodeType::Sub => self.push(Object::Integer(Integer {
-                    value: left_int.value - right_int.value,
-                })),
-                OpCodeType::Mul => self.push(Object::Integer(Integer {
-                    value: left_int.value * right_int.value,
-                })),
-                OpCodeType::Div => self.push(Object::Integer(Integer {
-                    value: left_int.value / right_int.value,
-                })),
+                OpCodeType::Add => match left_int.value.checked_add(right_int.value) {
+                    Some(value) => self.push(Object::Integer(Integer { value })),
+                    None => Err(String::from("integer overflow in addition")),
+                },
+                OpCodeType::Sub => match left_int.value.checked_sub(right_int.value) {
+                    Some(value) => self.push(Object::Integer(Integer { value })),
+                    None => Err(String::from("integer overflow in subtraction")),
+                },
+                OpCodeType::Mul => match left_int.value.checked_mul(right_int.value) {
+                    Some(value) => self.push(Object::Integer(Integer { value })),
+                    None => Err(String::from("integer overflow in multiplication")),
+                },
+                OpCodeType::Div => match right_int.value {
+                    0 => Err(String::from("division by zero")),
+                    _ => match left_int.value.checked_div(right_int.value) {
+                        Some(value) => self.push(Object::Integer(Integer { value })),
+                        None => Err(String::from("integer overflow in division")),
+                    },
+                },
+                OpCodeType::Mod => match right_int.value {
+                    0 => Err(String::from("division by zero")),
+                    _ => self.push(Object::Integer(Integer {
+                        value: left_int.value.wrapping_rem(right_int.value),
+                    })),
+                },
                 t => Err(format!(
                     "couldn't execute binary operation, wrong operation type - {t}"
                 ))?,
             },
+            (left @ (Object::Integer(_) | Object::Float(_)), right @ (Object::Integer(_) | Object::Float(_))) => {
+                let (left, right) = (left.as_float().unwrap(), right.as_float().unwrap());
+
+                match op {
+                    OpCodeType::Add => self.push(Object::Float(Float { value: left + right })),
+                    OpCodeType::Sub => self.push(Object::Float(Float { value: left - right })),
+                    OpCodeType::Mul => self.push(Object::Float(Float { value: left * right })),
+                    OpCodeType::Div | OpCodeType::Mod if right == 0.0 => Err(String::from("division by zero")),
+                    OpCodeType::Div => self.push(Object::Float(Float { value: left / right })),
+                    OpCodeType::Mod => self.push(Object::Float(Float { value: left % right })),
+                    t => Err(format!(
+                        "couldn't execute binary operation, wrong operation type - {t}"
+                    )),
+                }
+            }
             (Object::String(left_str), Object::String(right_str)) => match op {
                 OpCodeType::Add => self.push(Object::String(Str {
-                    value: left_str.value + &right_str.value,
+                    value: format!("{}{}", left_str.value, right_str.value),
                 })),
                 t => Err(format!(
-                    "couldn't execute binary operation, wrong operation type - {t}"
+                    "couldn't execute binary operation for strings, {} expected, but got {t}", OpCodeType::Add
                 ))?,
             },
             (obj1, obj2) => Err(format!(
@@ -351,10 +565,10 @@ impl Vm {
     }
 
     fn execute_comparison(&mut self, op: OpCodeType) -> MonkeyResult<()> {
-        let right = self.pop()?;
-        let left = self.pop()?;
+        let right = self.pop_shared()?;
+        let left = self.pop_shared()?;
 
-        match (left, right) {
+        match (left.as_ref(), right.as_ref()) {
             (Object::Integer(int1), Object::Integer(int2)) => match op {
                 OpCodeType::Equal => self.push(Object::Boolean(Boolean {
                     value: int1.value == int2.value,
@@ -365,10 +579,26 @@ impl Vm {
                 OpCodeType::GreaterThan => self.push(Object::Boolean(Boolean {
                     value: int1.value > int2.value,
                 })),
+                OpCodeType::LessThan => self.push(Object::Boolean(Boolean {
+                    value: int1.value < int2.value,
+                })),
                 op => Err(format!(
                     "couldn't compare two objects, got wrong operator {op}"
                 )),
             },
+            (left @ (Object::Integer(_) | Object::Float(_)), right @ (Object::Integer(_) | Object::Float(_))) => {
+                let (left, right) = (left.as_float().unwrap(), right.as_float().unwrap());
+
+                match op {
+                    OpCodeType::Equal => self.push(Object::Boolean(Boolean { value: left == right })),
+                    OpCodeType::NotEqual => self.push(Object::Boolean(Boolean { value: left != right })),
+                    OpCodeType::GreaterThan => self.push(Object::Boolean(Boolean { value: left > right })),
+                    OpCodeType::LessThan => self.push(Object::Boolean(Boolean { value: left < right })),
+                    op => Err(format!(
+                        "couldn't compare two objects, got wrong operator {op}"
+                    )),
+                }
+            }
             (Object::Boolean(bool1), Object::Boolean(bool2)) => match op {
                 OpCodeType::Equal => self.push(Object::Boolean(Boolean {
                     value: bool1.value == bool2.value,
@@ -376,8 +606,22 @@ impl Vm {
                 OpCodeType::NotEqual => self.push(Object::Boolean(Boolean {
                     value: bool1.value != bool2.value,
                 })),
+                op => Err(format!(
+                    "couldn't compare two objects, got wrong operator {op}"
+                )),
+            },
+            (Object::String(str1), Object::String(str2)) => match op {
+                OpCodeType::Equal => self.push(Object::Boolean(Boolean {
+                    value: str1.value == str2.value,
+                })),
+                OpCodeType::NotEqual => self.push(Object::Boolean(Boolean {
+                    value: str1.value != str2.value,
+                })),
                 OpCodeType::GreaterThan => self.push(Object::Boolean(Boolean {
-                    value: bool1.value > bool2.value,
+                    value: str1.value > str2.value,
+                })),
+                OpCodeType::LessThan => self.push(Object::Boolean(Boolean {
+                    value: str1.value < str2.value,
                 })),
                 op => Err(format!(
                     "couldn't compare two objects, got wrong operator {op}"
@@ -389,95 +633,120 @@ impl Vm {
         }
     }
 
-    fn is_truthy(condition: Object) -> bool {
-        match condition {
-            Object::Boolean(bool) => bool.value,
-            Object::Null(_) => false,
-            _ => true,
-        }
-    }
-
-    fn build_array(&self, start_idx: usize, end_idx: usize) -> MonkeyResult<Object> {
-        let elements = Vec::from(
-            self.stack
-                .get(start_idx..end_idx)
-                .ok_or(String::from("couldn't build an array"))?,
-        );
+    fn build_array(&mut self, array_len: usize) -> MonkeyResult<Object> {
+        let elements = self.take_objects(array_len)
+            .map_err(|_| String::from("couldn't build an array"))?;
 
         Ok(Object::Array(Array { elements }))
     }
 
-    fn build_hash(&self, hash_len: usize) -> MonkeyResult<Object> {
-        let start_idx = self.sp - hash_len;
-        let pair_count = hash_len / 2;
-
-        let mut pairs = HashMap::new();
-
-        for idx in 0..pair_count {
-            let key = self
-                .stack
-                .get(start_idx + 2 * idx)
-                .ok_or(String::from("couldn't build a hash"))?;
-            let value = self
-                .stack
-                .get(start_idx + 2 * idx + 1)
-                .ok_or(String::from("couldn't build a hash"))?;
-
-            pairs.insert(key.clone(), value.clone());
-        }
+    fn build_hash(&mut self, hash_len: usize) -> MonkeyResult<Object> {
+        let mut objects = self.take_objects(hash_len)
+            .map_err(|_| String::from("couldn't build a hash"))?
+            .into_iter();
 
-        Ok(Object::HashTable(HashTable { pairs }))
+        Ok(Object::HashTable(HashTable {
+            pairs: std::iter::from_fn(|| Some((objects.next()?, objects.next()?)))
+                .map(|(key, value)| key.validate_hash_key().map(|_| (key, value)))
+                .collect::<MonkeyResult<_>>()?,
+        }))
     }
 
-    fn execute_index_expression(&mut self, left: Object, index: Object) -> MonkeyResult<()> {
-        match (left, &index) {
+    fn execute_index_expression(&mut self, left: &Object, index: &Object) -> MonkeyResult<()> {
+        match (left, index) {
             (Object::Array(array), Object::Integer(idx)) => {
-                match array.elements.get(idx.value as usize) {
+                match array.get(idx.value) {
                     Some(el) => self.push(el.clone()),
                     None => self.push(Object::Null(Null {  }))
                 }
             }
-            (Object::HashTable(hash), Object::Integer(_)) 
-                | (Object::HashTable(hash), Object::Boolean(_)) 
-                | (Object::HashTable(hash), Object::String(_)) => {
-                    match hash.pairs.get(&index) {
-                        Some(el) => self.push(el.clone()),
-                        None => self.push(Object::Null(Null { }))
-                    }
+            (Object::String(string), Object::Integer(idx)) => {
+                self.push(string.get(idx.value).unwrap_or(Object::Null(Null { })))
+            }
+            (Object::HashTable(hash), _) => {
+                index.validate_hash_key()?;
+
+                match hash.pairs.get(index) {
+                    Some(el) => self.push(el.clone()),
+                    None => self.push(Object::Null(Null { }))
                 }
-            (actual_left, actual_idx) => panic!("couldn't execute index expression, array with int index or hash table expected, but got type \"{actual_left}\" and idx \"{actual_idx}\""),
+            }
+            (actual_left, actual_idx) => Err(format!("couldn't execute index expression, array or string with int index or hash table expected, but got type \"{actual_left}\" and idx \"{actual_idx}\"")),
+        }
+    }
+
+    fn trace_instruction(&mut self, ip: usize) -> MonkeyResult<()> {
+        let ins = self.current_frame()?.instructions();
+        let op: OpCodeType = (*ins.get(ip).ok_or_else(|| String::from("couldn't parse byte code"))?).try_into()?;
+        let def = definition(op.clone());
+        let width = def.operand_widths.iter().sum::<u32>() as usize;
+
+        let operands_bytes = ins.get(ip + 1..ip + 1 + width).ok_or_else(|| format!("couldn't trace {op} at {ip:0>4}, expected {width} operand bytes"))?;
+        let (operands, _) = read_operands(def, operands_bytes.into())?;
+
+        let operands = operands
+            .iter()
+            .map(|o| format!(" {o}"))
+            .collect::<String>();
+        let stack = self.stack
+            .iter()
+            .take(self.sp)
+            .map(|o| o.to_string())
+            .reduce(|acc, cur| format!("{acc}, {cur}"))
+            .unwrap_or(String::new());
+
+        writeln!(self.trace_output, "{ip:0>4} {op}{operands} [{stack}]").map_err(|err| format!("unable to write trace: {err}"))
+    }
+
+    fn count_instruction(&mut self, ip: usize) -> MonkeyResult<()> {
+        let op: OpCodeType = (*self.current_frame()?.instructions().get(ip).ok_or_else(|| String::from("couldn't parse byte code"))?).try_into()?;
+
+        if let Some(counts) = self.instruction_counts.as_mut() {
+            *counts.entry(op).or_insert(0) += 1;
         }
+
+        Ok(())
     }
 
     fn current_frame(&mut self) -> MonkeyResult<&mut Frame> {
         self.frames
             .get_mut(self.frames_index - 1)
-            .ok_or(String::from("couldn't get current frame"))?
+            .ok_or_else(|| String::from("couldn't get current frame"))?
             .as_mut()
-            .ok_or(format!("couldn't get current frame"))
+            .ok_or_else(|| String::from("couldn't get current frame"))
     }
 
-    fn push_frame(&mut self, frame: Frame) {
-        self.frames[self.frames_index] = Some(frame);
+    fn push_frame(&mut self, frame: Frame) -> MonkeyResult<()> {
+        if self.frames_index >= self.max_frames {
+            return Err(String::from("maximum call depth exceeded"));
+        }
+
+        match self.frames.get_mut(self.frames_index) {
+            Some(slot) => *slot = Some(frame),
+            None => self.frames.push(Some(frame)),
+        }
         self.frames_index +=1;
+
+        Ok(())
     }
 
     fn pop_frame(&mut self) -> MonkeyResult<Frame> {
         self.frames_index -= 1;
         self.frames
             .get(self.frames_index)
-            .ok_or(format!("couldn't pop frame, frames stack is empty"))?
+            .ok_or_else(|| String::from("couldn't pop frame, frames stack is empty"))?
             .clone()
-            .ok_or(format!("couldn't pop frame, frames stack is empty"))
+            .ok_or_else(|| String::from("couldn't pop frame, frames stack is empty"))
     }
 
     fn execute_call(&mut self, args_num: usize) -> MonkeyResult<()> {
-        let callee = self.stack.get(self.sp - 1 - args_num).ok_or(format!("couldn't get callee, while executing call"))?.clone();
+        let callee = self.stack.get_mut(self.sp - 1 - args_num).ok_or_else(|| String::from("couldn't get callee, while executing call"))?;
+        let callee = Rc::unwrap_or_clone(std::mem::replace(callee, Rc::new(Object::Null(Null {}))));
 
         match callee {
             Object::Closure(closure) => self.call_closure(closure, args_num),
             Object::Builtin(func) => self.call_builtin(func, args_num),
-            actual => Err(format!("closure or builtin function expected, but got \"{actual:?}\"")),
+            actual => Err(format!("closure or builtin function expected, but got \"{actual}\"")),
         }
     }
 
@@ -485,21 +754,26 @@ impl Vm {
         if args_num != closure.func.parameters_num {
             return Err(format!("wrong number of arguments: want={}, got={}", closure.func.parameters_num, args_num));
         }
-        let frame = Frame::new(closure.clone(), self.sp - args_num);
+        let locals_num = closure.func.locals_num;
+        let frame = Frame::new(closure, self.sp - args_num);
 
         let base_pointer = frame.base_pointer;
-        let locals_num = closure.func.locals_num;
 
-        self.push_frame(frame);
+        if base_pointer + locals_num > self.stack_limit {
+            return Err(String::from("stack overflow"));
+        }
+
+        self.push_frame(frame)?;
         self.sp = base_pointer + locals_num;
+        self.stack.resize(self.sp, Rc::new(Object::Null(Null {})));
 
         Ok(())
     }
 
     fn call_builtin(&mut self, builtin: BuiltinFunction, args_num: usize) -> MonkeyResult<()> {
-        let args = self.stack.get(self.sp - args_num..self.sp).ok_or(format!("couldn't get args while calling builtin"))?;
-        let result = (builtin.0)(args.to_vec())?;
-        self.sp = self.sp - args_num - 1;
+        let args = self.take_objects(args_num).map_err(|_| String::from("couldn't get args while calling builtin"))?;
+        let result = (builtin.0)(args, self)?;
+        self.sp -= 1;
 
         self.push(result)?;
 
@@ -507,11 +781,12 @@ impl Vm {
     }
 
     fn push_closure(&mut self, const_index: usize, free_num: usize) -> MonkeyResult<()> {
-        let constant = self.constants.get(const_index).ok_or(format!("couldn't get constant, while pushing closure"))?.clone();
+        let constant = self.constants.get(const_index).ok_or_else(|| String::from("couldn't get constant, while pushing closure"))?.clone();
 
-        match constant {
-            Object::CompiledFunction(compiled_fn) => { 
-                let free = self.stack.get(self.sp - free_num..self.sp).ok_or(format!("couldn't get free vars while, pushing closure"))?.iter().cloned().collect::<Vec<_>>();
+        match constant.as_ref() {
+            Object::CompiledFunction(compiled_fn) => {
+                let compiled_fn = compiled_fn.clone();
+                let free = self.take_objects(free_num).map_err(|_| String::from("couldn't get free vars while, pushing closure"))?;
                 self.push(Object::Closure(Closure { func: compiled_fn, free })) 
             },
             actual => Err(format!("couldn't push closure, compiled function expected, but got \"{actual}\""))
@@ -519,13 +794,43 @@ impl Vm {
     }
 }
 
+impl BuiltinContext for Vm {
+    fn output(&mut self) -> &mut dyn Write {
+        self.output.as_mut()
+    }
+
+    fn call_function(&mut self, func: Object, args: Vec<Object>) -> MonkeyResult<Object> {
+        if self.builtin_call_depth >= MAX_BUILTIN_CALL_DEPTH {
+            return Err(String::from("maximum call depth exceeded"));
+        }
+
+        let frames_index = self.frames_index;
+        let args_num = args.len();
+
+        self.push(func)?;
+        for arg in args {
+            self.push(arg)?;
+        }
+
+        self.builtin_call_depth += 1;
+        let result = self.execute_call(args_num).and_then(|_| match self.frames_index > frames_index {
+            true => self.execute(frames_index),
+            false => Ok(()),
+        });
+        self.builtin_call_depth -= 1;
+
+        result?;
+        self.pop()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use core::panic;
-    use std::collections::HashMap;
+    use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
     use crate::{
-        compiler::compiler::Compiler, lexer::lexer::Lexer,
+        code::code::make, compiler::{compiler::Compiler, symbol_table::SymbolTable}, evaluator::{environment::Environment, evaluator::eval}, lexer::lexer::Lexer,
         parser::parser::Parser, types::Object,
     };
 
@@ -539,6 +844,7 @@ mod tests {
     #[derive(Debug)]
     enum TestCaseResult {
         Integer(i64),
+        Float(f64),
         Boolean(bool),
         String(String),
         Array(Vec<TestCaseResult>),
@@ -553,6 +859,9 @@ mod tests {
                 (TestCaseResult::Integer(expected), Object::Integer(actual_int)) => {
                     assert_eq!(expected, &actual_int.value)
                 }
+                (TestCaseResult::Float(expected), Object::Float(actual_float)) => {
+                    assert_eq!(expected, &actual_float.value)
+                }
                 (TestCaseResult::Boolean(expected), Object::Boolean(actual_bool)) => {
                     assert_eq!(expected, &actual_bool.value)
                 }
@@ -617,7 +926,7 @@ mod tests {
             for (idx, constant) in byte_code.constants.iter().enumerate() {
                 println!("CONSTANT {idx} {constant}");
 
-                match constant {
+                match constant.as_ref() {
                     Object::Integer(int) => println!("Value: {}", int.value),
                     Object::CompiledFunction(compiled_func) => println!("Instructions: {}", compiled_func.instructions),
                     _ => ()
@@ -627,7 +936,7 @@ mod tests {
             let mut vm = Vm::new(byte_code);
 
             if let Err(err) = vm.run() {
-                case.expected.test_err(err);
+                case.expected.test_err(err.to_string());
                 continue;
             }
 
@@ -699,6 +1008,18 @@ mod tests {
                 input: String::from("-10"),
                 expected: TestCaseResult::Integer(-10),
             },
+            TestCase {
+                input: String::from("+5"),
+                expected: TestCaseResult::Integer(5),
+            },
+            TestCase {
+                input: String::from("+(-3)"),
+                expected: TestCaseResult::Integer(-3),
+            },
+            TestCase {
+                input: String::from("+\"a\""),
+                expected: TestCaseResult::Error(String::from("unsupported type for unary plus, got a")),
+            },
             TestCase {
                 input: String::from("-50 + 100 + -50"),
                 expected: TestCaseResult::Integer(0),
@@ -819,53 +1140,81 @@ mod tests {
                 input: String::from("!(if (false) { 5; })"),
                 expected: TestCaseResult::Boolean(true),
             },
+            TestCase {
+                input: String::from("true > false"),
+                expected: TestCaseResult::Error(String::from("couldn't compare two objects, got wrong operator OpGreaterThan")),
+            },
+            TestCase {
+                input: String::from("true < false"),
+                expected: TestCaseResult::Error(String::from("couldn't compare two objects, got wrong operator OpLessThan")),
+            },
+            TestCase {
+                input: String::from("(1 < 2) > (2 < 3)"),
+                expected: TestCaseResult::Error(String::from("couldn't compare two objects, got wrong operator OpGreaterThan")),
+            },
         ];
 
         run_vm_tests(expected);
     }
 
     #[test]
-    fn conditionals_test() {
+    fn logical_operators_test() {
+        let expected = vec![
+            ("true && true", true),
+            ("true && false", false),
+            ("false && true", false),
+            ("false || false", false),
+            ("false || true", true),
+            ("true || false", true),
+            ("1 && \"a\"", true),
+            ("if (false) { 1 } || 0", true),
+            ("1 < 2 && 2 < 3 || false", true),
+            ("let i = 0; let f = fn() { i = i + 1; true }; false && f(); true || f(); i == 0", true),
+            ("let i = 0; let f = fn() { i = i + 1; true }; true && f(); false || f(); i == 2", true),
+        ];
+
+        run_vm_tests(
+            expected
+                .into_iter()
+                .map(|(input, expected)| TestCase { input: String::from(input), expected: TestCaseResult::Boolean(expected) })
+                .collect(),
+        );
+    }
+
+    #[test]
+    fn float_arithmetic_test() {
         let expected = vec![
             TestCase {
-                input: String::from("if (true) { 10 }"),
-                expected: TestCaseResult::Integer(10),
-            },
-            TestCase {
-                input: String::from("if (true) { 10 } else { 20 }"),
-                expected: TestCaseResult::Integer(10),
-            },
-            TestCase {
-                input: String::from("if (false) { 10 } else { 20 } "),
-                expected: TestCaseResult::Integer(20),
+                input: String::from("2.75"),
+                expected: TestCaseResult::Float(2.75),
             },
             TestCase {
-                input: String::from("if (1) { 10 }"),
-                expected: TestCaseResult::Integer(10),
+                input: String::from("1.0 + 2"),
+                expected: TestCaseResult::Float(3.0),
             },
             TestCase {
-                input: String::from("if (1 < 2) { 10 }"),
-                expected: TestCaseResult::Integer(10),
+                input: String::from("10.0 / 4.0"),
+                expected: TestCaseResult::Float(2.5),
             },
             TestCase {
-                input: String::from("if (1 < 2) { 10 } else { 20 }"),
-                expected: TestCaseResult::Integer(10),
+                input: String::from("-1.5 - 1"),
+                expected: TestCaseResult::Float(-2.5),
             },
             TestCase {
-                input: String::from("if (1 > 2) { 10 } else { 20 }"),
-                expected: TestCaseResult::Integer(20),
+                input: String::from("7.5 % 2"),
+                expected: TestCaseResult::Float(1.5),
             },
             TestCase {
-                input: String::from("if (1 > 2) { 10 }"),
-                expected: TestCaseResult::Null,
+                input: String::from("1.5 < 2"),
+                expected: TestCaseResult::Boolean(true),
             },
             TestCase {
-                input: String::from("if (false) { 10 }"),
-                expected: TestCaseResult::Null,
+                input: String::from("2 == 2.0"),
+                expected: TestCaseResult::Boolean(true),
             },
             TestCase {
-                input: String::from("if ((if (false) { 10 })) { 10 } else { 20 }"),
-                expected: TestCaseResult::Integer(20),
+                input: String::from("1.0 / 0"),
+                expected: TestCaseResult::Error(String::from("division by zero")),
             },
         ];
 
@@ -873,19 +1222,23 @@ mod tests {
     }
 
     #[test]
-    fn global_let_statement_test() {
+    fn modulo_test() {
         let expected = vec![
             TestCase {
-                input: String::from("let one = 1; one"),
+                input: String::from("10 % 3"),
                 expected: TestCaseResult::Integer(1),
             },
             TestCase {
-                input: String::from("let one = 1; let two = 2; one + two"),
-                expected: TestCaseResult::Integer(3),
+                input: String::from("9 % 3"),
+                expected: TestCaseResult::Integer(0),
             },
             TestCase {
-                input: String::from("let one = 1; let two = one + one; one + two"),
-                expected: TestCaseResult::Integer(3),
+                input: String::from("2 + 7 % 4 * 2"),
+                expected: TestCaseResult::Integer(8),
+            },
+            TestCase {
+                input: String::from("7 % 0"),
+                expected: TestCaseResult::Error(String::from("division by zero")),
             },
         ];
 
@@ -893,19 +1246,19 @@ mod tests {
     }
 
     #[test]
-    fn string_expression_test() {
+    fn division_by_zero_test() {
         let expected = vec![
             TestCase {
-                input: String::from(r#""monkey""#),
-                expected: TestCaseResult::String(String::from("monkey")),
+                input: String::from("5 / 0"),
+                expected: TestCaseResult::Error(String::from("division by zero")),
             },
             TestCase {
-                input: String::from(r#""mon" + "key""#),
-                expected: TestCaseResult::String(String::from("monkey")),
+                input: String::from("let zero = 1 - 1; 10 / zero"),
+                expected: TestCaseResult::Error(String::from("division by zero")),
             },
             TestCase {
-                input: String::from(r#""mon" + "key" + "banana""#),
-                expected: TestCaseResult::String(String::from("monkeybanana")),
+                input: String::from("fn(x) { x / 0 }(1)"),
+                expected: TestCaseResult::Error(String::from("division by zero")),
             },
         ];
 
@@ -913,27 +1266,27 @@ mod tests {
     }
 
     #[test]
-    fn array_literl_test() {
+    fn integer_overflow_test() {
         let expected = vec![
             TestCase {
-                input: String::from("[]"),
-                expected: TestCaseResult::Array(vec![]),
+                input: String::from("9223372036854775807 * 2"),
+                expected: TestCaseResult::Error(String::from("integer overflow in multiplication")),
             },
             TestCase {
-                input: String::from("[1, 2, 3]"),
-                expected: TestCaseResult::Array(vec![
-                    TestCaseResult::Integer(1),
-                    TestCaseResult::Integer(2),
-                    TestCaseResult::Integer(3),
-                ]),
+                input: String::from("9223372036854775807 + 1"),
+                expected: TestCaseResult::Error(String::from("integer overflow in addition")),
             },
             TestCase {
-                input: String::from("[1 + 2, 3 * 4, 5 + 6]"),
-                expected: TestCaseResult::Array(vec![
-                    TestCaseResult::Integer(3),
-                    TestCaseResult::Integer(12),
-                    TestCaseResult::Integer(11),
-                ]),
+                input: String::from("let min = -9223372036854775807 - 1; min - 1"),
+                expected: TestCaseResult::Error(String::from("integer overflow in subtraction")),
+            },
+            TestCase {
+                input: String::from("let min = -9223372036854775807 - 1; min / -1"),
+                expected: TestCaseResult::Error(String::from("integer overflow in division")),
+            },
+            TestCase {
+                input: String::from("let min = -9223372036854775807 - 1; -min"),
+                expected: TestCaseResult::Error(String::from("integer overflow in negation")),
             },
         ];
 
@@ -941,38 +1294,319 @@ mod tests {
     }
 
     #[test]
-    fn hash_literal_test() {
+    fn conditionals_test() {
         let expected = vec![
             TestCase {
-                input: String::from("{}"),
-                expected: TestCaseResult::Hash(HashMap::new()),
+                input: String::from("if (true) { 10 }"),
+                expected: TestCaseResult::Integer(10),
             },
             TestCase {
-                input: String::from("{ 1: 2, 2: 3 }"),
-                expected: TestCaseResult::Hash(HashMap::from([
-                    (
-                        Object::Integer(Integer { value: 1 }),
-                        TestCaseResult::Integer(2),
-                    ),
-                    (
-                        Object::Integer(Integer { value: 2 }),
-                        TestCaseResult::Integer(3),
-                    ),
-                ])),
+                input: String::from("1 > 2 ? 10 : 20"),
+                expected: TestCaseResult::Integer(20),
             },
             TestCase {
-                input: String::from("{1 + 1: 2 * 2, 3 + 3: 4 * 4}"),
-                expected: TestCaseResult::Hash(HashMap::from([
-                    (
-                        Object::Integer(Integer { value: 2 }),
-                        TestCaseResult::Integer(4),
-                    ),
-                    (
-                        Object::Integer(Integer { value: 6 }),
+                input: String::from("1 < 2 ? 10 : 20"),
+                expected: TestCaseResult::Integer(10),
+            },
+            TestCase {
+                input: String::from("if (true) { 10 } else { 20 }"),
+                expected: TestCaseResult::Integer(10),
+            },
+            TestCase {
+                input: String::from("if (false) { 10 } else { 20 } "),
+                expected: TestCaseResult::Integer(20),
+            },
+            TestCase {
+                input: String::from("if (1) { 10 }"),
+                expected: TestCaseResult::Integer(10),
+            },
+            TestCase {
+                input: String::from("if (1 < 2) { 10 }"),
+                expected: TestCaseResult::Integer(10),
+            },
+            TestCase {
+                input: String::from("if (1 < 2) { 10 } else { 20 }"),
+                expected: TestCaseResult::Integer(10),
+            },
+            TestCase {
+                input: String::from("if (1 > 2) { 10 } else { 20 }"),
+                expected: TestCaseResult::Integer(20),
+            },
+            TestCase {
+                input: String::from("if (1 > 2) { 10 }"),
+                expected: TestCaseResult::Null,
+            },
+            TestCase {
+                input: String::from("if (false) { 10 }"),
+                expected: TestCaseResult::Null,
+            },
+            TestCase {
+                input: String::from("if ((if (false) { 10 })) { 10 } else { 20 }"),
+                expected: TestCaseResult::Integer(20),
+            },
+            TestCase {
+                input: String::from("if (true) { if (false) { 1 } else { 2 } } else { 3 }"),
+                expected: TestCaseResult::Integer(2),
+            },
+            TestCase {
+                input: String::from("if (1 > 2) { 10 } else { if (2 > 1) { 20 } else { 30 } }"),
+                expected: TestCaseResult::Integer(20),
+            },
+            TestCase {
+                input: String::from("let x = if (true) { if (true) { 5 } }; x * 2"),
+                expected: TestCaseResult::Integer(10),
+            },
+            TestCase {
+                input: String::from("if (true) { }"),
+                expected: TestCaseResult::Null,
+            },
+            TestCase {
+                input: String::from("if (false) { 10 } else { }"),
+                expected: TestCaseResult::Null,
+            },
+            TestCase {
+                input: String::from("if (true) { let a = 1; }"),
+                expected: TestCaseResult::Null,
+            },
+        ];
+
+        run_vm_tests(expected);
+    }
+
+    #[test]
+    fn while_loop_test() {
+        let expected = vec![
+            TestCase {
+                input: String::from("let i = 0; while (i < 3) { let i = i + 1; } i;"),
+                expected: TestCaseResult::Integer(3),
+            },
+            TestCase {
+                input: String::from("let sum = fn(n) { let i = 0; let acc = 0; while (i < n) { let i = i + 1; let acc = acc + i; } acc }; sum(4)"),
+                expected: TestCaseResult::Integer(10),
+            },
+            TestCase {
+                input: String::from("let f = fn() { let i = 0; while (true) { let i = i + 1; if (i == 5) { return i * 10; } } }; f()"),
+                expected: TestCaseResult::Integer(50),
+            },
+            TestCase {
+                input: String::from("let f = fn() { while (false) { 1 } }; f()"),
+                expected: TestCaseResult::Null,
+            },
+
+            TestCase {
+                input: String::from("1; while (false) { 2 }"),
+                expected: TestCaseResult::Null,
+            },
+            TestCase {
+                input: String::from("let i = 0; while (i < 3) { i = i + 1; }"),
+                expected: TestCaseResult::Null,
+            },
+        ];
+
+        run_vm_tests(expected);
+    }
+
+    #[test]
+    fn assign_expression_test() {
+        let expected = vec![
+            TestCase {
+                input: String::from("let x = 1; x = 2; x;"),
+                expected: TestCaseResult::Integer(2),
+            },
+            TestCase {
+                input: String::from("let x = 1; let y = 2; x = y = 3; x + y"),
+                expected: TestCaseResult::Integer(6),
+            },
+            TestCase {
+                input: String::from("let x = 1; let set = fn() { x = 5; }; set(); x"),
+                expected: TestCaseResult::Integer(5),
+            },
+            TestCase {
+                input: String::from("let f = fn(a) { let b = 1; a = a + b; b = a * 2; b }; f(2)"),
+                expected: TestCaseResult::Integer(6),
+            },
+            TestCase {
+                input: String::from("let i = 0; while (i < 5) { i = i + 1; } i"),
+                expected: TestCaseResult::Integer(5),
+            },
+        ];
+
+        run_vm_tests(expected);
+    }
+
+    #[test]
+    fn global_let_statement_test() {
+        let expected = vec![
+            TestCase {
+                input: String::from("let one = 1; one"),
+                expected: TestCaseResult::Integer(1),
+            },
+            TestCase {
+                input: String::from("let one = 1; let two = 2; one + two"),
+                expected: TestCaseResult::Integer(3),
+            },
+            TestCase {
+                input: String::from("let one = 1; let two = one + one; one + two"),
+                expected: TestCaseResult::Integer(3),
+            },
+        ];
+
+        run_vm_tests(expected);
+    }
+
+    #[test]
+    fn global_store_test() {
+        let symbol_table = SymbolTable::new();
+
+        let mut compiler = Compiler::new_with_state(symbol_table.clone(), vec![]);
+        compiler.compile(Parser::new(Lexer::new(String::from("let one = 1;"))).parse_program().unwrap()).unwrap();
+        let byte_code = compiler.byte_code().unwrap();
+        let constants = byte_code.constants.clone();
+
+        let mut vm = Vm::new_with_global_store(byte_code, empty_globals());
+        assert!(vm.run().is_ok());
+
+        let mut compiler = Compiler::new_with_state(symbol_table, constants);
+        compiler.compile(Parser::new(Lexer::new(String::from("let two = one + 1; one + two"))).parse_program().unwrap()).unwrap();
+
+        let mut vm = Vm::new_with_global_store(compiler.byte_code().unwrap(), vm.globals);
+        assert!(vm.run().is_ok());
+        TestCaseResult::Integer(3).test(&vm.last_popped_stack_elem().unwrap());
+    }
+
+    #[test]
+    fn string_expression_test() {
+        let expected = vec![
+            TestCase {
+                input: String::from(r#""monkey""#),
+                expected: TestCaseResult::String(String::from("monkey")),
+            },
+            TestCase {
+                input: String::from(r#""mon" + "key""#),
+                expected: TestCaseResult::String(String::from("monkey")),
+            },
+            TestCase {
+                input: String::from(r#""mon" + "key" + "banana""#),
+                expected: TestCaseResult::String(String::from("monkeybanana")),
+            },
+            TestCase {
+                input: String::from(r#""mon" + "key" == "monkey""#),
+                expected: TestCaseResult::Boolean(true),
+            },
+            TestCase {
+                input: String::from(r#""mon" != "key""#),
+                expected: TestCaseResult::Boolean(true),
+            },
+            TestCase {
+                input: String::from(r#""abc" == "abc""#),
+                expected: TestCaseResult::Boolean(true),
+            },
+            TestCase {
+                input: String::from(r#""a" < "b""#),
+                expected: TestCaseResult::Boolean(true),
+            },
+            TestCase {
+                input: String::from(r#""b" > "a""#),
+                expected: TestCaseResult::Boolean(true),
+            },
+            TestCase {
+                input: String::from(r#""a" > "b""#),
+                expected: TestCaseResult::Boolean(false),
+            },
+            TestCase {
+                input: String::from(r#""ab" < "b""#),
+                expected: TestCaseResult::Boolean(true),
+            },
+            TestCase {
+                input: String::from(r#""mon" - "key""#),
+                expected: TestCaseResult::Error(String::from("couldn't execute binary operation for strings, OpAdd expected, but got OpSub")),
+            },
+            TestCase {
+                input: String::from(r#""mon" * 2"#),
+                expected: TestCaseResult::Error(String::from("couldn't execute binary operation: got \"mon\" and \"2\"")),
+            },
+        ];
+
+        run_vm_tests(expected);
+    }
+
+    #[test]
+    fn array_literl_test() {
+        let expected = vec![
+            TestCase {
+                input: String::from("[]"),
+                expected: TestCaseResult::Array(vec![]),
+            },
+            TestCase {
+                input: String::from("[1, 2, 3]"),
+                expected: TestCaseResult::Array(vec![
+                    TestCaseResult::Integer(1),
+                    TestCaseResult::Integer(2),
+                    TestCaseResult::Integer(3),
+                ]),
+            },
+            TestCase {
+                input: String::from("[1 + 2, 3 * 4, 5 + 6]"),
+                expected: TestCaseResult::Array(vec![
+                    TestCaseResult::Integer(3),
+                    TestCaseResult::Integer(12),
+                    TestCaseResult::Integer(11),
+                ]),
+            },
+        ];
+
+        run_vm_tests(expected);
+    }
+
+    #[test]
+    fn hash_literal_test() {
+        let expected = vec![
+            TestCase {
+                input: String::from("{}"),
+                expected: TestCaseResult::Hash(HashMap::new()),
+            },
+            TestCase {
+                input: String::from("{ 1: 2, 2: 3 }"),
+                expected: TestCaseResult::Hash(HashMap::from([
+                    (
+                        Object::Integer(Integer { value: 1 }),
+                        TestCaseResult::Integer(2),
+                    ),
+                    (
+                        Object::Integer(Integer { value: 2 }),
+                        TestCaseResult::Integer(3),
+                    ),
+                ])),
+            },
+            TestCase {
+                input: String::from("{1 + 1: 2 * 2, 3 + 3: 4 * 4}"),
+                expected: TestCaseResult::Hash(HashMap::from([
+                    (
+                        Object::Integer(Integer { value: 2 }),
+                        TestCaseResult::Integer(4),
+                    ),
+                    (
+                        Object::Integer(Integer { value: 6 }),
                         TestCaseResult::Integer(16),
                     ),
                 ])),
             },
+            TestCase {
+                input: String::from("{[1]: 2}"),
+                expected: TestCaseResult::Error(String::from("unusable as hash key: Array")),
+            },
+            TestCase {
+                input: String::from("{\"one\": 1, {}: 2}"),
+                expected: TestCaseResult::Error(String::from("unusable as hash key: HashTable")),
+            },
+            TestCase {
+                input: String::from("{fn(x) { x }: 1}"),
+                expected: TestCaseResult::Error(String::from("unusable as hash key: Function")),
+            },
+            TestCase {
+                input: String::from("{true: 1, \"a\": 2}[fn() { 1 }]"),
+                expected: TestCaseResult::Error(String::from("unusable as hash key: Function")),
+            },
         ];
 
         run_vm_tests(expected);
@@ -1003,8 +1637,40 @@ mod tests {
             },
             TestCase {
                 input: String::from("[1][-1]"),
+                expected: TestCaseResult::Integer(1),
+            },
+            TestCase {
+                input: String::from("[1, 2, 3][-1]"),
+                expected: TestCaseResult::Integer(3),
+            },
+            TestCase {
+                input: String::from("[1, 2, 3][-3]"),
+                expected: TestCaseResult::Integer(1),
+            },
+            TestCase {
+                input: String::from("[1, 2, 3][-4]"),
+                expected: TestCaseResult::Null,
+            },
+            TestCase {
+                input: String::from("[1, 2, 3][3]"),
+                expected: TestCaseResult::Null,
+            },
+            TestCase {
+                input: String::from("\"hello\"[1]"),
+                expected: TestCaseResult::String(String::from("e")),
+            },
+            TestCase {
+                input: String::from("\"héllo\"[1]"),
+                expected: TestCaseResult::String(String::from("é")),
+            },
+            TestCase {
+                input: String::from("\"hello\"[-6]"),
                 expected: TestCaseResult::Null,
             },
+            TestCase {
+                input: String::from("chars(\"ab\")"),
+                expected: TestCaseResult::Array(vec![TestCaseResult::String(String::from("a")), TestCaseResult::String(String::from("b"))]),
+            },
             TestCase {
                 input: String::from("{1: 1, 2: 2}[1]"),
                 expected: TestCaseResult::Integer(1),
@@ -1021,6 +1687,58 @@ mod tests {
                 input: String::from("{}[0]"),
                 expected: TestCaseResult::Null,
             },
+            TestCase {
+                input: String::from("{1: 2}[[1]]"),
+                expected: TestCaseResult::Error(String::from("unusable as hash key: Array")),
+            },
+            TestCase {
+                input: String::from("1[0]"),
+                expected: TestCaseResult::Error(String::from("couldn't execute index expression, array or string with int index or hash table expected, but got type \"1\" and idx \"0\"")),
+            },
+            TestCase {
+                input: String::from("[1][true]"),
+                expected: TestCaseResult::Error(String::from("couldn't execute index expression, array or string with int index or hash table expected, but got type \"[1]\" and idx \"true\"")),
+            },
+        ];
+
+        run_vm_tests(expected);
+    }
+
+    #[test]
+    fn return_statement_test() {
+        let expected = vec![
+            TestCase {
+                input: String::from("return 10; 9;"),
+                expected: TestCaseResult::Integer(10),
+            },
+            TestCase {
+                input: String::from("9; return 2 * 5; 9;"),
+                expected: TestCaseResult::Integer(10),
+            },
+            TestCase {
+                input: String::from("if (10 > 1) { if (10 > 1) { return 10; } return 1; }"),
+                expected: TestCaseResult::Integer(10),
+            },
+            TestCase {
+                input: String::from("if (false) { 1 } else { return 3; 4 }"),
+                expected: TestCaseResult::Integer(3),
+            },
+            TestCase {
+                input: String::from("
+let f = fn(x) {
+    if (x > 1) {
+        if (x > 5) { return 10; } else { return 5; }
+    }
+    return 1;
+};
+f(7) + f(3) + f(0)
+"),
+                expected: TestCaseResult::Integer(16),
+            },
+            TestCase {
+                input: String::from("let f = fn() { return 1; 2 }; f(); 5"),
+                expected: TestCaseResult::Integer(5),
+            },
         ];
 
         run_vm_tests(expected);
@@ -1083,6 +1801,17 @@ noReturnTwo();"
 ),
                 expected: TestCaseResult::Null
             },
+            TestCase {
+                input: String::from("
+let outer = fn() {
+    let a = 1;
+    let inner = fn() { let b = 2; b * 10 };
+    inner() + a
+};
+outer() + outer();"
+),
+                expected: TestCaseResult::Integer(42)
+            },
         ];
 
         run_vm_tests(expected);
@@ -1152,6 +1881,14 @@ minusOne() + minusTwo();
 "),
                 expected: TestCaseResult::Integer(97),
             },
+            TestCase {
+                input: String::from("let a = 1; let f = fn() { let a = 2; a }; f() + a"),
+                expected: TestCaseResult::Integer(3),
+            },
+            TestCase {
+                input: String::from("fn(a) { let a = a + 1; a }(1)"),
+                expected: TestCaseResult::Integer(2),
+            },
         ];
 
         run_vm_tests(expected);
@@ -1261,20 +1998,63 @@ outer() + globalNum;
             TestCase { input: String::from(r#"len("")"#), expected: TestCaseResult::Integer(0), },
             TestCase { input: String::from(r#"len("four")"#), expected: TestCaseResult::Integer(4) },
             TestCase { input: String::from(r#"len("hello world")"#), expected: TestCaseResult::Integer(11) },
-            TestCase { input: String::from(r#"len(1)"#), expected: TestCaseResult::Error(String::from("argument to len function is not supported, String expected, but got \"1\"")) }, 
+            TestCase { input: String::from(r#"len(1)"#), expected: TestCaseResult::Error(String::from("argument to len function is not supported, String or Array expected, but got \"1\"")) }, 
             TestCase { input: String::from(r#"len("one", "two")"#), expected: TestCaseResult::Error(String::from("wrong number of arguments for len function, 1 argument expected, but got 2")) }, 
             TestCase { input: String::from(r#"len([])"#), expected: TestCaseResult::Integer(0)},
             TestCase { input: String::from(r#"puts("hello", "world!")"#), expected: TestCaseResult::Null},
             TestCase { input: String::from(r#"first([1, 2, 3])"#), expected: TestCaseResult::Integer(1)},
             TestCase { input: String::from(r#"first([])"#), expected: TestCaseResult::Null},
-            TestCase { input: String::from(r#"first(1)"#), expected: TestCaseResult::Error(String::from("argument to first function is not supported, Array expected, but got \"1\"")) }, 
+            TestCase { input: String::from(r#"first(1)"#), expected: TestCaseResult::Error(String::from("argument to first function is not supported, String or Array expected, but got \"1\"")) }, 
             TestCase { input: String::from(r#"last([1, 2, 3])"#), expected: TestCaseResult::Integer(3)},
             TestCase { input: String::from(r#"last([])"#), expected: TestCaseResult::Null},
-            TestCase { input: String::from(r#"last(1)"#), expected: TestCaseResult::Error(String::from("argument to last function is not supported, Array expected, but got \"1\"")) }, 
+            TestCase { input: String::from(r#"last(1)"#), expected: TestCaseResult::Error(String::from("argument to last function is not supported, String or Array expected, but got \"1\"")) }, 
             TestCase { input: String::from(r#"rest([1, 2, 3])"#), expected: TestCaseResult::Array(vec![ TestCaseResult::Integer(2), TestCaseResult::Integer(3)])},
             TestCase { input: String::from(r#"rest([])"#), expected: TestCaseResult::Null},
+            TestCase { input: String::from(r#"first("héllo")"#), expected: TestCaseResult::String(String::from("h"))},
+            TestCase { input: String::from(r#"last("héllo")"#), expected: TestCaseResult::String(String::from("o"))},
+            TestCase { input: String::from(r#"rest("héllo")"#), expected: TestCaseResult::String(String::from("éllo"))},
+            TestCase { input: String::from(r#"first(rest("日本"))"#), expected: TestCaseResult::String(String::from("本"))},
+            TestCase { input: String::from(r#"rest("a")"#), expected: TestCaseResult::String(String::new())},
+            TestCase { input: String::from(r#"first("")"#), expected: TestCaseResult::Null},
+            TestCase { input: String::from(r#"last("")"#), expected: TestCaseResult::Null},
+            TestCase { input: String::from(r#"rest("")"#), expected: TestCaseResult::Null},
+            TestCase { input: String::from(r#"range(0, 6, 2)"#), expected: TestCaseResult::Array(vec![TestCaseResult::Integer(0), TestCaseResult::Integer(2), TestCaseResult::Integer(4)])},
+            TestCase { input: String::from(r#"len(range(2, 5))"#), expected: TestCaseResult::Integer(3)},
+            TestCase { input: String::from(r#"range(3, 0)"#), expected: TestCaseResult::Array(vec![])},
+            TestCase { input: String::from(r#"range(1, 2, 0)"#), expected: TestCaseResult::Error(String::from("argument to range function is not supported, step can't be zero")) },
+            TestCase { input: String::from(r#"range(9223372036854775807, -9223372036854775807, -1)"#), expected: TestCaseResult::Error(String::from("argument to range function is not supported, range is longer than 10000000 elements")) },
+            TestCase { input: String::from(r#"len(range(0, 20000000, 2))"#), expected: TestCaseResult::Integer(10000000)},
+            TestCase { input: String::from(r#"abs(-5)"#), expected: TestCaseResult::Integer(5)},
+            TestCase { input: String::from(r#"min(3, 1, 2)"#), expected: TestCaseResult::Integer(1)},
+            TestCase { input: String::from(r#"max(3, 1, 2)"#), expected: TestCaseResult::Integer(3)},
+            TestCase { input: String::from(r#"assert_eq(max(1, 2), 2)"#), expected: TestCaseResult::Null},
+            TestCase { input: String::from(r#"assert(1 > 2)"#), expected: TestCaseResult::Error(String::from("assertion failed")) },
+            TestCase { input: String::from(r#"contains([1, 2], 2)"#), expected: TestCaseResult::Boolean(true)},
+            TestCase { input: String::from(r#"index_of([1, 2], 3)"#), expected: TestCaseResult::Integer(-1)},
+            TestCase { input: String::from(r#"slice([1, 2, 3, 4], 1, 3)"#), expected: TestCaseResult::Array(vec![TestCaseResult::Integer(2), TestCaseResult::Integer(3)])},
+            TestCase { input: String::from(r#"slice("hello", 1, 4)"#), expected: TestCaseResult::String(String::from("ell"))},
+            TestCase { input: String::from(r#"max(true, false)"#), expected: TestCaseResult::Error(String::from("arguments to max function are not supported, couldn't compare \"true\" and \"false\"")) },
+            TestCase { input: String::from(r#"rest(1)"#), expected: TestCaseResult::Error(String::from("argument to rest function is not supported, String or Array expected, but got \"1\"")) },
             TestCase { input: String::from(r#"push([], 1)"#), expected: TestCaseResult::Array(vec![TestCaseResult::Integer(1)])},
             TestCase { input: String::from(r#"push(1, 1)"#), expected: TestCaseResult::Error(String::from("argument to push function is not supported, Array expected, but got \"1\"")) }, 
+            TestCase { input: String::from(r#"push([])"#), expected: TestCaseResult::Error(String::from("wrong number of arguments for push function, 2 arguments expected, but got 1")) },
+            TestCase { input: String::from(r#"len([1, 2, 3])"#), expected: TestCaseResult::Integer(3)},
+            TestCase { input: String::from(r#"let f = fn() { len }; f()([1, 2])"#), expected: TestCaseResult::Integer(2)},
+            TestCase { input: String::from(r#"let len = fn(x) { 42 }; len([1])"#), expected: TestCaseResult::Integer(42)},
+            TestCase { input: String::from(r#"type(5)"#), expected: TestCaseResult::String(String::from("Integer"))},
+            TestCase { input: String::from(r#"type("a")"#), expected: TestCaseResult::String(String::from("String"))},
+            TestCase { input: String::from(r#"type([1])"#), expected: TestCaseResult::String(String::from("Array"))},
+            TestCase { input: String::from(r#"type(fn(x) { x })"#), expected: TestCaseResult::String(String::from("Function"))},
+            TestCase { input: String::from(r#"type(len)"#), expected: TestCaseResult::String(String::from("Builtin"))},
+            TestCase { input: String::from(r#"map([1, 2, 3], fn(x) { x * 2 })"#), expected: TestCaseResult::Array(vec![TestCaseResult::Integer(2), TestCaseResult::Integer(4), TestCaseResult::Integer(6)])},
+            TestCase { input: String::from(r#"filter([1, 2, 3, 4], fn(x) { x > 2 })"#), expected: TestCaseResult::Array(vec![TestCaseResult::Integer(3), TestCaseResult::Integer(4)])},
+            TestCase { input: String::from(r#"let n = 1; let f = fn() { reduce([1, 2, 3], n, fn(acc, x) { let y = acc + x; y }) }; f() * 2"#), expected: TestCaseResult::Integer(14)},
+            TestCase { input: String::from(r#"map([[1], [2, 3]], len)"#), expected: TestCaseResult::Array(vec![TestCaseResult::Integer(1), TestCaseResult::Integer(2)])},
+            TestCase { input: String::from(r#"map([1], fn(x, y) { x })"#), expected: TestCaseResult::Error(String::from("wrong number of arguments: want=2, got=1")) },
+            TestCase { input: String::from(r#"sort([3, 1, 2])"#), expected: TestCaseResult::Array(vec![TestCaseResult::Integer(1), TestCaseResult::Integer(2), TestCaseResult::Integer(3)])},
+            TestCase { input: String::from(r#"sort(["b", "a"])"#), expected: TestCaseResult::Array(vec![TestCaseResult::String(String::from("a")), TestCaseResult::String(String::from("b"))])},
+            TestCase { input: String::from(r#"sort([1, "a"])"#), expected: TestCaseResult::Error(String::from("argument to sort function is not supported, couldn't compare \"1\" and \"a\"")) },
+            TestCase { input: String::from(r#"type(1, 2)"#), expected: TestCaseResult::Error(String::from("wrong number of arguments for type function, 1 argument expected, but got 2")) },
         ];
 
         run_vm_tests(expected);
@@ -1359,6 +2139,16 @@ closure();
 "
             ),
                 expected: TestCaseResult::Integer(99),
+            },
+            TestCase {
+                input: String::from("
+let newAdder = fn(a) {
+    fn(b) { a + b };
+};
+newAdder(1)(2);
+"
+            ),
+                expected: TestCaseResult::Integer(3),
             }
         ];
 
@@ -1413,6 +2203,27 @@ let wrapper = fn() {
 wrapper();
 "),
                 expected: TestCaseResult::Integer(0)
+            },
+            TestCase {
+                input: String::from("let fib = fn(x) { if (x < 2) { x } else { fib(x-1) + fib(x-2) } }; fib(10);"),
+                expected: TestCaseResult::Integer(55)
+            },
+            TestCase {
+                input: String::from("
+let isEven = fn(n) { if (n == 0) { true } else { isOdd(n - 1) } };
+let isOdd = fn(n) { if (n == 0) { false } else { isEven(n - 1) } };
+[isEven(10), isOdd(10), isEven(7), isOdd(7)];
+"),
+                expected: TestCaseResult::Array(vec![
+                    TestCaseResult::Boolean(true),
+                    TestCaseResult::Boolean(false),
+                    TestCaseResult::Boolean(false),
+                    TestCaseResult::Boolean(true),
+                ])
+            },
+            TestCase {
+                input: String::from("let x = 1; let f = fn() { let x = x + 1; x }; f();"),
+                expected: TestCaseResult::Integer(2)
             }
         ];
 
@@ -1444,4 +2255,372 @@ fibonacci(15);
 
         run_vm_tests(expected);
     }
+
+    fn compile_input(input: &str) -> ByteCode {
+        let lexer = Lexer::new(input.to_string());
+        let mut parser = Parser::new(lexer);
+        let program = parser.parse_program().unwrap();
+
+        let mut compiler = Compiler::new();
+        compiler.compile(program).unwrap();
+
+        compiler.byte_code().unwrap()
+    }
+
+    #[test]
+    fn stack_limit_test() {
+        let input = format!("len([{}])", vec!["1"; STACK_SIZE + 1].join(", "));
+
+        let mut vm = Vm::new(compile_input(&input));
+        assert_eq!(vm.run(), Err(String::from("stack overflow").into()));
+
+        let mut vm = Vm::new(compile_input(&input)).with_stack_limit(STACK_SIZE * 2);
+        assert!(vm.run().is_ok());
+        TestCaseResult::Integer(STACK_SIZE as i64 + 1).test(&vm.last_popped_stack_elem().unwrap());
+
+        let mut vm = Vm::new(compile_input("[1, 2, 3]")).with_stack_limit(2);
+        assert_eq!(vm.run(), Err(String::from("stack overflow").into()));
+
+        let mut vm = Vm::new(compile_input("let f = fn() { let a = 1; let b = 2; a + b }; f()")).with_stack_limit(2);
+        assert_eq!(vm.run(), Err(String::from("stack overflow").into()));
+    }
+
+    #[test]
+    fn max_frames_test() {
+        let mut vm = Vm::new(compile_input("let f = fn() { f() }; f()"));
+        assert_eq!(vm.run(), Err(String::from("maximum call depth exceeded").into()));
+
+        let mut vm = Vm::new(compile_input("let f = fn(x) { map([x], f) }; f(0)"));
+        assert_eq!(vm.run(), Err(String::from("maximum call depth exceeded").into()));
+
+        let input = "let f = fn(x) { if (x == 10) { x } else { f(x + 1) } }; f(0)";
+
+        let mut vm = Vm::new(compile_input(input)).with_max_frames(5);
+        assert_eq!(vm.run(), Err(String::from("maximum call depth exceeded").into()));
+
+        let mut vm = Vm::new(compile_input(input)).with_max_frames(12);
+        assert!(vm.run().is_ok());
+        TestCaseResult::Integer(10).test(&vm.last_popped_stack_elem().unwrap());
+
+        let mut vm = Vm::new(compile_input("let f = fn(x) { f(x + 1) }; f(0)"));
+        assert_eq!(vm.run(), Err(String::from("maximum call depth exceeded").into()));
+
+        let mut vm = Vm::new(compile_input("let f = fn(x, y) { let a = x + y; let b = a - y; f(b, y) }; f(0, 1)"));
+        assert_eq!(vm.run(), Err(String::from("maximum call depth exceeded").into()));
+
+        let input = "let f = fn(x) { if (x == 2000) { x } else { f(x + 1) } }; f(0)";
+        let mut vm = Vm::new(compile_input(input)).with_max_frames(MAX_FRAMES * 4).with_stack_limit(STACK_SIZE * 4);
+        assert!(vm.run().is_ok());
+        TestCaseResult::Integer(2000).test(&vm.last_popped_stack_elem().unwrap());
+    }
+
+    #[test]
+    fn stack_top_test() {
+        let mut vm = Vm::new(compile_input(""));
+        assert!(vm.stack_top().is_none());
+
+        assert!(vm.run().is_ok());
+        assert!(vm.stack_top().is_none());
+    }
+
+    #[test]
+    fn closure_free_variables_stack_test() {
+        let input = "
+let f = fn(n) {
+    let g = fn() { n };
+    if (n == 0) { g() } else { f(n - 1) }
+};
+f(100);
+";
+
+        let mut vm = Vm::new(compile_input(input)).with_stack_limit(310);
+        assert!(vm.run().is_ok());
+        TestCaseResult::Integer(0).test(&vm.last_popped_stack_elem().unwrap());
+    }
+
+    #[test]
+    fn moved_stack_values_test() {
+        let expected = vec![
+            TestCase { input: String::from(r#"let s = "ab"; let arr = [s, s]; arr[0] + arr[1] + s"#), expected: TestCaseResult::String(String::from("ababab")) },
+            TestCase { input: String::from(r#"let a = [1, 2]; let h = {"a": a}; len(push(h["a"], 3)) + len(a) + len(h["a"])"#), expected: TestCaseResult::Integer(7) },
+            TestCase { input: String::from(r#"let f = fn(x) { fn() { x } }; let g = f("v"); g() + g()"#), expected: TestCaseResult::String(String::from("vv")) },
+            TestCase { input: String::from("let f = fn(a) { a[0] }; let a = [3]; f(a) + f(a) + a[0]"), expected: TestCaseResult::Integer(9) },
+            TestCase { input: String::from("first(rest(map([1, 2, 3], fn(x) { x * 2 })))"), expected: TestCaseResult::Integer(4) },
+        ];
+
+        run_vm_tests(expected);
+    }
+
+    #[test]
+    fn truncated_operands_test() {
+        let func = compile_input("fn() { 1 }").constants;
+        let truncated = vec![
+            make(OpCodeType::Closure, vec![0, 0])[..3].to_vec(),
+            vec![OpCodeType::GetLocal.into()],
+            vec![OpCodeType::Call.into()],
+            vec![OpCodeType::Constant.into(), 0],
+        ];
+
+        for instructions in truncated {
+            let mut vm = Vm::new(ByteCode { instructions: Instructions(instructions.clone()), constants: func.clone(), global_names: HashMap::new(), builtin_names: vec![] });
+
+            assert_eq!(
+                vm.run().map_err(|err| err.message().to_string()),
+                Err(String::from("couldn't read operand, unexpected end of instructions")),
+                "running {instructions:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn shared_constants_test() {
+        let large = "x".repeat(10_000);
+        let inputs = vec![
+            format!(r#""{large}""#),
+            format!(r#"let f = fn(a, b) {{ let c = b; a; c }}; f(1, "{large}")"#),
+        ];
+
+        for input in inputs {
+            let mut vm = Vm::new(compile_input(&input));
+            assert!(vm.run().is_ok());
+
+            let constant = vm.constants.iter().find(|constant| matches!(constant.as_ref(), Object::String(_))).unwrap();
+            let popped = vm.last_popped.as_ref().unwrap();
+
+            assert!(Rc::ptr_eq(popped, constant), "constant was copied while running {input}");
+            assert_eq!(Rc::strong_count(constant), 2);
+        }
+    }
+
+    #[test]
+    fn dispatch_matches_evaluator_test() {
+        let inputs = vec![
+            "1 + 2 * 3 - 4 / 2 % 3",
+            "1.5 * 2 - 0.5 / 4 + 7 % 2.5",
+            r#""a" + "b""#,
+            "[1 > 2, 2 > 1, 1 < 2, 1 == 1, 1 != 1, 1.5 > 1, 2 == 2.0]",
+            r#"[true == false, true != false, "a" == "a", "a" != "b"]"#,
+            "[-5, -2.5, !true, !5]",
+            "let f = fn(x) { if (x > 10) { x } else { f(x * 2) } }; f(1)",
+            r#"let a = [1, 2, 3]; let h = {"k": a}; h["k"][1] + a[-1]"#,
+            "let i = 0; let s = 0; while (i < 10) { s = s + i; i = i + 1; } s",
+            "1 / 0",
+            r#""a" - "b""#,
+            "true + 1",
+            "let f = fn() { 1; 2 }; let x = f();",
+            "let x = 5;",
+            "5; let y = 1;",
+            "1; while (false) { 2 }",
+            "let i = 0; while (i < 3) { i = i + 1; }",
+        ];
+
+        for input in inputs {
+            let mut vm = Vm::new(compile_input(input));
+            let vm_result = vm.run().map_err(|err| err.to_string()).and_then(|_| vm.last_popped_stack_elem().map_err(|err| err.to_string()));
+
+            let program = Parser::new(Lexer::new(input.to_string())).parse_program().unwrap();
+            let env = Rc::new(RefCell::new(Environment::new()));
+            let eval_result = eval(program, &env).map_err(|err| err.to_string());
+
+            match (vm_result, eval_result) {
+                (Ok(vm_obj), Ok(eval_obj)) => assert_eq!(vm_obj.to_string(), eval_obj.to_string(), "result of {input}"),
+                (Err(_), Err(_)) => (),
+                (vm_result, eval_result) => panic!("engines disagree on {input}: vm {vm_result:?}, eval {eval_result:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn last_popped_stack_elem_test() {
+        let expected = vec![
+            TestCase { input: String::from(""), expected: TestCaseResult::Null },
+            TestCase { input: String::from("5;"), expected: TestCaseResult::Integer(5) },
+            TestCase { input: String::from("1; 2;"), expected: TestCaseResult::Integer(2) },
+            TestCase { input: String::from("let x = 3; x;"), expected: TestCaseResult::Integer(3) },
+            TestCase { input: String::from("let f = fn() { 7 }; f();"), expected: TestCaseResult::Integer(7) },
+            TestCase { input: String::from("let x = 5;"), expected: TestCaseResult::Integer(5) },
+            TestCase { input: String::from("5; let y = 1;"), expected: TestCaseResult::Integer(1) },
+            TestCase { input: String::from("let f = fn() { 1; 2 }; let x = f();"), expected: TestCaseResult::Integer(2) },
+            TestCase { input: String::from("let f = fn() { 1; 2; }; f(); 3"), expected: TestCaseResult::Integer(3) },
+            TestCase { input: String::from("let f = fn() { 1; 2; }; f()"), expected: TestCaseResult::Integer(2) },
+        ];
+
+        run_vm_tests(expected);
+
+        let mut vm = Vm::new(compile_input(""));
+        assert!(vm.run().is_ok());
+
+        assert_eq!(vm.last_popped_stack_elem(), Ok(Object::Null(Null {})));
+    }
+
+    #[derive(Clone, Default)]
+    struct SharedOutput(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedOutput {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn puts_output_test() {
+        let output = SharedOutput::default();
+
+        let mut vm = Vm::new(compile_input(r#"puts("hello", 42)"#)).with_output(Box::new(output.clone()));
+        assert!(vm.run().is_ok());
+
+        TestCaseResult::Null.test(&vm.last_popped_stack_elem().unwrap());
+        assert_eq!(String::from_utf8(output.0.borrow().clone()).unwrap(), "hello\n42\n");
+    }
+
+    #[test]
+    fn trace_test() {
+        let output = SharedOutput::default();
+
+        let mut vm = Vm::new(compile_input("1 + 2")).with_trace(true).with_trace_output(Box::new(output.clone()));
+        assert!(vm.run().is_ok());
+
+        let expected = "0000 OpConstant 0 []
+0003 OpConstant 1 [1]
+0006 OpAdd [1, 2]
+0007 OpPop [3]
+";
+        assert_eq!(String::from_utf8(output.0.borrow().clone()).unwrap(), expected);
+
+        let output = SharedOutput::default();
+
+        let mut vm = Vm::new(compile_input("1 + 2")).with_trace_output(Box::new(output.clone()));
+        assert!(vm.run().is_ok());
+        assert!(output.0.borrow().is_empty());
+    }
+
+    #[test]
+    fn instruction_counts_test() {
+        let input = "
+let x = 0;
+while (x < 3) {
+    x = x + 1;
+}
+x";
+
+        let mut vm = Vm::new(compile_input(input)).with_instruction_counts(true);
+        assert!(vm.run().is_ok());
+        TestCaseResult::Integer(3).test(&vm.last_popped_stack_elem().unwrap());
+
+        let counts = vm.instruction_counts().unwrap();
+        assert_eq!(counts.get(&OpCodeType::Constant), Some(&8));
+        assert_eq!(counts.get(&OpCodeType::Add), Some(&3));
+        assert_eq!(counts.get(&OpCodeType::Mul), None);
+
+        let mut vm = Vm::new(compile_input(input));
+        assert!(vm.run().is_ok());
+        assert!(vm.instruction_counts().is_none());
+    }
+
+    fn double_builtin(args: Vec<Object>, _ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
+        match args.as_slice() {
+            [Object::Integer(int)] => Ok(Object::Integer(Integer { value: int.value * 2 })),
+            _ => Err(String::from("argument to double function is not supported, Integer expected")),
+        }
+    }
+
+    #[test]
+    fn register_builtin_test() {
+        let program = Parser::new(Lexer::new(String::from(r#"double(21) + len("ab")"#))).parse_program().unwrap();
+
+        let mut compiler = Compiler::new();
+        compiler.register_builtin("double");
+        compiler.compile(program).unwrap();
+
+        let mut vm = Vm::new(compiler.byte_code().unwrap());
+        vm.register_builtin("double", BuiltinFunction(double_builtin));
+        assert!(vm.run().is_ok());
+        TestCaseResult::Integer(44).test(&vm.last_popped_stack_elem().unwrap());
+
+        let program = Parser::new(Lexer::new(String::from("len(5)"))).parse_program().unwrap();
+
+        let mut compiler = Compiler::new();
+        compiler.register_builtin("len");
+        compiler.compile(program).unwrap();
+
+        let mut vm = Vm::new(compiler.byte_code().unwrap());
+        vm.register_builtin("len", BuiltinFunction(double_builtin));
+        assert!(vm.run().is_ok());
+        TestCaseResult::Integer(10).test(&vm.last_popped_stack_elem().unwrap());
+
+        let mut compiler = Compiler::new();
+        let program = Parser::new(Lexer::new(String::from("double(1)"))).parse_program().unwrap();
+        assert!(compiler.compile(program).is_err());
+
+        let program = Parser::new(Lexer::new(String::from("double(3) + square(3)"))).parse_program().unwrap();
+
+        let mut compiler = Compiler::new();
+        compiler.register_builtin("double");
+        compiler.register_builtin("square");
+        compiler.compile(program).unwrap();
+
+        let mut vm = Vm::new(compiler.byte_code().unwrap());
+        vm.register_builtin("square", BuiltinFunction(square_builtin));
+        vm.register_builtin("triple", BuiltinFunction(double_builtin));
+        vm.register_builtin("double", BuiltinFunction(double_builtin));
+        assert!(vm.run().is_ok());
+        TestCaseResult::Integer(15).test(&vm.last_popped_stack_elem().unwrap());
+
+        let program = Parser::new(Lexer::new(String::from("double(3)"))).parse_program().unwrap();
+
+        let mut compiler = Compiler::new();
+        compiler.register_builtin("double");
+        compiler.compile(program).unwrap();
+
+        let mut vm = Vm::new(compiler.byte_code().unwrap());
+        assert_eq!(vm.run().map_err(|err| err.message().to_string()), Err(String::from("couldn't find builtin function double")));
+    }
+
+    fn square_builtin(args: Vec<Object>, _ctx: &mut dyn BuiltinContext) -> MonkeyResult<Object> {
+        match args.as_slice() {
+            [Object::Integer(int)] => Ok(Object::Integer(Integer { value: int.value * int.value })),
+            _ => Err(String::from("argument to square function is not supported, Integer expected")),
+        }
+    }
+
+    #[test]
+    fn call_function_test() {
+        let input = "
+let add = fn(a, b) { a + b };
+let k = 10;
+let addK = fn(a) { add(a, k) };
+let fib = fn(n) { if (n < 2) { n } else { fib(n - 1) + fib(n - 2) } };";
+
+        let mut vm = Vm::new(compile_input(input));
+        assert!(vm.run().is_ok());
+
+        let int = |value| Object::Integer(Integer { value });
+
+        TestCaseResult::Integer(5).test(&vm.call_function("add", vec![int(2), int(3)]).unwrap());
+        TestCaseResult::Integer(42).test(&vm.call_function("addK", vec![int(32)]).unwrap());
+        TestCaseResult::Integer(55).test(&vm.call_function("fib", vec![int(10)]).unwrap());
+        TestCaseResult::Integer(-1).test(&vm.call_function("add", vec![int(2), int(-3)]).unwrap());
+
+        let expected_errors = vec![
+            ("missing", vec![], "couldn't find function missing"),
+            ("k", vec![], "k is not a function, but \"10\""),
+            ("add", vec![int(1)], "wrong number of arguments: want=2, got=1"),
+        ];
+
+        for (name, args, error) in expected_errors {
+            assert_eq!(vm.call_function(name, args).map_err(|err| err.to_string()), Err(String::from(error)));
+        }
+
+        let input = "let check = fn(n) { if (n < 0) { n / 0 } else { n } };";
+
+        let mut vm = Vm::new(compile_input(input));
+        assert!(vm.run().is_ok());
+
+        assert!(vm.call_function("check", vec![int(-1)]).is_err());
+        TestCaseResult::Integer(7).test(&vm.call_function("check", vec![int(7)]).unwrap());
+    }
 }